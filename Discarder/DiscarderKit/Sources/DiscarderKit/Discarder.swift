@@ -114,7 +114,10 @@ extension Card {
     fileprivate func makeCCard() -> CCard {
         CCard(
             rank: CRank(value: UInt8(self.rank.index)),
-            suit: CSuit(value: UInt8(self.suit.rawValue))
+            suit: CSuit(value: UInt8(self.suit.rawValue)),
+            enhancement: 0,
+            seal: 0,
+            edition: 0
         )
     }
 }
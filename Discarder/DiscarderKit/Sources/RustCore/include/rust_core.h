@@ -10,8 +10,75 @@
 #include <stdint.h>
 #include <stdlib.h>
 
+/**
+ * `rank` of a target made of cards of any rank
+ */
+#define OBJECTIVE_ANY_RANK 0
+
+/**
+ * `suit` of a target made of cards of any suit
+ */
+#define OBJECTIVE_ANY_SUIT 255
+
+/**
+ * Most cards that can be played at once
+ */
+#define MAX_PLAYED_CARDS 5
+
+typedef enum CBossBlind {
+  CBossBlind_None = 0,
+  CBossBlind_TheClub = 1,
+  CBossBlind_TheGoad = 2,
+  CBossBlind_TheWindow = 3,
+  CBossBlind_TheHead = 4,
+  CBossBlind_ThePlant = 5,
+  CBossBlind_ThePsychic = 6,
+  CBossBlind_TheEye = 7,
+  CBossBlind_TheMouth = 8,
+  CBossBlind_TheFlint = 9,
+} CBossBlind;
+
+/**
+ * What `strategy_advise` maximizes.
+ */
+typedef enum CGoal {
+  /**
+   * Chance of reaching `round.target`
+   */
+  CGoal_Clear = 0,
+  /**
+   * Mean score at the end of the round
+   */
+  CGoal_Score = 1,
+} CGoal;
+
+typedef enum CMode {
+  CMode_Auto = 0,
+  CMode_Exact = 1,
+  CMode_MonteCarlo = 2,
+} CMode;
+
+/**
+ * Generator for Monte Carlo draws. Streams differ between generators, so
+ * results are only reproducible for the same generator and seed.
+ */
+typedef enum CRng {
+  CRng_Pcg64 = 0,
+  CRng_Pcg64Mcg = 1,
+  CRng_Xoshiro256PlusPlus = 2,
+  CRng_Xoshiro128PlusPlus = 3,
+} CRng;
+
+typedef struct Deck Deck;
+
 typedef struct Discarder Discarder;
 
+/**
+ * Outcomes a draw should reach, e.g. "a Flush or a Straight". The
+ * objective is met when the drawn hand meets any of its targets.
+ */
+typedef struct Objective Objective;
+
 typedef struct CRank {
   uint8_t value;
 } CRank;
@@ -20,9 +87,15 @@ typedef struct CSuit {
   uint8_t value;
 } CSuit;
 
+/**
+ * Unknown `enhancement`, `seal` and `edition` values read as none.
+ */
 typedef struct CCard {
   struct CRank rank;
   struct CSuit suit;
+  uint8_t enhancement;
+  uint8_t seal;
+  uint8_t edition;
 } CCard;
 
 typedef struct CPokerHandsCount {
@@ -34,6 +107,166 @@ typedef void (*ProgressHandler)(void *context,
                                 uintptr_t iterations,
                                 double fraction);
 
+/**
+ * Draws by the ranks and suits of the cards that can take part in each
+ * hand type. `ranks[t][r]` holds the draws where the hand type of raw
+ * value `t` can be played with a card of rank `r + 2`, `suits[t][s]` those
+ * where it can be played with a card of suit `s`.
+ */
+typedef struct CHandBreakdown {
+  int64_t ranks[13][13];
+  int64_t suits[13][4];
+} CHandBreakdown;
+
+/**
+ * Draws by the full set of poker hands they contain. The set is a mask
+ * with bit `n` set for every poker hand of raw value `n`, so `counts[m]`
+ * holds the draws containing exactly the hands in `m`.
+ */
+typedef struct CHandSetHistogram {
+  int64_t counts[1024];
+} CHandSetHistogram;
+
+/**
+ * Why a string could not be used. Owned by the caller, release it with
+ * `discarder_error_free`.
+ */
+typedef struct CError {
+  /**
+   * Human readable description
+   */
+  char *message;
+  /**
+   * Offending card token, null if the problem is not a single token
+   */
+  char *token;
+  /**
+   * Byte offset of the problem in the input string
+   */
+  uintptr_t position;
+} CError;
+
+/**
+ * Outcome of `discarder_run_str`. Owned by the caller, release it with
+ * `discarder_run_result_free`.
+ */
+typedef struct CRunResult {
+  struct CPokerHandsCount counts;
+  uintptr_t iterations;
+  enum CMode mode;
+  /**
+   * Null on success, in which case the other fields are valid
+   */
+  struct CError *error;
+} CRunResult;
+
+typedef struct CObjectiveResult {
+  int64_t hits;
+  double probability;
+  /**
+   * 95% confidence interval, equal to the probability in exact mode
+   */
+  double low;
+  double high;
+  uintptr_t iterations;
+  enum CMode mode;
+} CObjectiveResult;
+
+typedef struct CScoreResult {
+  double mean;
+  double std_dev;
+  double max;
+  /**
+   * Probability of scoring at least the target
+   */
+  double hit_probability;
+  uintptr_t iterations;
+  enum CMode mode;
+} CScoreResult;
+
+/**
+ * Draws by how many cards of each rank and suit score in the best play.
+ * `ranks[r][n]` holds the draws scoring `n` cards of rank `r + 2`,
+ * `suits[s][n]` those scoring `n` cards of suit `s`.
+ */
+typedef struct CScoringCardHistogram {
+  int64_t ranks[13][6];
+  int64_t suits[4][6];
+} CScoringCardHistogram;
+
+/**
+ * Rules of the simulated round. The whole deck is shuffled, `hand_size`
+ * cards are drawn and `target` must be reached within `hands` plays.
+ */
+typedef struct CRound {
+  uintptr_t hand_size;
+  uintptr_t hands;
+  uintptr_t discards;
+  double target;
+  enum CBossBlind blind;
+  /**
+   * Hands already played, as for `discarder_set_boss_blind`
+   */
+  uint16_t played_hands;
+} CRound;
+
+/**
+ * A simulated mean with its 95% confidence interval.
+ */
+typedef struct CEstimate {
+  double mean;
+  double low;
+  double high;
+} CEstimate;
+
+/**
+ * How one policy did. Differences are paired over the same shuffles and
+ * taken against the first policy, so they are zero for the first one.
+ */
+typedef struct CPolicyReport {
+  struct CEstimate win_rate;
+  struct CEstimate mean_score;
+  struct CEstimate win_rate_difference;
+  struct CEstimate score_difference;
+} CPolicyReport;
+
+/**
+ * Best action found by `strategy_solve`.
+ */
+typedef struct CSolution {
+  /**
+   * Positions in the hand of the cards to play or discard
+   */
+  uintptr_t cards[5];
+  uintptr_t cards_size;
+  bool discard;
+  /**
+   * Chance of winning the round after the action
+   */
+  double probability;
+  enum CMode mode;
+} CSolution;
+
+/**
+ * An action ranked by `strategy_advise`.
+ */
+typedef struct CRankedAction {
+  /**
+   * Positions in the hand of the cards to play or discard
+   */
+  uintptr_t cards[5];
+  uintptr_t cards_size;
+  bool discard;
+  /**
+   * Search iterations that started with the action
+   */
+  uintptr_t visits;
+  /**
+   * Mean of the goal over those iterations
+   */
+  double value;
+} CRankedAction;
+
 struct Discarder *discarder_new(const struct CCard *deck,
                                 uintptr_t deck_size,
                                 uintptr_t hand_size,
@@ -41,6 +274,25 @@ struct Discarder *discarder_new(const struct CCard *deck,
 
 void discarder_free(struct Discarder *discarder);
 
+void discarder_set_mode(struct Discarder *discarder, enum CMode mode);
+
+/**
+ * Non-positive or non-finite `seconds` removes the time budget. Returns
+ * false and keeps the current budget if `seconds` is too large.
+ */
+bool discarder_set_time_budget(struct Discarder *discarder, double seconds);
+
+/**
+ * Non-positive or non-finite `std_error` removes the precision target.
+ */
+void discarder_set_precision(struct Discarder *discarder, double std_error);
+
+void discarder_set_rng(struct Discarder *discarder, enum CRng rng);
+
+void discarder_set_boss_blind(struct Discarder *discarder,
+                              enum CBossBlind blind,
+                              uint16_t played_hands);
+
 uintptr_t discarder_run(const struct Discarder *discarder,
                         const struct CCard *hand,
                         uintptr_t hand_size,
@@ -49,4 +301,298 @@ uintptr_t discarder_run(const struct Discarder *discarder,
                         ProgressHandler progress_handler,
                         void *context);
 
+/**
+ * Same as `discarder_run`, `out_mode` is optional and receives the mode
+ * actually used.
+ */
+uintptr_t discarder_run_with_mode(const struct Discarder *discarder,
+                                  const struct CCard *hand,
+                                  uintptr_t hand_size,
+                                  uintptr_t max_iterations,
+                                  struct CPokerHandsCount *out_counts,
+                                  ProgressHandler progress_handler,
+                                  void *context,
+                                  enum CMode *out_mode);
+
+/**
+ * Like `discarder_run`, but breaks the hand types of each draw down by
+ * rank and suit into `out_breakdown`. `out_counts` and `out_mode` are
+ * optional.
+ */
+uintptr_t discarder_run_breakdown(const struct Discarder *discarder,
+                                  const struct CCard *hand,
+                                  uintptr_t hand_size,
+                                  uintptr_t max_iterations,
+                                  struct CHandBreakdown *out_breakdown,
+                                  struct CPokerHandsCount *out_counts,
+                                  enum CMode *out_mode);
+
+/**
+ * Whether the hand type of raw value `hand_type` can be formed from the
+ * cards of `rank` and `suit` in `hand` alone, e.g. a flush of Hearts.
+ * Takes `OBJECTIVE_ANY_RANK` and `OBJECTIVE_ANY_SUIT` like
+ * `objective_add_target`, out of range values match nothing.
+ */
+bool hand_contains_made_of(const struct CCard *hand,
+                           uintptr_t hand_size,
+                           uint8_t hand_type,
+                           uint8_t rank,
+                           uint8_t suit);
+
+/**
+ * Whether the hand type of raw value `hand_type` can be played from `hand`
+ * with a card of `rank` and `suit` among the cards forming it, e.g. a
+ * straight containing an Ace.
+ */
+bool hand_contains_including(const struct CCard *hand,
+                             uintptr_t hand_size,
+                             uint8_t hand_type,
+                             uint8_t rank,
+                             uint8_t suit);
+
+struct Deck *deck_new(const struct CCard *cards, uintptr_t size);
+
+struct Deck *deck_new_standard(void);
+
+struct Deck *deck_new_abandoned(void);
+
+struct Deck *deck_new_checkered(void);
+
+struct Deck *deck_new_erratic(uint64_t seed);
+
+void deck_free(struct Deck *deck);
+
+/**
+ * Removes one copy of every card. Returns false and leaves the deck
+ * unchanged if some of them are not in the deck.
+ */
+bool deck_destroy_cards(struct Deck *deck, const struct CCard *cards, uintptr_t size);
+
+void deck_add_cards(struct Deck *deck, const struct CCard *cards, uintptr_t size);
+
+/**
+ * Whether every card of `hand`, duplicates included, is in the deck.
+ */
+bool deck_can_draw(const struct Deck *deck, const struct CCard *hand, uintptr_t hand_size);
+
+/**
+ * Removes the cards of `hand` that were drawn from the deck. Returns false
+ * and leaves the deck unchanged if some of them are not in the deck.
+ */
+bool deck_remove_hand(struct Deck *deck, const struct CCard *hand, uintptr_t hand_size);
+
+/**
+ * Number of copies of `card` in the deck.
+ */
+uintptr_t deck_card_count(const struct Deck *deck, struct CCard card);
+
+uintptr_t deck_size(const struct Deck *deck);
+
+/**
+ * Copies up to `capacity` cards into `out_cards`, returns the deck size.
+ */
+uintptr_t deck_get_cards(const struct Deck *deck, struct CCard *out_cards, uintptr_t capacity);
+
+/**
+ * Creates a discarder drawing from a copy of `deck`.
+ */
+struct Discarder *discarder_new_with_deck(const struct Deck *deck,
+                                          uintptr_t hand_size,
+                                          uint64_t seed);
+
+/**
+ * Like `discarder_run`, but records which poker hands each draw contains
+ * together in `out_sets`. `out_counts` and `out_mode` are optional.
+ */
+uintptr_t discarder_run_hand_sets(const struct Discarder *discarder,
+                                  const struct CCard *hand,
+                                  uintptr_t hand_size,
+                                  uintptr_t max_iterations,
+                                  struct CHandSetHistogram *out_sets,
+                                  struct CPokerHandsCount *out_counts,
+                                  enum CMode *out_mode);
+
+/**
+ * Draws containing every hand in `required` and none in `excluded`, both
+ * masks like the indices of `CHandSetHistogram`.
+ */
+int64_t hand_sets_count_matching(const struct CHandSetHistogram *sets,
+                                 uint16_t required,
+                                 uint16_t excluded);
+
+/**
+ * Draws containing at least one of the hands in `hands`.
+ */
+int64_t hand_sets_count_any(const struct CHandSetHistogram *sets, uint16_t hands);
+
+/**
+ * Draws containing all of the hands in `hands`.
+ */
+int64_t hand_sets_count_all(const struct CHandSetHistogram *sets, uint16_t hands);
+
+/**
+ * Writes the draws by the highest poker hand they contain to `out_best`.
+ */
+void hand_sets_best_hands(const struct CHandSetHistogram *sets, struct CPokerHandsCount *out_best);
+
+/**
+ * Runs a JSON analysis request and returns the JSON response, see
+ * `AnalysisRequest` for the schema. Errors are reported in the response.
+ * Release the returned string with `discarder_json_free`.
+ */
+char *discarder_run_json(const char *request);
+
+void discarder_json_free(char *response);
+
+/**
+ * Creates a discarder from a deck written in card notation, e.g.
+ * `"AS KH 10D QC:glass"`, or named after a preset: `standard`,
+ * `abandoned`, `checkered` or `erratic`. Returns null and stores an error
+ * in `out_error`, if given, when the deck cannot be parsed.
+ *
+ * The deck still holds the cards in hand: every run draws from it without
+ * the hand, and `discarder_run_str` rejects cards that are not in it.
+ */
+struct Discarder *discarder_new_from_str(const char *deck,
+                                         uintptr_t hand_size,
+                                         uint64_t seed,
+                                         struct CError **out_error);
+
+/**
+ * Runs the analysis for a hand written in card notation. Never returns
+ * null, check `error` on the result.
+ */
+struct CRunResult *discarder_run_str(const struct Discarder *discarder,
+                                     const char *hand,
+                                     uintptr_t max_iterations);
+
+void discarder_error_free(struct CError *error);
+
+void discarder_run_result_free(struct CRunResult *result);
+
+/**
+ * Creates an objective without targets, which no draw meets. Free it with
+ * `objective_free`.
+ */
+struct Objective *objective_new(void);
+
+void objective_free(struct Objective *objective);
+
+/**
+ * Adds a target to the objective: the hand type of raw value `hand`, made
+ * of cards of `rank` and `suit` only. Pass `OBJECTIVE_ANY_RANK` and
+ * `OBJECTIVE_ANY_SUIT` to leave them open. Returns false and leaves the
+ * objective untouched if a value is out of range.
+ */
+bool objective_add_target(struct Objective *objective, uint8_t hand, uint8_t rank, uint8_t suit);
+
+/**
+ * Probability of the hand drawn up to the hand size meeting `objective`.
+ */
+void discarder_run_objective(const struct Discarder *discarder,
+                             const struct CCard *hand,
+                             uintptr_t hand_size,
+                             const struct Objective *objective,
+                             uintptr_t max_iterations,
+                             struct CObjectiveResult *out_result);
+
+/**
+ * `jokers` holds `jokers_size` joker names, left to right. Returns false
+ * and leaves the outputs untouched if a name is not a known joker.
+ * `out_cards` is optional.
+ */
+bool discarder_run_scoring(const struct Discarder *discarder,
+                           const struct CCard *hand,
+                           uintptr_t hand_size,
+                           const char *const *jokers,
+                           uintptr_t jokers_size,
+                           double target,
+                           uintptr_t max_iterations,
+                           struct CScoreResult *out_result,
+                           struct CScoringCardHistogram *out_cards);
+
+/**
+ * Writes the best cards to play from `hand` into `out_cards`, which must
+ * hold five cards, and returns how many were written. `out_hand` receives
+ * the `HandType` played, secret hands included, and `out_score` its score. Returns 0 for an unknown
+ * joker or if `blind` allows no play.
+ */
+uintptr_t scoring_best_play(const struct CCard *hand,
+                            uintptr_t hand_size,
+                            const char *const *jokers,
+                            uintptr_t jokers_size,
+                            enum CBossBlind blind,
+                            uint16_t played_hands,
+                            struct CCard *out_cards,
+                            uint8_t *out_hand,
+                            double *out_score);
+
+/**
+ * Plays `rounds` shuffles of `deck` with each of the `policies_size`
+ * built-in policies named in `policies`, e.g. "Play Best", "Chase Flush"
+ * or "Keep Scoring", on `threads` threads or every core for 0. Writes one
+ * report per policy into `out_reports` and returns the rounds played, or 0
+ * if a policy or joker name is unknown.
+ */
+uintptr_t strategy_compare(const struct CRound *round,
+                           const struct CCard *deck,
+                           uintptr_t deck_size,
+                           const char *const *jokers,
+                           uintptr_t jokers_size,
+                           const char *const *policies,
+                           uintptr_t policies_size,
+                           uintptr_t rounds,
+                           uint64_t seed,
+                           enum CRng rng,
+                           uintptr_t threads,
+                           struct CPolicyReport *out_reports);
+
+/**
+ * Best action for `hand` with `deck` left to draw, `round.hands` hands and
+ * `round.discards` discards left and `score` already reached. Solves
+ * exactly within `budget` states, otherwise rolls out the best play and
+ * every discard `rollouts` times on `threads` threads, 0 for all cores.
+ * Returns false and leaves `out_solution` untouched if a joker name is
+ * unknown or nothing can be played.
+ */
+bool strategy_solve(const struct CRound *round,
+                    const struct CCard *hand,
+                    uintptr_t hand_size,
+                    const struct CCard *deck,
+                    uintptr_t deck_size,
+                    const char *const *jokers,
+                    uintptr_t jokers_size,
+                    double score,
+                    uintptr_t budget,
+                    uintptr_t rollouts,
+                    uint64_t seed,
+                    enum CRng rng,
+                    uintptr_t threads,
+                    struct CSolution *out_solution);
+
+/**
+ * Ranks the actions of `hand` by Monte Carlo tree search, with `deck` left
+ * to draw and `score` already reached. Searches on `threads` threads, 0
+ * for all cores, for `seconds` when positive and finite, otherwise for
+ * `iterations` iterations. Writes up to `max_actions` actions into
+ * `out_actions`, most visited first, and returns how many, or 0 if a joker
+ * name is unknown, `seconds` is too large or nothing can be played.
+ */
+uintptr_t strategy_advise(const struct CRound *round,
+                          const struct CCard *hand,
+                          uintptr_t hand_size,
+                          const struct CCard *deck,
+                          uintptr_t deck_size,
+                          const char *const *jokers,
+                          uintptr_t jokers_size,
+                          double score,
+                          enum CGoal goal,
+                          uintptr_t iterations,
+                          double seconds,
+                          uint64_t seed,
+                          enum CRng rng,
+                          uintptr_t threads,
+                          struct CRankedAction *out_actions,
+                          uintptr_t max_actions);
+
 #endif  /* RUST_CORE_H */
@@ -0,0 +1,119 @@
+"""Run with `maturin develop && pytest` from the RustCore directory."""
+
+import threading
+
+import pytest
+
+import discarder
+
+
+def test_card_parsing():
+    card = discarder.Card("AS:glass:red:foil")
+    assert card.rank == 14
+    assert card.suit == "S"
+    assert card.enhancement == "glass"
+    assert card.seal == "red"
+    assert card.edition == "foil"
+    assert str(card) == "AS:glass:red:foil"
+    assert discarder.Card("10H") == discarder.Card("TH")
+
+
+def test_parse_hand():
+    hand = discarder.parse_hand("AS KH 10D")
+    assert [str(card) for card in hand] == ["AS", "KH", "TD"]
+
+
+def test_parse_errors():
+    with pytest.raises(ValueError) as error:
+        discarder.parse_hand("AS KX")
+
+    message, token, position = error.value.args
+    assert token == "KX"
+    assert position == 3
+    assert "invalid suit" in message
+
+
+def test_deck():
+    assert len(discarder.Deck()) == 52
+    assert len(discarder.Deck("abandoned")) == 40
+    assert len(discarder.Deck("AS AS KH")) == 3
+
+    with pytest.raises(ValueError):
+        discarder.Deck("huge")
+
+
+def test_exact_run():
+    deck = discarder.Deck()
+    result = discarder.Discarder(deck, hand_size=8, seed=43).run(
+        "2S 3S 4S 5S 6S 7S 8S"
+    )
+
+    assert result.mode == "exact"
+    assert result.iterations == 45
+    assert result.counts["high_card"] == 45
+    assert result.counts["one_pair"] == 21
+    assert result.probability("straight_flush") == 1.0
+    assert result.confidence_interval("one_pair") == (21 / 45, 21 / 45)
+    assert result.counts.to_dict()["flush"] == 45
+
+
+def test_montecarlo_progress():
+    calls = []
+
+    def progress(iterations, fraction, counts):
+        calls.append((iterations, fraction, counts["high_card"]))
+
+    deck = discarder.Deck()
+    d = discarder.Discarder(deck, seed=43, mode="monte_carlo", threads=2)
+    result = d.run("AS", max_iterations=20_000, progress=progress)
+
+    assert result.mode == "monte_carlo"
+    assert result.iterations == 20_000
+    assert calls
+    assert calls[-1][0] == 20_000
+    assert calls[-1][1] == pytest.approx(1.0)
+
+    low, high = result.confidence_interval("flush")
+    assert low < result.probability("flush") < high
+
+
+def test_progress_exception_is_raised():
+    def progress(iterations, fraction, counts):
+        raise RuntimeError("stop")
+
+    d = discarder.Discarder(discarder.Deck(), mode="monte_carlo", threads=1)
+    with pytest.raises(RuntimeError, match="stop"):
+        d.run("AS", max_iterations=1000, progress=progress)
+
+
+def test_run_releases_gil():
+    ticks = []
+    done = threading.Event()
+
+    def ticker():
+        while not done.is_set():
+            ticks.append(1)
+            done.wait(0.001)
+
+    thread = threading.Thread(target=ticker)
+    thread.start()
+
+    d = discarder.Discarder(discarder.Deck(), mode="monte_carlo", threads=1)
+    d.run("", max_iterations=300_000)
+
+    done.set()
+    thread.join()
+    assert len(ticks) > 1
+
+
+def test_invalid_arguments():
+    with pytest.raises(ValueError):
+        discarder.Discarder(discarder.Deck(), mode="fast")
+    with pytest.raises(ValueError):
+        discarder.Discarder(discarder.Deck(), time_budget=1e30)
+    with pytest.raises(ValueError, match="not in the deck"):
+        discarder.Discarder(discarder.Deck()).run("AS AS")
+
+    result = discarder.Discarder(discarder.Deck()).run("AS KS QS JS TS 9S 8S 7S")
+    with pytest.raises(ValueError):
+        result.probability("royal")
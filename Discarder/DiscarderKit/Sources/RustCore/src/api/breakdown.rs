@@ -0,0 +1,188 @@
+use super::{
+    cards_from_raw, objective::target_from_raw, CCard, CMode, CPokerHandsCount,
+};
+use crate::discarder::Discarder;
+
+/// Draws by the ranks and suits of the cards that can take part in each
+/// hand type. `ranks[t][r]` holds the draws where the hand type of raw
+/// value `t` can be played with a card of rank `r + 2`, `suits[t][s]` those
+/// where it can be played with a card of suit `s`.
+#[repr(C)]
+pub struct CHandBreakdown {
+    pub ranks: [[i64; 13]; 13],
+    pub suits: [[i64; 4]; 13],
+}
+
+/// Like `discarder_run`, but breaks the hand types of each draw down by
+/// rank and suit into `out_breakdown`. `out_counts` and `out_mode` are
+/// optional.
+#[no_mangle]
+pub extern "C" fn discarder_run_breakdown(
+    discarder: *const Discarder,
+    hand: *const CCard,
+    hand_size: usize,
+    max_iterations: usize,
+    out_breakdown: *mut CHandBreakdown,
+    out_counts: *mut CPokerHandsCount,
+    out_mode: *mut CMode,
+) -> usize {
+    if discarder.is_null() {
+        panic!("discarder pointer must not be null");
+    }
+    if out_breakdown.is_null() {
+        panic!("out_breakdown pointer must not be null");
+    }
+
+    let discarder = unsafe { &*discarder }.clone().with_breakdown(true);
+    let rust_hand = cards_from_raw(hand, hand_size, "hand");
+
+    let result = discarder.run(&rust_hand, max_iterations, |_| {});
+    let breakdown = result.breakdown.unwrap_or_default();
+
+    unsafe {
+        let out = &mut *out_breakdown;
+        breakdown.to_arrays(&mut out.ranks, &mut out.suits);
+
+        if !out_counts.is_null() {
+            result.count.to_array(&mut (*out_counts).counts);
+        }
+        if !out_mode.is_null() {
+            *out_mode = result.mode.into();
+        }
+    }
+
+    result.iterations
+}
+
+/// Whether the hand type of raw value `hand_type` can be formed from the
+/// cards of `rank` and `suit` in `hand` alone, e.g. a flush of Hearts.
+/// Takes `OBJECTIVE_ANY_RANK` and `OBJECTIVE_ANY_SUIT` like
+/// `objective_add_target`, out of range values match nothing.
+#[no_mangle]
+pub extern "C" fn hand_contains_made_of(
+    hand: *const CCard,
+    hand_size: usize,
+    hand_type: u8,
+    rank: u8,
+    suit: u8,
+) -> bool {
+    let rust_hand = cards_from_raw(hand, hand_size, "hand");
+    target_from_raw(hand_type, rank, suit)
+        .is_some_and(|target| target.is_met_by(&rust_hand))
+}
+
+/// Whether the hand type of raw value `hand_type` can be played from `hand`
+/// with a card of `rank` and `suit` among the cards forming it, e.g. a
+/// straight containing an Ace.
+#[no_mangle]
+pub extern "C" fn hand_contains_including(
+    hand: *const CCard,
+    hand_size: usize,
+    hand_type: u8,
+    rank: u8,
+    suit: u8,
+) -> bool {
+    let rust_hand = cards_from_raw(hand, hand_size, "hand");
+    target_from_raw(hand_type, rank, suit)
+        .is_some_and(|target| target.is_met_including(&rust_hand))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        api::{
+            discarder_free, discarder_new_from_str, OBJECTIVE_ANY_RANK,
+            OBJECTIVE_ANY_SUIT,
+        },
+        deck::Card,
+        poker::HandType,
+    };
+    use std::{ffi::CString, ptr};
+
+    fn c_hand(hand: &str) -> Vec<CCard> {
+        Card::make_hand(hand)
+            .unwrap()
+            .iter()
+            .map(CCard::from)
+            .collect()
+    }
+
+    #[test]
+    fn test_run_breakdown() {
+        let deck = CString::new("standard").unwrap();
+        let discarder =
+            discarder_new_from_str(deck.as_ptr(), 6, 43, ptr::null_mut());
+        let hand = c_hand("2S 3S 4S 5S 9H");
+
+        let mut breakdown = CHandBreakdown {
+            ranks: [[0; 13]; 13],
+            suits: [[0; 4]; 13],
+        };
+        let mut counts = CPokerHandsCount { counts: [0; 10] };
+        let iterations = discarder_run_breakdown(
+            discarder,
+            hand.as_ptr(),
+            hand.len(),
+            10000,
+            &mut breakdown,
+            &mut counts,
+            ptr::null_mut(),
+        );
+        assert_eq!(iterations, 47);
+
+        let straight = HandType::Straight as usize;
+        assert_eq!(breakdown.ranks[straight][0], counts.counts[straight]);
+        assert_eq!(breakdown.ranks[straight][12], 4);
+        assert_eq!(breakdown.suits[HandType::Flush as usize][3], 9);
+
+        discarder_free(discarder);
+    }
+
+    #[test]
+    fn test_hand_queries() {
+        let hand = c_hand("AS 2H 3C 4D 5S KH KD");
+        let query =
+            |f: extern "C" fn(*const CCard, usize, u8, u8, u8) -> bool,
+             hand_type: HandType,
+             rank: u8,
+             suit: u8| {
+                f(hand.as_ptr(), hand.len(), hand_type as u8, rank, suit)
+            };
+
+        // A pair of Kings, but no pair of Aces
+        assert!(query(
+            hand_contains_made_of,
+            HandType::OnePair,
+            13,
+            OBJECTIVE_ANY_SUIT
+        ));
+        assert!(!query(
+            hand_contains_made_of,
+            HandType::OnePair,
+            14,
+            OBJECTIVE_ANY_SUIT
+        ));
+
+        // A straight containing an Ace, but no straight of Spades
+        assert!(query(
+            hand_contains_including,
+            HandType::Straight,
+            14,
+            OBJECTIVE_ANY_SUIT
+        ));
+        assert!(!query(
+            hand_contains_including,
+            HandType::Straight,
+            13,
+            OBJECTIVE_ANY_SUIT
+        ));
+        assert!(!query(
+            hand_contains_made_of,
+            HandType::Straight,
+            OBJECTIVE_ANY_RANK,
+            3
+        ));
+        assert!(!query(hand_contains_made_of, HandType::Straight, 1, 3));
+    }
+}
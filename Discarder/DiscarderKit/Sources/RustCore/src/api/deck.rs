@@ -0,0 +1,179 @@
+use super::{cards_from_raw, CCard};
+use crate::{
+    deck::{Card, CardCounts, Deck, DeckBuilder},
+    discarder::Discarder,
+};
+
+fn into_raw(deck: Deck) -> *mut Deck {
+    Box::into_raw(Box::new(deck))
+}
+
+#[no_mangle]
+pub extern "C" fn deck_new(cards: *const CCard, size: usize) -> *mut Deck {
+    into_raw(Deck::new(&cards_from_raw(cards, size, "cards")))
+}
+
+#[no_mangle]
+pub extern "C" fn deck_new_standard() -> *mut Deck {
+    into_raw(Deck::make_standard())
+}
+
+#[no_mangle]
+pub extern "C" fn deck_new_abandoned() -> *mut Deck {
+    into_raw(Deck::make_abandoned())
+}
+
+#[no_mangle]
+pub extern "C" fn deck_new_checkered() -> *mut Deck {
+    into_raw(Deck::make_checkered())
+}
+
+#[no_mangle]
+pub extern "C" fn deck_new_erratic(seed: u64) -> *mut Deck {
+    into_raw(Deck::make_erratic(seed))
+}
+
+#[no_mangle]
+pub extern "C" fn deck_free(deck: *mut Deck) {
+    if !deck.is_null() {
+        unsafe {
+            let _ = Box::from_raw(deck);
+        }
+    }
+}
+
+/// Removes one copy of every card. Returns false and leaves the deck
+/// unchanged if some of them are not in the deck.
+#[no_mangle]
+pub extern "C" fn deck_destroy_cards(
+    deck: *mut Deck,
+    cards: *const CCard,
+    size: usize,
+) -> bool {
+    if deck.is_null() {
+        panic!("deck pointer must not be null");
+    }
+
+    let deck = unsafe { &mut *deck };
+    let cards = cards_from_raw(cards, size, "cards");
+
+    match DeckBuilder::new(deck.clone()).destroy(&cards).build() {
+        Ok(result) => {
+            *deck = result;
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn deck_add_cards(
+    deck: *mut Deck,
+    cards: *const CCard,
+    size: usize,
+) {
+    if deck.is_null() {
+        panic!("deck pointer must not be null");
+    }
+
+    let deck = unsafe { &mut *deck };
+    let cards = cards_from_raw(cards, size, "cards");
+
+    *deck = DeckBuilder::new(deck.clone())
+        .add(&cards)
+        .build()
+        .expect("adding cards always succeeds");
+}
+
+/// Whether every card of `hand`, duplicates included, is in the deck.
+#[no_mangle]
+pub extern "C" fn deck_can_draw(
+    deck: *const Deck,
+    hand: *const CCard,
+    hand_size: usize,
+) -> bool {
+    if deck.is_null() {
+        panic!("deck pointer must not be null");
+    }
+
+    let deck = unsafe { &*deck };
+    deck.can_draw(&cards_from_raw(hand, hand_size, "hand"))
+}
+
+/// Removes the cards of `hand` that were drawn from the deck. Returns false
+/// and leaves the deck unchanged if some of them are not in the deck.
+#[no_mangle]
+pub extern "C" fn deck_remove_hand(
+    deck: *mut Deck,
+    hand: *const CCard,
+    hand_size: usize,
+) -> bool {
+    if deck.is_null() {
+        panic!("deck pointer must not be null");
+    }
+
+    let deck = unsafe { &mut *deck };
+    deck.remove_hand(&cards_from_raw(hand, hand_size, "hand"))
+        .is_ok()
+}
+
+/// Number of copies of `card` in the deck.
+#[no_mangle]
+pub extern "C" fn deck_card_count(deck: *const Deck, card: CCard) -> usize {
+    if deck.is_null() {
+        panic!("deck pointer must not be null");
+    }
+
+    let deck = unsafe { &*deck };
+    CardCounts::from(deck).count(&Card::from(&card))
+}
+
+#[no_mangle]
+pub extern "C" fn deck_size(deck: *const Deck) -> usize {
+    if deck.is_null() {
+        panic!("deck pointer must not be null");
+    }
+
+    unsafe { (*deck).cards.len() }
+}
+
+/// Copies up to `capacity` cards into `out_cards`, returns the deck size.
+#[no_mangle]
+pub extern "C" fn deck_get_cards(
+    deck: *const Deck,
+    out_cards: *mut CCard,
+    capacity: usize,
+) -> usize {
+    if deck.is_null() {
+        panic!("deck pointer must not be null");
+    }
+    if capacity > 0 && out_cards.is_null() {
+        panic!("out_cards pointer must not be null when capacity > 0");
+    }
+
+    let deck = unsafe { &*deck };
+    let count = capacity.min(deck.cards.len());
+
+    for (i, card) in deck.cards[..count].iter().enumerate() {
+        unsafe {
+            *out_cards.add(i) = card.into();
+        }
+    }
+
+    deck.cards.len()
+}
+
+/// Creates a discarder drawing from a copy of `deck`.
+#[no_mangle]
+pub extern "C" fn discarder_new_with_deck(
+    deck: *const Deck,
+    hand_size: usize,
+    seed: u64,
+) -> *mut Discarder {
+    if deck.is_null() {
+        panic!("deck pointer must not be null");
+    }
+
+    let deck = unsafe { &*deck };
+    Box::into_raw(Box::new(Discarder::new(deck.clone(), hand_size, seed)))
+}
@@ -0,0 +1,150 @@
+use super::{cards_from_raw, CCard, CMode, CPokerHandsCount};
+use crate::{discarder::Discarder, poker::HandSetHistogram};
+
+/// Draws by the full set of poker hands they contain. The set is a mask
+/// with bit `n` set for every poker hand of raw value `n`, so `counts[m]`
+/// holds the draws containing exactly the hands in `m`.
+#[repr(C)]
+pub struct CHandSetHistogram {
+    pub counts: [i64; 1024],
+}
+
+fn histogram_from_raw(sets: *const CHandSetHistogram) -> HandSetHistogram {
+    if sets.is_null() {
+        panic!("sets pointer must not be null");
+    }
+
+    HandSetHistogram::from_array(unsafe { &(*sets).counts })
+}
+
+/// Like `discarder_run`, but records which poker hands each draw contains
+/// together in `out_sets`. `out_counts` and `out_mode` are optional.
+#[no_mangle]
+pub extern "C" fn discarder_run_hand_sets(
+    discarder: *const Discarder,
+    hand: *const CCard,
+    hand_size: usize,
+    max_iterations: usize,
+    out_sets: *mut CHandSetHistogram,
+    out_counts: *mut CPokerHandsCount,
+    out_mode: *mut CMode,
+) -> usize {
+    if discarder.is_null() {
+        panic!("discarder pointer must not be null");
+    }
+    if out_sets.is_null() {
+        panic!("out_sets pointer must not be null");
+    }
+
+    let discarder = unsafe { &*discarder }.clone().with_hand_sets(true);
+    let rust_hand = cards_from_raw(hand, hand_size, "hand");
+
+    let result = discarder.run(&rust_hand, max_iterations, |_| {});
+    let hand_sets = result.hand_sets.unwrap_or_default();
+
+    unsafe {
+        hand_sets.to_array(&mut (*out_sets).counts);
+
+        if !out_counts.is_null() {
+            result.count.to_array(&mut (*out_counts).counts);
+        }
+        if !out_mode.is_null() {
+            *out_mode = result.mode.into();
+        }
+    }
+
+    result.iterations
+}
+
+/// Draws containing every hand in `required` and none in `excluded`, both
+/// masks like the indices of `CHandSetHistogram`.
+#[no_mangle]
+pub extern "C" fn hand_sets_count_matching(
+    sets: *const CHandSetHistogram,
+    required: u16,
+    excluded: u16,
+) -> i64 {
+    histogram_from_raw(sets).count_matching(required, excluded)
+}
+
+/// Draws containing at least one of the hands in `hands`.
+#[no_mangle]
+pub extern "C" fn hand_sets_count_any(
+    sets: *const CHandSetHistogram,
+    hands: u16,
+) -> i64 {
+    histogram_from_raw(sets).count_any(hands)
+}
+
+/// Draws containing all of the hands in `hands`.
+#[no_mangle]
+pub extern "C" fn hand_sets_count_all(
+    sets: *const CHandSetHistogram,
+    hands: u16,
+) -> i64 {
+    histogram_from_raw(sets).count_all(hands)
+}
+
+/// Writes the draws by the highest poker hand they contain to `out_best`.
+#[no_mangle]
+pub extern "C" fn hand_sets_best_hands(
+    sets: *const CHandSetHistogram,
+    out_best: *mut CPokerHandsCount,
+) {
+    if out_best.is_null() {
+        panic!("out_best pointer must not be null");
+    }
+
+    let best = histogram_from_raw(sets).best_hands();
+    best.to_array(unsafe { &mut (*out_best).counts });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        api::{discarder_free, discarder_new_from_str},
+        deck::Card,
+        poker::PokerHand,
+    };
+    use std::{ffi::CString, ptr};
+
+    #[test]
+    fn test_run_hand_sets() {
+        let deck = CString::new("standard").unwrap();
+        let discarder =
+            discarder_new_from_str(deck.as_ptr(), 8, 43, ptr::null_mut());
+        let hand: Vec<CCard> = Card::make_hand("2S 3S 4S 5S 6S 7S 8S")
+            .unwrap()
+            .iter()
+            .map(CCard::from)
+            .collect();
+
+        let mut sets = CHandSetHistogram { counts: [0; 1024] };
+        let mut counts = CPokerHandsCount { counts: [0; 10] };
+        let iterations = discarder_run_hand_sets(
+            discarder,
+            hand.as_ptr(),
+            hand.len(),
+            10000,
+            &mut sets,
+            &mut counts,
+            ptr::null_mut(),
+        );
+        assert_eq!(iterations, 45);
+        assert_eq!(sets.counts.iter().sum::<i64>(), 45);
+
+        let flush = PokerHand::Flush.bit();
+        let pair = PokerHand::OnePair.bit();
+        assert_eq!(hand_sets_count_all(&sets, flush), counts.counts[5]);
+        assert_eq!(hand_sets_count_any(&sets, flush | pair), 45);
+        assert_eq!(hand_sets_count_all(&sets, flush | pair), 21);
+        assert_eq!(hand_sets_count_matching(&sets, flush, pair), 24);
+
+        let mut best = CPokerHandsCount { counts: [0; 10] };
+        hand_sets_best_hands(&sets, &mut best);
+        assert_eq!(best.counts, [0, 0, 0, 0, 0, 0, 0, 0, 45, 0]);
+
+        discarder_free(discarder);
+    }
+}
@@ -0,0 +1,49 @@
+use std::{
+    ffi::{CStr, CString},
+    os::raw::c_char,
+};
+
+use crate::discarder::run_json_bytes;
+
+/// Runs a JSON analysis request and returns the JSON response, see
+/// `AnalysisRequest` for the schema. Errors are reported in the response.
+/// Release the returned string with `discarder_json_free`.
+#[no_mangle]
+pub extern "C" fn discarder_run_json(request: *const c_char) -> *mut c_char {
+    if request.is_null() {
+        panic!("request pointer must not be null");
+    }
+
+    let request = unsafe { CStr::from_ptr(request) };
+    let response = run_json_bytes(request.to_bytes());
+
+    CString::new(response)
+        .expect("JSON responses never contain nul bytes")
+        .into_raw()
+}
+
+#[no_mangle]
+pub extern "C" fn discarder_json_free(response: *mut c_char) {
+    if !response.is_null() {
+        unsafe {
+            let _ = CString::from_raw(response);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_utf8() {
+        let request = CString::new(b"{\"hand\": \"A\xff\"}".to_vec()).unwrap();
+        let response = discarder_run_json(request.as_ptr());
+
+        let json = unsafe { CStr::from_ptr(response) }.to_str().unwrap();
+        let json: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(json["error"]["position"], 11);
+
+        discarder_json_free(response);
+    }
+}
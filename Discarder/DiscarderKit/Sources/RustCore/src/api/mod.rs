@@ -1,8 +1,29 @@
+mod breakdown;
+mod deck;
+mod hand_sets;
+mod json;
+mod notation;
+mod objective;
+mod scoring;
+mod strategy;
+
+pub use breakdown::*;
+pub use deck::*;
+pub use hand_sets::*;
+pub use json::*;
+pub use notation::*;
+pub use objective::*;
+pub use scoring::*;
+pub use strategy::*;
+
 use crate::{
-    deck::{Card, Deck, Rank, Suit},
-    discarder::Discarder,
+    deck::{Card, Deck, Edition, Enhancement, Rank, Seal, Suit},
+    discarder::{Discarder, Mode},
+    montecarlo::RngKind,
+    poker::{BossBlind, PokerHand},
 };
-use std::ptr;
+use std::time::Duration;
+use strum::IntoEnumIterator;
 
 #[repr(C)]
 pub struct CRank {
@@ -14,10 +35,61 @@ pub struct CSuit {
     pub value: u8,
 }
 
+/// Unknown `enhancement`, `seal` and `edition` values read as none.
 #[repr(C)]
 pub struct CCard {
     pub rank: CRank,
     pub suit: CSuit,
+    pub enhancement: u8,
+    pub seal: u8,
+    pub edition: u8,
+}
+
+impl From<&CCard> for Card {
+    fn from(card: &CCard) -> Self {
+        Card::new(
+            Rank {
+                value: card.rank.value,
+            },
+            Suit {
+                value: card.suit.value,
+            },
+        )
+        .with_enhancement(
+            Enhancement::from_raw(card.enhancement).unwrap_or_default(),
+        )
+        .with_seal(Seal::from_raw(card.seal).unwrap_or_default())
+        .with_edition(Edition::from_raw(card.edition).unwrap_or_default())
+    }
+}
+
+impl From<&Card> for CCard {
+    fn from(card: &Card) -> Self {
+        CCard {
+            rank: CRank {
+                value: card.rank.value,
+            },
+            suit: CSuit {
+                value: card.suit.value,
+            },
+            enhancement: card.enhancement as u8,
+            seal: card.seal as u8,
+            edition: card.edition as u8,
+        }
+    }
+}
+
+fn cards_from_raw(cards: *const CCard, size: usize, name: &str) -> Vec<Card> {
+    if size == 0 {
+        return Vec::new();
+    }
+
+    if cards.is_null() {
+        panic!("{} pointer must not be null when {}_size > 0", name, name);
+    }
+
+    let cards = unsafe { std::slice::from_raw_parts(cards, size) };
+    cards.iter().map(Card::from).collect()
 }
 
 #[repr(C)]
@@ -25,6 +97,95 @@ pub struct CPokerHandsCount {
     pub counts: [i64; 10],
 }
 
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum CMode {
+    Auto = 0,
+    Exact = 1,
+    MonteCarlo = 2,
+}
+
+impl From<CMode> for Mode {
+    fn from(mode: CMode) -> Self {
+        match mode {
+            CMode::Auto => Mode::Auto,
+            CMode::Exact => Mode::Exact,
+            CMode::MonteCarlo => Mode::MonteCarlo,
+        }
+    }
+}
+
+impl From<Mode> for CMode {
+    fn from(mode: Mode) -> Self {
+        match mode {
+            Mode::Auto => CMode::Auto,
+            Mode::Exact => CMode::Exact,
+            Mode::MonteCarlo => CMode::MonteCarlo,
+        }
+    }
+}
+
+/// Generator for Monte Carlo draws. Streams differ between generators, so
+/// results are only reproducible for the same generator and seed.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum CRng {
+    Pcg64 = 0,
+    Pcg64Mcg = 1,
+    Xoshiro256PlusPlus = 2,
+    Xoshiro128PlusPlus = 3,
+}
+
+impl From<CRng> for RngKind {
+    fn from(rng: CRng) -> Self {
+        match rng {
+            CRng::Pcg64 => RngKind::Pcg64,
+            CRng::Pcg64Mcg => RngKind::Pcg64Mcg,
+            CRng::Xoshiro256PlusPlus => RngKind::Xoshiro256PlusPlus,
+            CRng::Xoshiro128PlusPlus => RngKind::Xoshiro128PlusPlus,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum CBossBlind {
+    None = 0,
+    TheClub = 1,
+    TheGoad = 2,
+    TheWindow = 3,
+    TheHead = 4,
+    ThePlant = 5,
+    ThePsychic = 6,
+    TheEye = 7,
+    TheMouth = 8,
+    TheFlint = 9,
+}
+
+/// `played_hands` has bit `n` set for every poker hand of raw value `n`
+/// played this round, and is only read by The Eye and The Mouth.
+fn boss_blind_from_raw(blind: CBossBlind, played_hands: u16) -> BossBlind {
+    let mut played = PokerHand::iter()
+        .filter(|hand| played_hands & (1 << hand.raw_value()) != 0);
+
+    match blind {
+        CBossBlind::None => BossBlind::None,
+        CBossBlind::TheClub => BossBlind::TheClub,
+        CBossBlind::TheGoad => BossBlind::TheGoad,
+        CBossBlind::TheWindow => BossBlind::TheWindow,
+        CBossBlind::TheHead => BossBlind::TheHead,
+        CBossBlind::ThePlant => BossBlind::ThePlant,
+        CBossBlind::ThePsychic => BossBlind::ThePsychic,
+        CBossBlind::TheEye => BossBlind::TheEye {
+            played: played.collect(),
+        },
+        CBossBlind::TheMouth => BossBlind::TheMouth {
+            hand: played.next(),
+        },
+        CBossBlind::TheFlint => BossBlind::TheFlint,
+    }
+}
+
 pub type ProgressHandler = extern "C" fn(
     context: *mut std::ffi::c_void,
     counts: *const CPokerHandsCount,
@@ -39,29 +200,7 @@ pub extern "C" fn discarder_new(
     hand_size: usize,
     seed: u64,
 ) -> *mut Discarder {
-    if deck_size > 0 && deck.is_null() {
-        panic!("deck pointer must not be null when deck_size > 0");
-    }
-
-    let deck_cards = if deck_size > 0 {
-        unsafe { std::slice::from_raw_parts(deck, deck_size) }
-    } else {
-        &[]
-    };
-
-    let rust_deck: Vec<Card> = deck_cards
-        .iter()
-        .map(|c| {
-            Card::new(
-                Rank {
-                    value: c.rank.value,
-                },
-                Suit {
-                    value: c.suit.value,
-                },
-            )
-        })
-        .collect();
+    let rust_deck = cards_from_raw(deck, deck_size, "deck");
 
     let discarder = Discarder::new(Deck::new(&rust_deck), hand_size, seed);
     Box::into_raw(Box::new(discarder))
@@ -76,6 +215,81 @@ pub extern "C" fn discarder_free(discarder: *mut Discarder) {
     }
 }
 
+#[no_mangle]
+pub extern "C" fn discarder_set_mode(discarder: *mut Discarder, mode: CMode) {
+    if discarder.is_null() {
+        panic!("discarder pointer must not be null");
+    }
+
+    let discarder = unsafe { &mut *discarder };
+    discarder.set_mode(mode.into());
+}
+
+/// Non-positive or non-finite `seconds` removes the time budget. Returns
+/// false and keeps the current budget if `seconds` is too large.
+#[no_mangle]
+pub extern "C" fn discarder_set_time_budget(
+    discarder: *mut Discarder,
+    seconds: f64,
+) -> bool {
+    if discarder.is_null() {
+        panic!("discarder pointer must not be null");
+    }
+
+    let discarder = unsafe { &mut *discarder };
+    let time_budget = if seconds.is_finite() && seconds > 0.0 {
+        match Duration::try_from_secs_f64(seconds) {
+            Ok(budget) => Some(budget),
+            Err(_) => return false,
+        }
+    } else {
+        None
+    };
+    discarder.set_time_budget(time_budget);
+
+    true
+}
+
+/// Non-positive or non-finite `std_error` removes the precision target.
+#[no_mangle]
+pub extern "C" fn discarder_set_precision(
+    discarder: *mut Discarder,
+    std_error: f64,
+) {
+    if discarder.is_null() {
+        panic!("discarder pointer must not be null");
+    }
+
+    let discarder = unsafe { &mut *discarder };
+    let precision =
+        (std_error.is_finite() && std_error > 0.0).then_some(std_error);
+    discarder.set_precision(precision);
+}
+
+#[no_mangle]
+pub extern "C" fn discarder_set_rng(discarder: *mut Discarder, rng: CRng) {
+    if discarder.is_null() {
+        panic!("discarder pointer must not be null");
+    }
+
+    let discarder = unsafe { &mut *discarder };
+    discarder.set_rng(rng.into());
+}
+
+#[no_mangle]
+pub extern "C" fn discarder_set_boss_blind(
+    discarder: *mut Discarder,
+    blind: CBossBlind,
+    played_hands: u16,
+) {
+    if discarder.is_null() {
+        panic!("discarder pointer must not be null");
+    }
+
+    let discarder = unsafe { &mut *discarder };
+    discarder.set_boss_blind(boss_blind_from_raw(blind, played_hands));
+}
+
 #[no_mangle]
 pub extern "C" fn discarder_run(
     discarder: *const Discarder,
@@ -85,37 +299,41 @@ pub extern "C" fn discarder_run(
     out_counts: *mut CPokerHandsCount,
     progress_handler: ProgressHandler,
     context: *mut std::ffi::c_void,
+) -> usize {
+    discarder_run_with_mode(
+        discarder,
+        hand,
+        hand_size,
+        max_iterations,
+        out_counts,
+        progress_handler,
+        context,
+        std::ptr::null_mut(),
+    )
+}
+
+/// Same as `discarder_run`, `out_mode` is optional and receives the mode
+/// actually used.
+#[no_mangle]
+pub extern "C" fn discarder_run_with_mode(
+    discarder: *const Discarder,
+    hand: *const CCard,
+    hand_size: usize,
+    max_iterations: usize,
+    out_counts: *mut CPokerHandsCount,
+    progress_handler: ProgressHandler,
+    context: *mut std::ffi::c_void,
+    out_mode: *mut CMode,
 ) -> usize {
     if discarder.is_null() {
         panic!("discarder pointer must not be null");
     }
-    if hand_size > 0 && hand.is_null() {
-        panic!("hand pointer must not be null when hand_size > 0");
-    }
     if out_counts.is_null() {
         panic!("out_counts pointer must not be null");
     }
 
     let discarder = unsafe { &*discarder };
-    let hand = if hand_size > 0 {
-        unsafe { std::slice::from_raw_parts(hand, hand_size) }
-    } else {
-        &[]
-    };
-
-    let rust_hand: Vec<Card> = hand
-        .iter()
-        .map(|c| {
-            Card::new(
-                Rank {
-                    value: c.rank.value,
-                },
-                Suit {
-                    value: c.suit.value,
-                },
-            )
-        })
-        .collect();
+    let rust_hand = cards_from_raw(hand, hand_size, "hand");
 
     let result = discarder.run(&rust_hand, max_iterations, |progress| {
         let mut c_counts = CPokerHandsCount { counts: [0; 10] };
@@ -130,7 +348,84 @@ pub extern "C" fn discarder_run(
 
     unsafe {
         result.count.to_array(&mut (*out_counts).counts);
+
+        if !out_mode.is_null() {
+            *out_mode = result.mode.into();
+        }
     }
 
     result.iterations
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_time_budget() {
+        let deck: Vec<CCard> = Deck::make_standard()
+            .cards
+            .iter()
+            .map(CCard::from)
+            .collect();
+        let discarder = discarder_new(deck.as_ptr(), deck.len(), 8, 43);
+
+        assert!(discarder_set_time_budget(discarder, 0.5));
+        assert!(discarder_set_time_budget(discarder, -1.0));
+        assert!(discarder_set_time_budget(discarder, f64::INFINITY));
+        assert!(!discarder_set_time_budget(discarder, 1e30));
+
+        discarder_free(discarder);
+    }
+
+    #[test]
+    fn test_run_with_mode() {
+        extern "C" fn ignore(
+            _: *mut std::ffi::c_void,
+            _: *const CPokerHandsCount,
+            _: usize,
+            _: f64,
+        ) {
+        }
+
+        let held = Card::make_hand("AS KS QS JS").unwrap();
+        let deck: Vec<CCard> = Deck::make_standard()
+            .cards
+            .iter()
+            .filter(|card| !held.contains(card))
+            .map(CCard::from)
+            .collect();
+        let hand: Vec<CCard> = held.iter().map(CCard::from).collect();
+        let discarder = discarder_new(deck.as_ptr(), deck.len(), 5, 43);
+        let mut counts = CPokerHandsCount { counts: [0; 10] };
+        let mut mode = CMode::Auto;
+
+        let iterations = discarder_run_with_mode(
+            discarder,
+            hand.as_ptr(),
+            hand.len(),
+            1000,
+            &mut counts,
+            ignore,
+            std::ptr::null_mut(),
+            &mut mode,
+        );
+        assert!(matches!(mode, CMode::Exact));
+        assert_eq!(iterations, 48);
+
+        let mut plain = CPokerHandsCount { counts: [0; 10] };
+        let iterations = discarder_run(
+            discarder,
+            hand.as_ptr(),
+            hand.len(),
+            1000,
+            &mut plain,
+            ignore,
+            std::ptr::null_mut(),
+        );
+        assert_eq!(iterations, 48);
+        assert_eq!(plain.counts, counts.counts);
+
+        discarder_free(discarder);
+    }
+}
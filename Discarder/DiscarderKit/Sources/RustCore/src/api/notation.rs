@@ -0,0 +1,255 @@
+use std::{
+    ffi::{CStr, CString},
+    os::raw::c_char,
+    ptr,
+};
+
+use super::{CMode, CPokerHandsCount};
+use crate::{
+    deck::{Deck, ParseCardError},
+    discarder::Discarder,
+};
+
+/// Why a string could not be used. Owned by the caller, release it with
+/// `discarder_error_free`.
+#[repr(C)]
+pub struct CError {
+    /// Human readable description
+    pub message: *mut c_char,
+    /// Offending card token, null if the problem is not a single token
+    pub token: *mut c_char,
+    /// Byte offset of the problem in the input string
+    pub position: usize,
+}
+
+/// Outcome of `discarder_run_str`. Owned by the caller, release it with
+/// `discarder_run_result_free`.
+#[repr(C)]
+pub struct CRunResult {
+    pub counts: CPokerHandsCount,
+    pub iterations: usize,
+    pub mode: CMode,
+    /// Null on success, in which case the other fields are valid
+    pub error: *mut CError,
+}
+
+fn c_string(s: &str) -> *mut c_char {
+    // Messages are built from valid UTF-8 input and never contain nul bytes
+    CString::new(s).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+fn error_into_raw(
+    message: &str,
+    token: Option<&str>,
+    position: usize,
+) -> *mut CError {
+    Box::into_raw(Box::new(CError {
+        message: c_string(message),
+        token: token.map_or(ptr::null_mut(), c_string),
+        position,
+    }))
+}
+
+impl From<ParseCardError> for *mut CError {
+    fn from(error: ParseCardError) -> Self {
+        error_into_raw(&error.to_string(), Some(&error.token), error.position)
+    }
+}
+
+fn str_from_raw<'a>(
+    s: *const c_char,
+    name: &str,
+) -> Result<&'a str, *mut CError> {
+    if s.is_null() {
+        panic!("{} pointer must not be null", name);
+    }
+
+    unsafe { CStr::from_ptr(s) }.to_str().map_err(|error| {
+        let position = error.valid_up_to();
+        error_into_raw(
+            &format!("invalid UTF-8 in {} at {}", name, position),
+            None,
+            position,
+        )
+    })
+}
+
+/// Creates a discarder from a deck written in card notation, e.g.
+/// `"AS KH 10D QC:glass"`, or named after a preset: `standard`,
+/// `abandoned`, `checkered` or `erratic`. Returns null and stores an error
+/// in `out_error`, if given, when the deck cannot be parsed.
+///
+/// The deck still holds the cards in hand: every run draws from it without
+/// the hand, and `discarder_run_str` rejects cards that are not in it.
+#[no_mangle]
+pub extern "C" fn discarder_new_from_str(
+    deck: *const c_char,
+    hand_size: usize,
+    seed: u64,
+    out_error: *mut *mut CError,
+) -> *mut Discarder {
+    let deck = str_from_raw(deck, "deck")
+        .and_then(|deck| Deck::parse(deck, seed).map_err(Into::into));
+
+    match deck {
+        Ok(deck) => {
+            let discarder =
+                Discarder::new(deck, hand_size, seed).with_hand_in_deck(true);
+            Box::into_raw(Box::new(discarder))
+        }
+        Err(error) => {
+            if out_error.is_null() {
+                discarder_error_free(error);
+            } else {
+                unsafe { *out_error = error };
+            }
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Runs the analysis for a hand written in card notation. Never returns
+/// null, check `error` on the result.
+#[no_mangle]
+pub extern "C" fn discarder_run_str(
+    discarder: *const Discarder,
+    hand: *const c_char,
+    max_iterations: usize,
+) -> *mut CRunResult {
+    if discarder.is_null() {
+        panic!("discarder pointer must not be null");
+    }
+
+    let discarder = unsafe { &*discarder };
+    let mut result = CRunResult {
+        counts: CPokerHandsCount { counts: [0; 10] },
+        iterations: 0,
+        mode: CMode::Auto,
+        error: ptr::null_mut(),
+    };
+
+    let hand = str_from_raw(hand, "hand")
+        .and_then(|hand| discarder.parse_hand(hand).map_err(Into::into));
+
+    match hand {
+        Ok(hand) => {
+            let run = discarder.run(&hand, max_iterations, |_| {});
+            run.count.to_array(&mut result.counts.counts);
+            result.iterations = run.iterations;
+            result.mode = run.mode.into();
+        }
+        Err(error) => result.error = error,
+    }
+
+    Box::into_raw(Box::new(result))
+}
+
+#[no_mangle]
+pub extern "C" fn discarder_error_free(error: *mut CError) {
+    if error.is_null() {
+        return;
+    }
+
+    unsafe {
+        let error = Box::from_raw(error);
+        for s in [error.message, error.token] {
+            if !s.is_null() {
+                let _ = CString::from_raw(s);
+            }
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn discarder_run_result_free(result: *mut CRunResult) {
+    if result.is_null() {
+        return;
+    }
+
+    let result = unsafe { Box::from_raw(result) };
+    discarder_error_free(result.error);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::discarder_free;
+
+    fn read(s: *mut c_char) -> String {
+        unsafe { CStr::from_ptr(s) }.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_run_str() {
+        let deck = CString::new("standard").unwrap();
+        let discarder =
+            discarder_new_from_str(deck.as_ptr(), 8, 43, ptr::null_mut());
+        assert!(!discarder.is_null());
+
+        let hand = CString::new("2S 3S 4S 5S 6S 7S 8S").unwrap();
+        let result = discarder_run_str(discarder, hand.as_ptr(), 10000);
+
+        let result_ref = unsafe { &*result };
+        assert!(result_ref.error.is_null());
+        assert_eq!(result_ref.iterations, 45);
+        assert_eq!(result_ref.counts.counts[0], 45);
+
+        discarder_run_result_free(result);
+        discarder_free(discarder);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let deck = CString::new("AS KH 10D QX").unwrap();
+        let mut error = ptr::null_mut();
+        let discarder =
+            discarder_new_from_str(deck.as_ptr(), 8, 43, &mut error);
+        assert!(discarder.is_null());
+
+        let error_ref = unsafe { &*error };
+        assert_eq!(read(error_ref.token), "QX");
+        assert_eq!(error_ref.position, 10);
+        assert_eq!(read(error_ref.message), "invalid suit in `QX` at 10");
+        discarder_error_free(error);
+
+        let deck = CString::new("AS KH").unwrap();
+        let discarder =
+            discarder_new_from_str(deck.as_ptr(), 8, 43, ptr::null_mut());
+
+        let hand = CString::new("AS:shiny").unwrap();
+        let result = discarder_run_str(discarder, hand.as_ptr(), 10000);
+        let error_ref = unsafe { &*(*result).error };
+        assert_eq!(read(error_ref.token), "AS:shiny");
+        assert_eq!(error_ref.position, 0);
+        discarder_run_result_free(result);
+
+        let hand = CString::new("AS KH AS").unwrap();
+        let result = discarder_run_str(discarder, hand.as_ptr(), 10000);
+        let error_ref = unsafe { &*(*result).error };
+        assert_eq!(read(error_ref.token), "AS");
+        assert_eq!(error_ref.position, 6);
+        assert_eq!(read(error_ref.message), "not in the deck in `AS` at 6");
+
+        discarder_run_result_free(result);
+        discarder_free(discarder);
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        let hand = CString::new(vec![b'A', b'S', b' ', 0xff]).unwrap();
+        let discarder = discarder_new_from_str(
+            CString::new("standard").unwrap().as_ptr(),
+            8,
+            43,
+            ptr::null_mut(),
+        );
+
+        let result = discarder_run_str(discarder, hand.as_ptr(), 10000);
+        let error_ref = unsafe { &*(*result).error };
+        assert!(error_ref.token.is_null());
+        assert_eq!(error_ref.position, 3);
+
+        discarder_run_result_free(result);
+        discarder_free(discarder);
+    }
+}
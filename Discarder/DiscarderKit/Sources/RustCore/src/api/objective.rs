@@ -0,0 +1,187 @@
+use super::{cards_from_raw, CCard, CMode};
+use crate::{
+    deck::{Rank, Suit},
+    discarder::{Discarder, Objective, CONFIDENCE_Z},
+    poker::{HandTarget, HandType},
+};
+
+/// `rank` of a target made of cards of any rank
+pub const OBJECTIVE_ANY_RANK: u8 = 0;
+
+/// `suit` of a target made of cards of any suit
+pub const OBJECTIVE_ANY_SUIT: u8 = 255;
+
+#[repr(C)]
+pub struct CObjectiveResult {
+    pub hits: i64,
+    pub probability: f64,
+    /// 95% confidence interval, equal to the probability in exact mode
+    pub low: f64,
+    pub high: f64,
+    pub iterations: usize,
+    pub mode: CMode,
+}
+
+/// Target of the hand type of raw value `hand`, or `None` if a value is out
+/// of range
+pub(super) fn target_from_raw(
+    hand: u8,
+    rank: u8,
+    suit: u8,
+) -> Option<HandTarget> {
+    let mut target = HandTarget::new(HandType::from_raw(hand)?);
+
+    match rank {
+        OBJECTIVE_ANY_RANK => {}
+        2..=14 => target = target.with_rank(Rank { value: rank }),
+        _ => return None,
+    }
+    match suit {
+        OBJECTIVE_ANY_SUIT => {}
+        0..=3 => target = target.with_suit(Suit { value: suit }),
+        _ => return None,
+    }
+
+    Some(target)
+}
+
+/// Creates an objective without targets, which no draw meets. Free it with
+/// `objective_free`.
+#[no_mangle]
+pub extern "C" fn objective_new() -> *mut Objective {
+    Box::into_raw(Box::new(Objective::new()))
+}
+
+#[no_mangle]
+pub extern "C" fn objective_free(objective: *mut Objective) {
+    if !objective.is_null() {
+        unsafe {
+            let _ = Box::from_raw(objective);
+        }
+    }
+}
+
+/// Adds a target to the objective: the hand type of raw value `hand`, made
+/// of cards of `rank` and `suit` only. Pass `OBJECTIVE_ANY_RANK` and
+/// `OBJECTIVE_ANY_SUIT` to leave them open. Returns false and leaves the
+/// objective untouched if a value is out of range.
+#[no_mangle]
+pub extern "C" fn objective_add_target(
+    objective: *mut Objective,
+    hand: u8,
+    rank: u8,
+    suit: u8,
+) -> bool {
+    if objective.is_null() {
+        panic!("objective pointer must not be null");
+    }
+
+    let Some(target) = target_from_raw(hand, rank, suit) else {
+        return false;
+    };
+
+    unsafe { &mut *objective }.push(target);
+    true
+}
+
+/// Probability of the hand drawn up to the hand size meeting `objective`.
+#[no_mangle]
+pub extern "C" fn discarder_run_objective(
+    discarder: *const Discarder,
+    hand: *const CCard,
+    hand_size: usize,
+    objective: *const Objective,
+    max_iterations: usize,
+    out_result: *mut CObjectiveResult,
+) {
+    if discarder.is_null() {
+        panic!("discarder pointer must not be null");
+    }
+    if objective.is_null() {
+        panic!("objective pointer must not be null");
+    }
+    if out_result.is_null() {
+        panic!("out_result pointer must not be null");
+    }
+    let discarder = unsafe { &*discarder };
+    let objective = unsafe { &*objective };
+    let rust_hand = cards_from_raw(hand, hand_size, "hand");
+
+    let result = discarder.run_objective(&rust_hand, objective, max_iterations);
+    let (low, high) = result.confidence_interval(CONFIDENCE_Z);
+
+    unsafe {
+        *out_result = CObjectiveResult {
+            hits: result.hits,
+            probability: result.probability(),
+            low,
+            high,
+            iterations: result.iterations,
+            mode: result.mode.into(),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        api::{discarder_free, discarder_new_from_str},
+        deck::Card,
+    };
+    use std::{ffi::CString, ptr};
+
+    #[test]
+    fn test_run_objective() {
+        let deck = CString::new("standard").unwrap();
+        let discarder =
+            discarder_new_from_str(deck.as_ptr(), 5, 43, ptr::null_mut());
+        let hand: Vec<CCard> = Card::make_hand("9H 10H JH QH")
+            .unwrap()
+            .iter()
+            .map(CCard::from)
+            .collect();
+
+        let objective = objective_new();
+        let flush = HandType::Flush as u8;
+        assert!(!objective_add_target(objective, 13, 0, OBJECTIVE_ANY_SUIT));
+        assert!(!objective_add_target(
+            objective,
+            flush,
+            1,
+            OBJECTIVE_ANY_SUIT
+        ));
+        assert!(!objective_add_target(objective, flush, 0, 4));
+        assert!(objective_add_target(objective, flush, 0, 0));
+        assert!(objective_add_target(
+            objective,
+            HandType::Straight as u8,
+            OBJECTIVE_ANY_RANK,
+            OBJECTIVE_ANY_SUIT
+        ));
+
+        let mut result = CObjectiveResult {
+            hits: 0,
+            probability: 0.0,
+            low: 0.0,
+            high: 0.0,
+            iterations: 0,
+            mode: CMode::Auto,
+        };
+        discarder_run_objective(
+            discarder,
+            hand.as_ptr(),
+            hand.len(),
+            objective,
+            10000,
+            &mut result,
+        );
+        assert_eq!(result.iterations, 48);
+        assert_eq!(result.hits, 15);
+        assert_eq!(result.low, result.probability);
+        assert_eq!(result.high, result.probability);
+
+        objective_free(objective);
+        discarder_free(discarder);
+    }
+}
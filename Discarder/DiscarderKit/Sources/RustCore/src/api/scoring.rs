@@ -0,0 +1,224 @@
+use std::{ffi::CStr, os::raw::c_char, sync::Arc};
+
+use super::{boss_blind_from_raw, cards_from_raw, CBossBlind, CCard, CMode};
+use crate::{
+    discarder::Discarder,
+    jokers::{joker_by_name, JokerEffect},
+    scoring::{Scorer, MAX_PLAYED_CARDS},
+};
+
+/// Looks up `size` C string names with `lookup`, `None` if one is unknown.
+/// `what` names the array in panic messages.
+pub(super) fn named_from_raw<T>(
+    names: *const *const c_char,
+    size: usize,
+    what: &str,
+    lookup: impl Fn(&str) -> Option<T>,
+) -> Option<Vec<T>> {
+    if size == 0 {
+        return Some(Vec::new());
+    }
+
+    if names.is_null() {
+        panic!("{} pointer must not be null when {}_size > 0", what, what);
+    }
+
+    let names = unsafe { std::slice::from_raw_parts(names, size) };
+    names
+        .iter()
+        .map(|&name| {
+            if name.is_null() {
+                panic!("{} name must not be null", what);
+            }
+
+            let name = unsafe { CStr::from_ptr(name) }.to_string_lossy();
+            lookup(&name)
+        })
+        .collect()
+}
+
+pub(super) fn jokers_from_raw(
+    jokers: *const *const c_char,
+    size: usize,
+) -> Option<Vec<Box<dyn JokerEffect>>> {
+    named_from_raw(jokers, size, "jokers", joker_by_name)
+}
+
+#[repr(C)]
+pub struct CScoreResult {
+    pub mean: f64,
+    pub std_dev: f64,
+    pub max: f64,
+    /// Probability of scoring at least the target
+    pub hit_probability: f64,
+    pub iterations: usize,
+    pub mode: CMode,
+}
+
+/// Draws by how many cards of each rank and suit score in the best play.
+/// `ranks[r][n]` holds the draws scoring `n` cards of rank `r + 2`,
+/// `suits[s][n]` those scoring `n` cards of suit `s`.
+#[repr(C)]
+pub struct CScoringCardHistogram {
+    pub ranks: [[i64; 6]; 13],
+    pub suits: [[i64; 6]; 4],
+}
+
+/// `jokers` holds `jokers_size` joker names, left to right. Returns false
+/// and leaves the outputs untouched if a name is not a known joker.
+/// `out_cards` is optional.
+#[no_mangle]
+pub extern "C" fn discarder_run_scoring(
+    discarder: *const Discarder,
+    hand: *const CCard,
+    hand_size: usize,
+    jokers: *const *const c_char,
+    jokers_size: usize,
+    target: f64,
+    max_iterations: usize,
+    out_result: *mut CScoreResult,
+    out_cards: *mut CScoringCardHistogram,
+) -> bool {
+    if discarder.is_null() {
+        panic!("discarder pointer must not be null");
+    }
+    if out_result.is_null() {
+        panic!("out_result pointer must not be null");
+    }
+    let discarder = unsafe { &*discarder };
+    let rust_hand = cards_from_raw(hand, hand_size, "hand");
+
+    let Some(rust_jokers) = jokers_from_raw(jokers, jokers_size) else {
+        return false;
+    };
+
+    let result = discarder.run_scoring(
+        &rust_hand,
+        Arc::new(rust_jokers),
+        target,
+        max_iterations,
+    );
+
+    unsafe {
+        *out_result = CScoreResult {
+            mean: result.mean(),
+            std_dev: result.variance().sqrt(),
+            max: result.summary.max,
+            hit_probability: result.hit_probability(),
+            iterations: result.iterations,
+            mode: result.mode.into(),
+        };
+
+        if !out_cards.is_null() {
+            let out = &mut *out_cards;
+            result
+                .summary
+                .cards
+                .to_arrays(&mut out.ranks, &mut out.suits);
+        }
+    }
+
+    true
+}
+
+/// Writes the best cards to play from `hand` into `out_cards`, which must
+/// hold five cards, and returns how many were written. `out_hand` receives
+/// the `HandType` played, secret hands included, and `out_score` its score. Returns 0 for an unknown
+/// joker or if `blind` allows no play.
+#[no_mangle]
+pub extern "C" fn scoring_best_play(
+    hand: *const CCard,
+    hand_size: usize,
+    jokers: *const *const c_char,
+    jokers_size: usize,
+    blind: CBossBlind,
+    played_hands: u16,
+    out_cards: *mut CCard,
+    out_hand: *mut u8,
+    out_score: *mut f64,
+) -> usize {
+    if out_cards.is_null() {
+        panic!("out_cards pointer must not be null");
+    }
+
+    let rust_hand = cards_from_raw(hand, hand_size, "hand");
+    let Some(rust_jokers) = jokers_from_raw(jokers, jokers_size) else {
+        return 0;
+    };
+
+    let blind = boss_blind_from_raw(blind, played_hands);
+    let Some(play) = Scorer::best_play(&rust_hand, &rust_jokers, &blind) else {
+        return 0;
+    };
+
+    let out =
+        unsafe { std::slice::from_raw_parts_mut(out_cards, MAX_PLAYED_CARDS) };
+    for (out, card) in out.iter_mut().zip(&play.cards) {
+        *out = card.into();
+    }
+
+    unsafe {
+        if !out_hand.is_null() {
+            *out_hand = play.hand as u8;
+        }
+        if !out_score.is_null() {
+            *out_score = play.score.total();
+        }
+    }
+
+    play.cards.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{discarder_free, discarder_new_from_str};
+    use crate::deck::Card;
+    use std::{ffi::CString, ptr};
+
+    #[test]
+    fn test_run_scoring() {
+        let deck = CString::new("standard").unwrap();
+        let discarder =
+            discarder_new_from_str(deck.as_ptr(), 5, 43, ptr::null_mut());
+        let hand: Vec<CCard> = Card::make_hand("AS AH KC 7D")
+            .unwrap()
+            .iter()
+            .map(CCard::from)
+            .collect();
+        let jokers = [CString::new("Jolly Joker").unwrap()];
+        let joker_names: Vec<_> = jokers.iter().map(|j| j.as_ptr()).collect();
+
+        let mut result = CScoreResult {
+            mean: 0.0,
+            std_dev: 0.0,
+            max: 0.0,
+            hit_probability: 0.0,
+            iterations: 0,
+            mode: CMode::Auto,
+        };
+        let mut cards = CScoringCardHistogram {
+            ranks: [[0; 6]; 13],
+            suits: [[0; 6]; 4],
+        };
+        assert!(discarder_run_scoring(
+            discarder,
+            hand.as_ptr(),
+            hand.len(),
+            joker_names.as_ptr(),
+            joker_names.len(),
+            100.0,
+            1000,
+            &mut result,
+            &mut cards,
+        ));
+        assert_eq!(result.iterations, 48);
+
+        // Every best play scores both aces, so at least one Heart
+        assert_eq!(cards.ranks[12][0] + cards.ranks[12][1], 0);
+        assert_eq!(cards.suits[0][0], 0);
+        assert_eq!(cards.suits[0].iter().sum::<i64>(), 48);
+
+        discarder_free(discarder);
+    }
+}
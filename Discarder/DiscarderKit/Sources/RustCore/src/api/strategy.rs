@@ -0,0 +1,546 @@
+use std::{os::raw::c_char, sync::Arc, time::Duration};
+
+use super::{
+    boss_blind_from_raw, cards_from_raw,
+    scoring::{jokers_from_raw, named_from_raw},
+    CBossBlind, CCard, CMode, CRng,
+};
+use crate::{
+    deck::Deck,
+    discarder::CONFIDENCE_Z,
+    scoring::MAX_PLAYED_CARDS,
+    strategy::{
+        policy_by_name, Action, Advisor, Budget, Estimate, Goal, Round,
+        RoundState, Simulation, Solver,
+    },
+};
+
+/// Rules of the simulated round. The whole deck is shuffled, `hand_size`
+/// cards are drawn and `target` must be reached within `hands` plays.
+#[repr(C)]
+pub struct CRound {
+    pub hand_size: usize,
+    pub hands: usize,
+    pub discards: usize,
+    pub target: f64,
+    pub blind: CBossBlind,
+    /// Hands already played, as for `discarder_set_boss_blind`
+    pub played_hands: u16,
+}
+
+/// A simulated mean with its 95% confidence interval.
+#[repr(C)]
+pub struct CEstimate {
+    pub mean: f64,
+    pub low: f64,
+    pub high: f64,
+}
+
+impl From<Estimate> for CEstimate {
+    fn from(estimate: Estimate) -> Self {
+        Self {
+            mean: estimate.mean,
+            low: estimate.low,
+            high: estimate.high,
+        }
+    }
+}
+
+/// How one policy did. Differences are paired over the same shuffles and
+/// taken against the first policy, so they are zero for the first one.
+#[repr(C)]
+pub struct CPolicyReport {
+    pub win_rate: CEstimate,
+    pub mean_score: CEstimate,
+    pub win_rate_difference: CEstimate,
+    pub score_difference: CEstimate,
+}
+
+/// Threads to sample on, 0 for every available core
+fn threads_or_all(threads: usize) -> usize {
+    if threads > 0 {
+        return threads;
+    }
+
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Plays `rounds` shuffles of `deck` with each of the `policies_size`
+/// built-in policies named in `policies`, e.g. "Play Best", "Chase Flush"
+/// or "Keep Scoring", on `threads` threads or every core for 0. Writes one
+/// report per policy into `out_reports` and returns the rounds played, or 0
+/// if a policy or joker name is unknown.
+#[no_mangle]
+pub extern "C" fn strategy_compare(
+    round: *const CRound,
+    deck: *const CCard,
+    deck_size: usize,
+    jokers: *const *const c_char,
+    jokers_size: usize,
+    policies: *const *const c_char,
+    policies_size: usize,
+    rounds: usize,
+    seed: u64,
+    rng: CRng,
+    threads: usize,
+    out_reports: *mut CPolicyReport,
+) -> usize {
+    if round.is_null() {
+        panic!("round pointer must not be null");
+    }
+    if policies_size > 0 && out_reports.is_null() {
+        panic!("out_reports pointer must not be null when policies_size > 0");
+    }
+
+    let round = unsafe { &*round };
+    let rust_deck = cards_from_raw(deck, deck_size, "deck");
+    let Some(rust_jokers) = jokers_from_raw(jokers, jokers_size) else {
+        return 0;
+    };
+    let Some(rust_policies) =
+        named_from_raw(policies, policies_size, "policies", policy_by_name)
+    else {
+        return 0;
+    };
+
+    let rust_round =
+        Round::new(Deck::new(&rust_deck), round.hand_size, round.target)
+            .with_hands(round.hands)
+            .with_discards(round.discards)
+            .with_boss_blind(boss_blind_from_raw(
+                round.blind,
+                round.played_hands,
+            ))
+            .with_jokers(Arc::new(rust_jokers));
+
+    let result = Simulation::new(rust_round, rust_policies, seed)
+        .with_threads(threads_or_all(threads))
+        .with_rng(rng.into())
+        .run(rounds);
+
+    let out =
+        unsafe { std::slice::from_raw_parts_mut(out_reports, policies_size) };
+    for (i, out) in out.iter_mut().enumerate() {
+        *out = CPolicyReport {
+            win_rate: result.win_rate(i, CONFIDENCE_Z).into(),
+            mean_score: result.mean_score(i, CONFIDENCE_Z).into(),
+            win_rate_difference: result
+                .win_rate_difference(i, 0, CONFIDENCE_Z)
+                .into(),
+            score_difference: result
+                .score_difference(i, 0, CONFIDENCE_Z)
+                .into(),
+        };
+    }
+
+    result.rounds
+}
+
+/// Best action found by `strategy_solve`.
+#[repr(C)]
+pub struct CSolution {
+    /// Positions in the hand of the cards to play or discard
+    pub cards: [usize; 5],
+    pub cards_size: usize,
+    pub discard: bool,
+    /// Chance of winning the round after the action
+    pub probability: f64,
+    pub mode: CMode,
+}
+
+/// Best action for `hand` with `deck` left to draw, `round.hands` hands and
+/// `round.discards` discards left and `score` already reached. Solves
+/// exactly within `budget` states, otherwise rolls out the best play and
+/// every discard `rollouts` times on `threads` threads, 0 for all cores.
+/// Returns false and leaves `out_solution` untouched if a joker name is
+/// unknown or nothing can be played.
+#[no_mangle]
+pub extern "C" fn strategy_solve(
+    round: *const CRound,
+    hand: *const CCard,
+    hand_size: usize,
+    deck: *const CCard,
+    deck_size: usize,
+    jokers: *const *const c_char,
+    jokers_size: usize,
+    score: f64,
+    budget: usize,
+    rollouts: usize,
+    seed: u64,
+    rng: CRng,
+    threads: usize,
+    out_solution: *mut CSolution,
+) -> bool {
+    if round.is_null() {
+        panic!("round pointer must not be null");
+    }
+    if out_solution.is_null() {
+        panic!("out_solution pointer must not be null");
+    }
+
+    let round = unsafe { &*round };
+    let rust_hand = cards_from_raw(hand, hand_size, "hand");
+    let rust_deck = Deck::new(&cards_from_raw(deck, deck_size, "deck"));
+    let Some(rust_jokers) = jokers_from_raw(jokers, jokers_size) else {
+        return false;
+    };
+    if rust_hand.is_empty() || round.hands == 0 {
+        return false;
+    }
+
+    let state = RoundState {
+        hand: &rust_hand,
+        deck: &rust_deck,
+        hands_left: round.hands,
+        discards_left: round.discards,
+        score,
+        target: round.target,
+        blind: &boss_blind_from_raw(round.blind, round.played_hands),
+        jokers: &Arc::new(rust_jokers),
+    };
+    let solution = Solver::new(round.hand_size, seed)
+        .with_budget(budget)
+        .with_rollouts(rollouts)
+        .with_threads(threads_or_all(threads))
+        .with_rng(rng.into())
+        .solve(&state);
+
+    let (cards, cards_size, discard) = picked_cards(&solution.action);
+
+    unsafe {
+        *out_solution = CSolution {
+            cards,
+            cards_size,
+            discard,
+            probability: solution.probability,
+            mode: solution.mode.into(),
+        };
+    }
+
+    true
+}
+
+/// Positions of the cards an action picks, how many and whether they are
+/// discarded
+fn picked_cards(action: &Action) -> ([usize; MAX_PLAYED_CARDS], usize, bool) {
+    let (picked, discard) = match action {
+        Action::Play(picked) => (picked, false),
+        Action::Discard(picked) => (picked, true),
+    };
+    let mut cards = [0; MAX_PLAYED_CARDS];
+    cards[..picked.len()].copy_from_slice(picked);
+
+    (cards, picked.len(), discard)
+}
+
+/// What `strategy_advise` maximizes.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum CGoal {
+    /// Chance of reaching `round.target`
+    Clear = 0,
+    /// Mean score at the end of the round
+    Score = 1,
+}
+
+impl From<CGoal> for Goal {
+    fn from(goal: CGoal) -> Self {
+        match goal {
+            CGoal::Clear => Goal::Clear,
+            CGoal::Score => Goal::Score,
+        }
+    }
+}
+
+/// An action ranked by `strategy_advise`.
+#[repr(C)]
+pub struct CRankedAction {
+    /// Positions in the hand of the cards to play or discard
+    pub cards: [usize; 5],
+    pub cards_size: usize,
+    pub discard: bool,
+    /// Search iterations that started with the action
+    pub visits: usize,
+    /// Mean of the goal over those iterations
+    pub value: f64,
+}
+
+/// Ranks the actions of `hand` by Monte Carlo tree search, with `deck` left
+/// to draw and `score` already reached. Searches on `threads` threads, 0
+/// for all cores, for `seconds` when positive and finite, otherwise for
+/// `iterations` iterations. Writes up to `max_actions` actions into
+/// `out_actions`, most visited first, and returns how many, or 0 if a joker
+/// name is unknown, `seconds` is too large or nothing can be played.
+#[no_mangle]
+pub extern "C" fn strategy_advise(
+    round: *const CRound,
+    hand: *const CCard,
+    hand_size: usize,
+    deck: *const CCard,
+    deck_size: usize,
+    jokers: *const *const c_char,
+    jokers_size: usize,
+    score: f64,
+    goal: CGoal,
+    iterations: usize,
+    seconds: f64,
+    seed: u64,
+    rng: CRng,
+    threads: usize,
+    out_actions: *mut CRankedAction,
+    max_actions: usize,
+) -> usize {
+    if round.is_null() {
+        panic!("round pointer must not be null");
+    }
+    if max_actions > 0 && out_actions.is_null() {
+        panic!("out_actions pointer must not be null when max_actions > 0");
+    }
+
+    let round = unsafe { &*round };
+    let rust_hand = cards_from_raw(hand, hand_size, "hand");
+    let rust_deck = Deck::new(&cards_from_raw(deck, deck_size, "deck"));
+    let Some(rust_jokers) = jokers_from_raw(jokers, jokers_size) else {
+        return 0;
+    };
+    if rust_hand.is_empty() || round.hands == 0 {
+        return 0;
+    }
+
+    let budget = if seconds.is_finite() && seconds > 0.0 {
+        match Duration::try_from_secs_f64(seconds) {
+            Ok(seconds) => Budget::Time(seconds),
+            Err(_) => return 0,
+        }
+    } else {
+        Budget::Iterations(iterations)
+    };
+
+    let state = RoundState {
+        hand: &rust_hand,
+        deck: &rust_deck,
+        hands_left: round.hands,
+        discards_left: round.discards,
+        score,
+        target: round.target,
+        blind: &boss_blind_from_raw(round.blind, round.played_hands),
+        jokers: &Arc::new(rust_jokers),
+    };
+    let advice = Advisor::new(round.hand_size, seed)
+        .with_goal(goal.into())
+        .with_budget(budget)
+        .with_threads(threads_or_all(threads))
+        .with_rng(rng.into())
+        .advise(&state);
+
+    let count = advice.actions.len().min(max_actions);
+    if count == 0 {
+        return 0;
+    }
+    let out = unsafe { std::slice::from_raw_parts_mut(out_actions, count) };
+    for (out, ranked) in out.iter_mut().zip(&advice.actions) {
+        let (cards, cards_size, discard) = picked_cards(&ranked.action);
+        *out = CRankedAction {
+            cards,
+            cards_size,
+            discard,
+            visits: ranked.visits,
+            value: ranked.value,
+        };
+    }
+
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::Card;
+    use std::{ffi::CString, ptr};
+
+    fn estimate() -> CEstimate {
+        CEstimate {
+            mean: 0.0,
+            low: 0.0,
+            high: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_strategy_compare() {
+        let deck: Vec<CCard> = Deck::make_standard()
+            .cards
+            .iter()
+            .map(CCard::from)
+            .collect();
+        let round = CRound {
+            hand_size: 8,
+            hands: 4,
+            discards: 3,
+            target: 300.0,
+            blind: CBossBlind::None,
+            played_hands: 0,
+        };
+        let names = [
+            CString::new("play best").unwrap(),
+            CString::new("Keep Scoring").unwrap(),
+        ];
+        let names: Vec<_> = names.iter().map(|name| name.as_ptr()).collect();
+        let mut reports: Vec<_> = (0..2)
+            .map(|_| CPolicyReport {
+                win_rate: estimate(),
+                mean_score: estimate(),
+                win_rate_difference: estimate(),
+                score_difference: estimate(),
+            })
+            .collect();
+
+        let compare = |names: &[*const c_char],
+                       rounds: usize,
+                       out: *mut CPolicyReport| {
+            strategy_compare(
+                &round,
+                deck.as_ptr(),
+                deck.len(),
+                ptr::null(),
+                0,
+                names.as_ptr(),
+                names.len(),
+                rounds,
+                43,
+                CRng::Pcg64,
+                4,
+                out,
+            )
+        };
+
+        assert_eq!(compare(&names, 500, reports.as_mut_ptr()), 500);
+        assert_eq!(reports[0].score_difference.high, 0.0);
+        assert!(reports[0].win_rate.mean > 0.0);
+        let difference =
+            reports[1].mean_score.mean - reports[0].mean_score.mean;
+        assert!((reports[1].score_difference.mean - difference).abs() < 1e-9);
+
+        let unknown = CString::new("Always Fold").unwrap();
+        assert_eq!(compare(&[unknown.as_ptr()], 500, reports.as_mut_ptr()), 0);
+
+        // No rounds at all, every estimate is exactly zero
+        assert_eq!(compare(&names, 0, reports.as_mut_ptr()), 0);
+        assert_eq!(reports[1].mean_score.mean, 0.0);
+        assert_eq!(reports[1].score_difference.high, 0.0);
+    }
+
+    #[test]
+    fn test_strategy_solve() {
+        let hand: Vec<CCard> = Card::make_hand("AH KH QH JH 2C")
+            .unwrap()
+            .iter()
+            .map(CCard::from)
+            .collect();
+        let deck: Vec<CCard> = Card::make_hand("TH 3D 4S")
+            .unwrap()
+            .iter()
+            .map(CCard::from)
+            .collect();
+        let round = CRound {
+            hand_size: 5,
+            hands: 1,
+            discards: 1,
+            target: 300.0,
+            blind: CBossBlind::None,
+            played_hands: 0,
+        };
+
+        let mut solution = CSolution {
+            cards: [0; 5],
+            cards_size: 0,
+            discard: false,
+            probability: 0.0,
+            mode: CMode::Auto,
+        };
+        assert!(strategy_solve(
+            &round,
+            hand.as_ptr(),
+            hand.len(),
+            deck.as_ptr(),
+            deck.len(),
+            ptr::null(),
+            0,
+            0.0,
+            100000,
+            100,
+            43,
+            CRng::Pcg64Mcg,
+            4,
+            &mut solution,
+        ));
+        assert!(solution.discard);
+        assert_eq!(&solution.cards[..solution.cards_size], &[4]);
+        assert_eq!(solution.probability, 1.0 / 3.0);
+        assert!(matches!(solution.mode, CMode::Exact));
+    }
+
+    #[test]
+    fn test_strategy_advise() {
+        let hand: Vec<CCard> = Card::make_hand("AH KH QH JH 2C")
+            .unwrap()
+            .iter()
+            .map(CCard::from)
+            .collect();
+        let deck: Vec<CCard> = Card::make_hand("TH 3D 4S")
+            .unwrap()
+            .iter()
+            .map(CCard::from)
+            .collect();
+        let round = CRound {
+            hand_size: 5,
+            hands: 1,
+            discards: 1,
+            target: 300.0,
+            blind: CBossBlind::None,
+            played_hands: 0,
+        };
+        let mut actions: Vec<_> = (0..3)
+            .map(|_| CRankedAction {
+                cards: [0; 5],
+                cards_size: 0,
+                discard: false,
+                visits: 0,
+                value: 0.0,
+            })
+            .collect();
+
+        let advise = |jokers: &[*const c_char],
+                      seconds: f64,
+                      out: &mut [CRankedAction]| {
+            strategy_advise(
+                &round,
+                hand.as_ptr(),
+                hand.len(),
+                deck.as_ptr(),
+                deck.len(),
+                jokers.as_ptr(),
+                jokers.len(),
+                0.0,
+                CGoal::Clear,
+                2000,
+                seconds,
+                43,
+                CRng::Pcg64,
+                4,
+                out.as_mut_ptr(),
+                out.len(),
+            )
+        };
+
+        assert_eq!(advise(&[], 0.0, &mut actions), 3);
+        assert!(actions[0].discard);
+        assert_eq!(&actions[0].cards[..actions[0].cards_size], &[4]);
+        assert!((actions[0].value - 1.0 / 3.0).abs() < 0.15);
+        assert!(actions[1].visits <= actions[0].visits);
+
+        let unknown = CString::new("Not A Joker").unwrap();
+        assert_eq!(advise(&[unknown.as_ptr()], 0.0, &mut actions), 0);
+        assert_eq!(advise(&[], 1e30, &mut actions), 0);
+    }
+}
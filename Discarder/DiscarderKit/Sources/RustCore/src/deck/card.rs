@@ -1,10 +1,16 @@
+use super::edition::Edition;
+use super::enhancement::Enhancement;
 use super::rank::Rank;
+use super::seal::Seal;
 use super::suit::Suit;
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct Card {
     pub rank: Rank,
     pub suit: Suit,
+    pub enhancement: Enhancement,
+    pub seal: Seal,
+    pub edition: Edition,
 }
 
 impl Card {
@@ -12,82 +18,62 @@ impl Card {
         Self {
             rank: Rank { value: 0 },
             suit: Suit { value: 5 },
+            enhancement: Enhancement::None,
+            seal: Seal::None,
+            edition: Edition::None,
         }
     }
 }
 
 impl Card {
     pub fn new(rank: Rank, suit: Suit) -> Self {
-        Self { rank, suit }
-    }
-
-    pub fn from_str(s: &str) -> Option<Self> {
-        if s.len() != 2 {
-            return None;
+        Self {
+            rank,
+            suit,
+            enhancement: Enhancement::None,
+            seal: Seal::None,
+            edition: Edition::None,
         }
-
-        let rank_char = s.chars().nth(0)?;
-        let suit_char = s.chars().nth(1)?;
-
-        let rank_value = Rank::from_char(rank_char)?;
-        let suit_value = Suit::from_char(suit_char)?;
-
-        Some(Card {
-            rank: Rank { value: rank_value },
-            suit: Suit { value: suit_value },
-        })
     }
 
-    pub fn make_hand(s: &str) -> Option<Vec<Self>> {
-        let cards: Vec<Option<Card>> = s
-            .split_whitespace()
-            .map(|card_str| Card::from_str(card_str))
-            .collect();
-
-        if cards.iter().any(|card| card.is_none()) {
-            return None;
-        }
-
-        Some(cards.into_iter().map(|card| card.unwrap()).collect())
+    pub fn with_enhancement(mut self, enhancement: Enhancement) -> Self {
+        self.enhancement = enhancement;
+        self
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    pub fn with_seal(mut self, seal: Seal) -> Self {
+        self.seal = seal;
+        self
+    }
 
-    #[test]
-    fn test_card_from_str() {
-        let card = Card::from_str("3H").unwrap();
-        assert_eq!(card.rank.value, 3);
-        assert_eq!(card.suit.value, 0);
+    pub fn with_edition(mut self, edition: Edition) -> Self {
+        self.edition = edition;
+        self
+    }
 
-        let card = Card::from_str("JS").unwrap();
-        assert_eq!(card.rank.value, 11);
-        assert_eq!(card.suit.value, 3);
+    /// Whether the card is a plain playing card without any attributes
+    pub fn is_plain(&self) -> bool {
+        self.enhancement == Enhancement::None
+            && self.seal == Seal::None
+            && self.edition == Edition::None
+    }
 
-        assert!(Card::from_str("").is_none());
-        assert!(Card::from_str("XH").is_none());
-        assert!(Card::from_str("3X").is_none());
+    /// Jack, Queen or King
+    pub fn is_face(&self) -> bool {
+        !self.is_stone() && (11..=13).contains(&self.rank.value)
     }
 
-    #[test]
-    fn test_make_hand() {
-        let hand = Card::make_hand("2H 3D 4C 5S 7H").unwrap();
-        assert_eq!(hand.len(), 5);
-        assert_eq!(hand[0].rank.value, 2);
-        assert_eq!(hand[0].suit.value, 0);
-        assert_eq!(hand[1].rank.value, 3);
-        assert_eq!(hand[1].suit.value, 1);
-        assert_eq!(hand[2].rank.value, 4);
-        assert_eq!(hand[2].suit.value, 2);
-        assert_eq!(hand[3].rank.value, 5);
-        assert_eq!(hand[3].suit.value, 3);
-        assert_eq!(hand[4].rank.value, 7);
-        assert_eq!(hand[4].suit.value, 0);
+    /// Stone cards have no rank or suit
+    pub fn is_stone(&self) -> bool {
+        self.enhancement == Enhancement::Stone
+    }
 
-        assert_eq!(Card::make_hand(""), Some(vec![]));
-        assert!(Card::make_hand("2H 3D XC").is_none());
-        assert!(Card::make_hand("2H 3D 4X").is_none());
+    /// Whether the card counts as `suit`, Wild cards count as every suit
+    pub fn is_suit(&self, suit: Suit) -> bool {
+        match self.enhancement {
+            Enhancement::Stone => false,
+            Enhancement::Wild => true,
+            _ => self.suit == suit,
+        }
     }
 }
@@ -0,0 +1,261 @@
+use std::collections::BTreeMap;
+
+use super::card::Card;
+use super::deck::Deck;
+use super::rank::Rank;
+use super::suit::Suit;
+
+pub(super) const RANKS: usize = 13;
+pub(super) const SUITS: usize = 4;
+
+/// Order-independent view of a deck: number of copies of every card. Plain
+/// cards are counted per rank and suit, cards with attributes separately.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CardCounts {
+    plain: [[u32; SUITS]; RANKS],
+    other: BTreeMap<Card, u32>,
+    len: usize,
+}
+
+impl CardCounts {
+    pub fn new(cards: &[Card]) -> Self {
+        let mut result = Self::default();
+
+        for &card in cards {
+            result.add(card);
+        }
+
+        result
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn count(&self, card: &Card) -> usize {
+        match Self::slot(card) {
+            Some((row, suit)) => self.plain[row][suit] as usize,
+            None => self.other.get(card).copied().unwrap_or(0) as usize,
+        }
+    }
+
+    pub fn add(&mut self, card: Card) {
+        match Self::slot(&card) {
+            Some((row, suit)) => self.plain[row][suit] += 1,
+            None => *self.other.entry(card).or_insert(0) += 1,
+        }
+
+        self.len += 1;
+    }
+
+    /// Removes one copy of `card`, returns false if there is none
+    pub fn remove(&mut self, card: &Card) -> bool {
+        let count = match Self::slot(card) {
+            Some((row, suit)) => &mut self.plain[row][suit],
+            None => match self.other.get_mut(card) {
+                Some(count) => count,
+                None => return false,
+            },
+        };
+
+        if *count == 0 {
+            return false;
+        }
+
+        *count -= 1;
+        if *count == 0 && Self::slot(card).is_none() {
+            self.other.remove(card);
+        }
+
+        self.len -= 1;
+        true
+    }
+
+    /// Whether every card of `hand` can be drawn, duplicates included
+    pub fn contains_hand(&self, hand: &[Card]) -> bool {
+        self.missing(hand).is_empty()
+    }
+
+    /// Removes the cards of `hand`, or fails with the ones that are missing
+    /// without changing anything.
+    pub fn remove_hand(&mut self, hand: &[Card]) -> Result<(), Vec<Card>> {
+        let missing = self.missing(hand);
+        if !missing.is_empty() {
+            return Err(missing);
+        }
+
+        for card in hand {
+            self.remove(card);
+        }
+
+        Ok(())
+    }
+
+    /// Distinct cards with their number of copies, plain cards first
+    pub fn iter(&self) -> impl Iterator<Item = (Card, usize)> + '_ {
+        let plain = self.plain.iter().enumerate().flat_map(|(row, counts)| {
+            counts.iter().enumerate().filter(|(_, &c)| c > 0).map(
+                move |(suit, &count)| {
+                    let card = Card::new(
+                        Rank {
+                            value: row as u8 + 2,
+                        },
+                        Suit { value: suit as u8 },
+                    );
+                    (card, count as usize)
+                },
+            )
+        });
+
+        let other = self.other.iter().map(|(&card, &c)| (card, c as usize));
+
+        plain.chain(other)
+    }
+
+    pub fn to_deck(&self) -> Deck {
+        let mut cards = Vec::with_capacity(self.len);
+
+        for (card, count) in self.iter() {
+            cards.extend(std::iter::repeat_n(card, count));
+        }
+
+        Deck { cards }
+    }
+
+    fn missing(&self, hand: &[Card]) -> Vec<Card> {
+        let mut remaining = self.clone();
+
+        hand.iter()
+            .filter(|card| !remaining.remove(card))
+            .copied()
+            .collect()
+    }
+
+    #[inline]
+    fn slot(card: &Card) -> Option<(usize, usize)> {
+        if card.is_plain() && (2..=14).contains(&card.rank.value) {
+            Some((card.rank.value as usize - 2, card.suit.value as usize))
+        } else {
+            None
+        }
+    }
+}
+
+impl From<&Deck> for CardCounts {
+    fn from(deck: &Deck) -> Self {
+        Self::new(&deck.cards)
+    }
+}
+
+impl From<&CardCounts> for Deck {
+    fn from(counts: &CardCounts) -> Self {
+        counts.to_deck()
+    }
+}
+
+impl Deck {
+    pub fn counts(&self) -> CardCounts {
+        CardCounts::from(self)
+    }
+
+    /// Whether `hand` could have been drawn from this deck
+    pub fn can_draw(&self, hand: &[Card]) -> bool {
+        self.counts().contains_hand(hand)
+    }
+
+    /// Removes one copy of every card of `hand`, keeping the order of the
+    /// remaining cards. Fails with the missing cards without changing the
+    /// deck.
+    pub fn remove_hand(&mut self, hand: &[Card]) -> Result<(), Vec<Card>> {
+        self.counts().remove_hand(hand)?;
+
+        for card in hand {
+            let index = self.cards.iter().position(|c| c == card).unwrap();
+            self.cards.remove(index);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::Enhancement;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_counts() {
+        let glass = Card::from_str("AS")
+            .unwrap()
+            .with_enhancement(Enhancement::Glass);
+        let mut cards = Card::make_hand("AS AS KH 2D").unwrap();
+        cards.push(glass);
+
+        let counts = CardCounts::new(&cards);
+
+        assert_eq!(counts.len(), 5);
+        assert_eq!(counts.count(&Card::from_str("AS").unwrap()), 2);
+        assert_eq!(counts.count(&Card::from_str("KH").unwrap()), 1);
+        assert_eq!(counts.count(&Card::from_str("KS").unwrap()), 0);
+        assert_eq!(counts.count(&glass), 1);
+    }
+
+    #[test]
+    fn test_equality_ignores_order() {
+        let a = CardCounts::new(&Card::make_hand("AS KH AS 2D").unwrap());
+        let b = CardCounts::new(&Card::make_hand("2D AS AS KH").unwrap());
+        let c = CardCounts::new(&Card::make_hand("2D AS KH").unwrap());
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_deck_round_trip() {
+        let deck = Deck::make_checkered();
+        let counts = deck.counts();
+
+        assert_eq!(counts.len(), 52);
+        assert_eq!(counts.to_deck().counts(), counts);
+        assert_eq!(Deck::from(&counts).cards.len(), 52);
+    }
+
+    #[test]
+    fn test_remove_hand() {
+        let mut counts = Deck::make_standard().counts();
+
+        assert!(counts.contains_hand(&Card::make_hand("AS KH").unwrap()));
+        assert!(!counts.contains_hand(&Card::make_hand("AS AS").unwrap()));
+
+        assert_eq!(
+            counts.remove_hand(&Card::make_hand("AS AS 2D").unwrap()),
+            Err(Card::make_hand("AS").unwrap())
+        );
+        assert_eq!(counts.len(), 52);
+
+        assert_eq!(
+            counts.remove_hand(&Card::make_hand("AS 2D").unwrap()),
+            Ok(())
+        );
+        assert_eq!(counts.len(), 50);
+        assert_eq!(counts.count(&Card::from_str("AS").unwrap()), 0);
+    }
+
+    #[test]
+    fn test_deck_remove_hand() {
+        let mut deck = Deck::make_standard();
+        let hand = Card::make_hand("AS KH 5C").unwrap();
+
+        assert!(deck.can_draw(&hand));
+        assert_eq!(deck.remove_hand(&hand), Ok(()));
+        assert_eq!(deck.cards.len(), 49);
+        assert!(!deck.can_draw(&hand));
+        assert!(deck.remove_hand(&hand).is_err());
+        assert_eq!(deck.cards.len(), 49);
+    }
+}
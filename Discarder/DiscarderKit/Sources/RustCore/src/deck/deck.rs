@@ -8,30 +8,10 @@ pub struct Deck {
 }
 
 impl Deck {
-    #[inline]
-    pub fn sample_draw(&self, rng: &mut impl rand::Rng, out: &mut [Card]) {
-        assert!(out.len() <= self.cards.len());
-
-        let deck_size = self.cards.len();
-        let draw_size = out.len();
-
-        // Use reservoir sampling algorithm for efficient random selection without allocations
-        for i in 0..draw_size {
-            out[i] = self.cards[i];
-        }
-
-        // For each remaining card, decide whether to include it
-        for i in draw_size..deck_size {
-            let j = rng.gen_range(0..=i);
-
-            if j < draw_size {
-                out[j] = self.cards[i];
-            }
-        }
-    }
-    
     pub fn new(cards: &[Card]) -> Self {
-        Self { cards: cards.to_vec() }
+        Self {
+            cards: cards.to_vec(),
+        }
     }
 
     pub fn make_standard() -> Self {
@@ -71,21 +51,13 @@ mod tests {
         }
 
         // Each rank should appear exactly 4 times (once per suit)
-        for rank in 2..=14 {
-            assert_eq!(
-                rank_counts[rank], 4,
-                "Rank {} should appear 4 times",
-                rank
-            );
+        for (rank, &count) in rank_counts.iter().enumerate().skip(2) {
+            assert_eq!(count, 4, "Rank {} should appear 4 times", rank);
         }
 
         // Each suit should appear exactly 13 times (once per rank)
-        for suit in 0..4 {
-            assert_eq!(
-                suit_counts[suit], 13,
-                "Suit {} should appear 13 times",
-                suit
-            );
+        for (suit, &count) in suit_counts.iter().enumerate() {
+            assert_eq!(count, 13, "Suit {} should appear 13 times", suit);
         }
     }
 
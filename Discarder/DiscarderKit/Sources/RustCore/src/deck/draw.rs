@@ -0,0 +1,412 @@
+use rand::{Rng, RngCore};
+
+use super::card::Card;
+use super::deck::Deck;
+
+/// Largest draw Floyd's algorithm takes, it checks every pick against the
+/// cards drawn so far.
+const FLOYD_MAX_DRAW: usize = 8;
+
+/// Algorithm drawing cards without replacement.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum DrawMethod {
+    /// Picks by draw size and deck size, drawing bounded integers only
+    #[default]
+    Auto,
+    /// One `gen_range` integer per card left in the deck, the stream seeded
+    /// results were first recorded with
+    Reservoir,
+    /// One bounded integer per drawn card, shuffles a scratch copy of the
+    /// deck in place
+    FisherYates,
+    /// One bounded integer per drawn card without scratch space, for up to
+    /// `FLOYD_MAX_DRAW` cards
+    Floyd,
+}
+
+impl DrawMethod {
+    /// Whether the method can draw `draw_size` cards at once
+    pub fn supports(self, draw_size: usize) -> bool {
+        self != Self::Floyd || draw_size <= FLOYD_MAX_DRAW
+    }
+
+    fn algorithm(
+        self,
+        draw_size: usize,
+        deck_size: usize,
+        scratch: bool,
+    ) -> Algorithm {
+        match self {
+            Self::Auto => Algorithm::select(draw_size, deck_size, scratch),
+            Self::Reservoir => Algorithm::GenRangeReservoir,
+            Self::FisherYates => Algorithm::FisherYates,
+            Self::Floyd => Algorithm::Floyd,
+        }
+    }
+}
+
+/// What a `DrawMethod` runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Algorithm {
+    GenRangeReservoir,
+    /// Reservoir drawing bounded integers instead of `gen_range`
+    Reservoir,
+    FisherYates,
+    Floyd,
+}
+
+impl Algorithm {
+    /// Picks the fastest method for drawing `draw_size` out of `deck_size`
+    /// cards, see `draw_throughput`. A reservoir step costs about 4/3 of a
+    /// Fisher–Yates step. Without a `scratch` deck kept between draws,
+    /// Floyd's algorithm takes the small draws instead of Fisher–Yates.
+    fn select(draw_size: usize, deck_size: usize, scratch: bool) -> Self {
+        if 4 * deck_size.saturating_sub(draw_size) < 3 * draw_size {
+            Self::Reservoir
+        } else if scratch {
+            Self::FisherYates
+        } else if draw_size <= FLOYD_MAX_DRAW {
+            Self::Floyd
+        } else {
+            Self::Reservoir
+        }
+    }
+}
+
+impl Deck {
+    /// Draws distinct cards into `out` without keeping any state. Prefer
+    /// `DrawSampler` in loops.
+    #[inline]
+    pub fn sample_draw(&self, rng: &mut impl RngCore, out: &mut [Card]) {
+        assert!(out.len() <= self.cards.len());
+
+        match Algorithm::select(out.len(), self.cards.len(), false) {
+            Algorithm::Floyd => floyd(&self.cards, rng, out),
+            _ => reservoir(&self.cards, rng, out, |rng, range| {
+                bounded(rng, range as u32) as usize
+            }),
+        }
+    }
+}
+
+/// Draws uniformly random cards from a deck. Owned by a single sampling
+/// thread, as Fisher–Yates keeps its scratch deck between draws.
+#[derive(Clone)]
+pub struct DrawSampler {
+    scratch: Vec<Card>,
+    algorithm: Algorithm,
+}
+
+impl DrawSampler {
+    pub fn new(deck: &Deck, draw_size: usize) -> Self {
+        Self::with_method(deck, draw_size, DrawMethod::Auto)
+    }
+
+    pub fn with_method(
+        deck: &Deck,
+        draw_size: usize,
+        method: DrawMethod,
+    ) -> Self {
+        assert!(draw_size <= deck.cards.len());
+        assert!(deck.cards.len() <= u32::MAX as usize);
+        assert!(method.supports(draw_size));
+
+        Self {
+            scratch: deck.cards.clone(),
+            algorithm: method.algorithm(draw_size, deck.cards.len(), true),
+        }
+    }
+
+    /// Fills `out` with distinct cards of the deck, in no particular order.
+    #[inline]
+    pub fn sample_draw(&mut self, rng: &mut impl RngCore, out: &mut [Card]) {
+        assert!(out.len() <= self.scratch.len());
+
+        match self.algorithm {
+            Algorithm::GenRangeReservoir => {
+                reservoir(&self.scratch, rng, out, |rng, range| {
+                    rng.gen_range(0..range)
+                })
+            }
+            Algorithm::Reservoir => {
+                reservoir(&self.scratch, rng, out, |rng, range| {
+                    bounded(rng, range as u32) as usize
+                })
+            }
+            Algorithm::FisherYates => fisher_yates(&mut self.scratch, rng, out),
+            Algorithm::Floyd => floyd(&self.scratch, rng, out),
+        }
+    }
+}
+
+/// `index` draws a uniform integer below its range
+fn reservoir<R: RngCore>(
+    cards: &[Card],
+    rng: &mut R,
+    out: &mut [Card],
+    index: impl Fn(&mut R, usize) -> usize,
+) {
+    let draw_size = out.len();
+    out.copy_from_slice(&cards[..draw_size]);
+
+    for (i, &card) in cards.iter().enumerate().skip(draw_size) {
+        let j = index(rng, i + 1);
+        if j < draw_size {
+            out[j] = card;
+        }
+    }
+}
+
+/// `scratch` stays a permutation of the deck, so it is not restored between
+/// draws.
+fn fisher_yates(
+    scratch: &mut [Card],
+    rng: &mut impl RngCore,
+    out: &mut [Card],
+) {
+    let deck_size = scratch.len() as u32;
+
+    for (i, card) in out.iter_mut().enumerate() {
+        let j = i + bounded(rng, deck_size - i as u32) as usize;
+        scratch.swap(i, j);
+        *card = scratch[i];
+    }
+}
+
+fn floyd(cards: &[Card], rng: &mut impl RngCore, out: &mut [Card]) {
+    let deck_size = cards.len() as u32;
+    let draw_size = out.len() as u32;
+
+    let mut picked = [0u32; FLOYD_MAX_DRAW];
+    for (i, j) in (deck_size - draw_size..deck_size).enumerate() {
+        let t = bounded(rng, j + 1);
+        picked[i] = if picked[..i].contains(&t) { j } else { t };
+        out[i] = cards[picked[i] as usize];
+    }
+}
+
+/// Uniform integer in `0..range` using Lemire's nearly divisionless
+/// method, which only divides when a sample lands in the rejection zone.
+#[inline]
+pub fn bounded(rng: &mut impl RngCore, range: u32) -> u32 {
+    debug_assert!(range > 0);
+
+    let mut m = rng.next_u32() as u64 * range as u64;
+    let mut low = m as u32;
+
+    if low < range {
+        let threshold = range.wrapping_neg() % range;
+        while low < threshold {
+            m = rng.next_u32() as u64 * range as u64;
+            low = m as u32;
+        }
+    }
+
+    (m >> 32) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::{Rank, Suit};
+    use rand::SeedableRng;
+    use rand_pcg::Pcg64;
+    use std::collections::HashMap;
+
+    const METHODS: [DrawMethod; 4] = [
+        DrawMethod::Auto,
+        DrawMethod::Reservoir,
+        DrawMethod::FisherYates,
+        DrawMethod::Floyd,
+    ];
+
+    /// Pearson's statistic of `observed` against equally likely outcomes
+    fn chi_square(observed: impl Iterator<Item = usize>, total: usize) -> f64 {
+        let observed: Vec<_> = observed.collect();
+        let expected = total as f64 / observed.len() as f64;
+
+        observed
+            .iter()
+            .map(|&o| (o as f64 - expected).powi(2) / expected)
+            .sum()
+    }
+
+    #[test]
+    fn test_select() {
+        use Algorithm::*;
+
+        assert_eq!(Algorithm::select(5, 44, true), FisherYates);
+        assert_eq!(Algorithm::select(29, 52, true), FisherYates);
+        assert_eq!(Algorithm::select(30, 52, true), Reservoir);
+        assert_eq!(Algorithm::select(52, 52, true), Reservoir);
+        assert_eq!(Algorithm::select(0, 0, true), FisherYates);
+
+        assert_eq!(Algorithm::select(5, 44, false), Floyd);
+        assert_eq!(Algorithm::select(12, 52, false), Reservoir);
+        assert_eq!(Algorithm::select(30, 52, false), Reservoir);
+
+        // Draws larger than the deck only take what is there
+        assert_eq!(Algorithm::select(60, 52, true), Reservoir);
+        assert_eq!(Algorithm::select(8, 0, false), Reservoir);
+
+        // Only the explicit reservoir keeps the `gen_range` stream
+        assert_eq!(DrawMethod::Auto.algorithm(30, 52, true), Reservoir);
+        assert_eq!(
+            DrawMethod::Reservoir.algorithm(5, 44, true),
+            GenRangeReservoir
+        );
+    }
+
+    #[test]
+    fn test_distinct_cards() {
+        let deck = Deck::make_standard();
+        let mut rng = Pcg64::seed_from_u64(42);
+
+        for method in METHODS {
+            let max_size = match method {
+                DrawMethod::Floyd => FLOYD_MAX_DRAW,
+                _ => 52,
+            };
+
+            for size in 0..=max_size {
+                let mut sampler = DrawSampler::with_method(&deck, size, method);
+                let mut out = vec![Card::invalid(); size];
+
+                for _ in 0..10 {
+                    sampler.sample_draw(&mut rng, &mut out);
+
+                    let mut cards = out.clone();
+                    cards.sort();
+                    cards.dedup();
+                    assert_eq!(cards.len(), size, "{:?}", method);
+                    assert!(cards.iter().all(|c| deck.cards.contains(c)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_bounded_uniformity() {
+        let mut rng = Pcg64::seed_from_u64(42);
+        let samples = 70_000;
+
+        let mut counts = [0; 7];
+        for _ in 0..samples {
+            counts[bounded(&mut rng, 7) as usize] += 1;
+        }
+
+        // 99.9% quantile with 6 degrees of freedom
+        assert!(chi_square(counts.into_iter(), samples) < 22.46);
+        assert_eq!(bounded(&mut rng, 1), 0);
+    }
+
+    #[test]
+    fn test_draw_uniformity() {
+        // Every 3 or 5 card subset of 7 distinct cards is equally likely.
+        // Auto draws the 5 cards with the bounded reservoir.
+        let cards: Vec<_> = (2..9)
+            .map(|rank| Card::new(Rank { value: rank }, Suit { value: 0 }))
+            .collect();
+        let deck = Deck::new(&cards);
+        let samples = 70_000;
+
+        // 99.9% quantiles with 34 and 20 degrees of freedom
+        for (size, subsets, quantile) in [(3, 35, 65.25), (5, 21, 45.31)] {
+            for method in METHODS {
+                if !method.supports(size) {
+                    continue;
+                }
+
+                let mut sampler = DrawSampler::with_method(&deck, size, method);
+                let mut rng = Pcg64::seed_from_u64(42);
+                let mut out = vec![Card::invalid(); size];
+                let mut counts = HashMap::new();
+
+                for _ in 0..samples {
+                    sampler.sample_draw(&mut rng, &mut out);
+                    let mask: u32 = out.iter().map(|c| 1 << c.rank.value).sum();
+                    *counts.entry(mask).or_insert(0) += 1;
+                }
+
+                assert_eq!(counts.len(), subsets);
+
+                let statistic = chi_square(counts.into_values(), samples);
+                assert!(statistic < quantile, "{:?}: {}", method, statistic);
+            }
+        }
+    }
+
+    #[test]
+    fn test_card_frequencies() {
+        // Each card of a full deck is drawn in 5/52 of the draws
+        let deck = Deck::make_standard();
+        let samples = 52_000;
+
+        for method in METHODS {
+            let mut sampler = DrawSampler::with_method(&deck, 5, method);
+            let mut rng = Pcg64::seed_from_u64(43);
+            let mut out = [Card::invalid(); 5];
+            let mut counts = HashMap::new();
+
+            for _ in 0..samples {
+                sampler.sample_draw(&mut rng, &mut out);
+                for card in out {
+                    *counts.entry(card).or_insert(0) += 1;
+                }
+            }
+
+            assert_eq!(counts.len(), 52);
+
+            // 99.9% quantile with 51 degrees of freedom
+            let statistic = chi_square(counts.into_values(), samples * 5);
+            assert!(statistic < 87.97, "{:?}: {}", method, statistic);
+        }
+    }
+
+    /// Draws per second for each method, run with
+    /// `cargo test --release draw_throughput -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn draw_throughput() {
+        use std::time::Instant;
+
+        let deck = Deck::make_standard();
+        let mut rng = Pcg64::seed_from_u64(42);
+        let draws = 10_000_000;
+
+        for size in [1, 3, 5, 8, 12, 20, 26, 35, 45] {
+            for method in METHODS {
+                if method == DrawMethod::Floyd && size > FLOYD_MAX_DRAW {
+                    continue;
+                }
+
+                let mut sampler = DrawSampler::with_method(&deck, size, method);
+                let mut out = vec![Card::invalid(); size];
+
+                let start = Instant::now();
+                for _ in 0..draws {
+                    sampler.sample_draw(&mut rng, &mut out);
+                    std::hint::black_box(&out);
+                }
+                let seconds = start.elapsed().as_secs_f64();
+
+                println!(
+                    "{:>2} cards {:?}: {:.1}M draws/s",
+                    size,
+                    method,
+                    draws as f64 / seconds / 1e6
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,37 @@
+/// Edition of a playing card.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Default,
+    strum::EnumIter,
+    strum::EnumCount,
+    strum::EnumString,
+    strum::IntoStaticStr,
+)]
+#[strum(serialize_all = "lowercase")]
+#[repr(u8)]
+pub enum Edition {
+    #[default]
+    #[strum(disabled)]
+    None = 0,
+    /// +50 chips
+    Foil = 1,
+    /// +10 mult
+    #[strum(to_string = "holo", serialize = "holographic")]
+    Holographic = 2,
+    /// x1.5 mult
+    Polychrome = 3,
+}
+
+impl Edition {
+    pub fn from_raw(value: u8) -> Option<Self> {
+        use strum::IntoEnumIterator;
+        Self::iter().find(|e| *e as u8 == value)
+    }
+}
@@ -0,0 +1,46 @@
+/// Card enhancement applied by Tarot cards.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Default,
+    strum::EnumIter,
+    strum::EnumCount,
+    strum::EnumString,
+    strum::IntoStaticStr,
+)]
+#[strum(serialize_all = "lowercase")]
+#[repr(u8)]
+pub enum Enhancement {
+    #[default]
+    #[strum(disabled)]
+    None = 0,
+    /// +30 chips
+    Bonus = 1,
+    /// +4 mult
+    Mult = 2,
+    /// Counts as every suit
+    Wild = 3,
+    /// x2 mult
+    Glass = 4,
+    /// x1.5 mult while held in hand
+    Steel = 5,
+    /// +50 chips, no rank or suit, always scores
+    Stone = 6,
+    /// $3 if held in hand at end of round
+    Gold = 7,
+    /// 1 in 5 chance for +20 mult
+    Lucky = 8,
+}
+
+impl Enhancement {
+    pub fn from_raw(value: u8) -> Option<Self> {
+        use strum::IntoEnumIterator;
+        Self::iter().find(|e| *e as u8 == value)
+    }
+}
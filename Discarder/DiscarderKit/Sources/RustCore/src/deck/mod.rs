@@ -1,9 +1,28 @@
 mod card;
+mod card_counts;
+#[allow(clippy::module_inception)]
 mod deck;
+mod draw;
+mod edition;
+mod enhancement;
+mod notation;
+mod presets;
 mod rank;
+mod seal;
 mod suit;
+mod symmetry;
+mod transform;
 
 pub use card::*;
+pub use card_counts::*;
 pub use deck::*;
+pub use draw::*;
+pub use edition::*;
+pub use enhancement::*;
+pub use notation::ParseCardError;
+pub use presets::*;
 pub use rank::*;
+pub use seal::*;
 pub use suit::*;
+pub use symmetry::*;
+pub use transform::*;
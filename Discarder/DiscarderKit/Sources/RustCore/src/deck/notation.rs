@@ -0,0 +1,357 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::card::Card;
+use super::deck::Deck;
+use super::edition::Edition;
+use super::enhancement::Enhancement;
+use super::rank::Rank;
+use super::seal::Seal;
+use super::suit::Suit;
+
+/// What is wrong with a card token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseCardErrorKind {
+    Empty,
+    InvalidRank,
+    InvalidSuit,
+    UnknownAttribute(String),
+    /// Two enhancements, seals or editions on the same card
+    DuplicateAttribute(String),
+    /// A valid card the deck has no copy left of
+    NotInDeck,
+}
+
+/// A card token that could not be parsed, with its byte offset in the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseCardError {
+    pub token: String,
+    pub position: usize,
+    pub kind: ParseCardErrorKind,
+}
+
+impl fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match &self.kind {
+            ParseCardErrorKind::Empty => "empty card".to_string(),
+            ParseCardErrorKind::InvalidRank => "invalid rank".to_string(),
+            ParseCardErrorKind::InvalidSuit => "invalid suit".to_string(),
+            ParseCardErrorKind::UnknownAttribute(name) => {
+                format!("unknown attribute `{}`", name)
+            }
+            ParseCardErrorKind::DuplicateAttribute(name) => {
+                format!("duplicate attribute `{}`", name)
+            }
+            ParseCardErrorKind::NotInDeck => "not in the deck".to_string(),
+        };
+
+        write!(f, "{} in `{}` at {}", reason, self.token, self.position)
+    }
+}
+
+impl std::error::Error for ParseCardError {}
+
+impl Card {
+    /// The card `stone` stands for. Stone cards have no rank or suit, so
+    /// any other rank and suit is written out, e.g. `KS:stone`.
+    pub fn stone() -> Self {
+        Card::new(Rank { value: 2 }, Suit { value: 0 })
+            .with_enhancement(Enhancement::Stone)
+    }
+
+    /// Parses whitespace separated cards, see `FromStr` for the notation.
+    /// Errors carry the byte offset of the offending token in `s`.
+    pub fn make_hand(s: &str) -> Result<Vec<Self>, ParseCardError> {
+        s.split_whitespace()
+            .map(|token| {
+                let position = token.as_ptr() as usize - s.as_ptr() as usize;
+                parse_card(token, position)
+            })
+            .collect()
+    }
+}
+
+impl Deck {
+    /// Parses a hand like `Card::make_hand`, that has to be drawn from this
+    /// deck. Fails on the first card the deck has no copy left of.
+    pub fn parse_hand(&self, s: &str) -> Result<Vec<Card>, ParseCardError> {
+        let hand = Card::make_hand(s)?;
+        let mut counts = self.counts();
+
+        for (card, token) in hand.iter().zip(s.split_whitespace()) {
+            if !counts.remove(card) {
+                return Err(ParseCardError {
+                    token: token.to_string(),
+                    position: token.as_ptr() as usize - s.as_ptr() as usize,
+                    kind: ParseCardErrorKind::NotInDeck,
+                });
+            }
+        }
+
+        Ok(hand)
+    }
+
+    /// Parses a preset name, `standard`, `abandoned`, `checkered` or
+    /// `erratic` (shuffled with `seed`), or the cards themselves.
+    pub fn parse(s: &str, seed: u64) -> Result<Deck, ParseCardError> {
+        match s.trim() {
+            "standard" => Ok(Deck::make_standard()),
+            "abandoned" => Ok(Deck::make_abandoned()),
+            "checkered" => Ok(Deck::make_checkered()),
+            "erratic" => Ok(Deck::make_erratic(seed)),
+            _ => Card::make_hand(s).map(|cards| Deck::new(&cards)),
+        }
+    }
+}
+
+/// Cards are written as rank and suit, like `AS` or `10H`, followed by any
+/// attributes separated by `:` or `+`, like `KS+wild` or `AS:glass:red:foil`.
+/// A `_seal` suffix picks the seal where names clash, as in `gold_seal`.
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_card(s, 0)
+    }
+}
+
+fn parse_card(token: &str, position: usize) -> Result<Card, ParseCardError> {
+    let error = |kind| ParseCardError {
+        token: token.to_string(),
+        position,
+        kind,
+    };
+
+    let mut parts = token.split([':', '+']);
+    let name = parts.next().unwrap_or_default();
+
+    let mut card = if name.eq_ignore_ascii_case("stone") {
+        Card::stone()
+    } else {
+        let mut chars = name.chars();
+        let suit = chars.next_back().ok_or(error(ParseCardErrorKind::Empty))?;
+        let rank = match chars.as_str() {
+            "10" => Some(10),
+            rank if rank.chars().count() == 1 => rank
+                .chars()
+                .next()
+                .and_then(|c| Rank::from_char(c.to_ascii_uppercase())),
+            _ => None,
+        }
+        .ok_or(error(ParseCardErrorKind::InvalidRank))?;
+        let suit = Suit::from_char(suit.to_ascii_uppercase())
+            .ok_or(error(ParseCardErrorKind::InvalidSuit))?;
+
+        Card::new(Rank { value: rank }, Suit { value: suit })
+    };
+
+    let mut enhancement = card.enhancement;
+    let mut seal = None;
+    let mut edition = None;
+
+    for attribute in parts {
+        let name = attribute.to_ascii_lowercase();
+        let duplicate =
+            || error(ParseCardErrorKind::DuplicateAttribute(name.clone()));
+        let unknown =
+            || error(ParseCardErrorKind::UnknownAttribute(name.clone()));
+
+        // Names clashing with an enhancement go to the enhancement
+        if let Some(value) = name.strip_suffix("_seal") {
+            let value = value.parse().map_err(|_| unknown())?;
+            seal.replace(value).map_or(Ok(()), |_| Err(duplicate()))?;
+        } else if let Ok(value) = name.parse::<Enhancement>() {
+            if enhancement != Enhancement::None {
+                return Err(duplicate());
+            }
+            enhancement = value;
+        } else if let Ok(value) = name.parse::<Seal>() {
+            seal.replace(value).map_or(Ok(()), |_| Err(duplicate()))?;
+        } else if let Ok(value) = name.parse::<Edition>() {
+            edition
+                .replace(value)
+                .map_or(Ok(()), |_| Err(duplicate()))?;
+        } else {
+            return Err(unknown());
+        }
+    }
+
+    card.enhancement = enhancement;
+    card.seal = seal.unwrap_or_default();
+    card.edition = edition.unwrap_or_default();
+
+    Ok(card)
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stone = Card::stone();
+        if self.rank == stone.rank && self.suit == stone.suit && self.is_stone()
+        {
+            write!(f, "stone")?;
+        } else {
+            write!(f, "{}{}", self.rank.to_char(), self.suit.to_char())?;
+
+            if self.enhancement != Enhancement::None {
+                write!(f, ":{}", <&str>::from(self.enhancement))?;
+            }
+        }
+
+        match self.seal {
+            Seal::None => {}
+            Seal::Gold => write!(f, ":gold_seal")?,
+            seal => write!(f, ":{}", <&str>::from(seal))?,
+        }
+
+        if self.edition != Edition::None {
+            write!(f, ":{}", <&str>::from(self.edition))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_card_from_str() {
+        let card = Card::from_str("3H").unwrap();
+        assert_eq!(card.rank.value, 3);
+        assert_eq!(card.suit.value, 0);
+
+        let card = Card::from_str("JS").unwrap();
+        assert_eq!(card.rank.value, 11);
+        assert_eq!(card.suit.value, 3);
+
+        let card = Card::from_str("10d").unwrap();
+        assert_eq!(card.rank.value, 10);
+        assert_eq!(card.suit.value, 1);
+
+        assert!(Card::from_str("").is_err());
+        assert!(Card::from_str("XH").is_err());
+        assert!(Card::from_str("3X").is_err());
+        assert!(Card::from_str("11H").is_err());
+    }
+
+    #[test]
+    fn test_card_attributes_from_str() {
+        let card = Card::from_str("AS:glass:red:foil").unwrap();
+        assert_eq!(card.rank.value, 14);
+        assert_eq!(card.enhancement, Enhancement::Glass);
+        assert_eq!(card.seal, Seal::Red);
+        assert_eq!(card.edition, Edition::Foil);
+        assert!(!card.is_plain());
+
+        let card = Card::from_str("KS+wild").unwrap();
+        assert_eq!(card.enhancement, Enhancement::Wild);
+
+        let card = Card::from_str("KH:holo").unwrap();
+        assert_eq!(card.edition, Edition::Holographic);
+        assert_eq!(card.enhancement, Enhancement::None);
+        let card = Card::from_str("KH:Holographic").unwrap();
+        assert_eq!(card.edition, Edition::Holographic);
+
+        // Gold is both an enhancement and a seal, the enhancement wins
+        let card = Card::from_str("2C:gold").unwrap();
+        assert_eq!(card.enhancement, Enhancement::Gold);
+        assert_eq!(card.seal, Seal::None);
+        let card = Card::from_str("2C:gold_seal").unwrap();
+        assert_eq!(card.enhancement, Enhancement::None);
+        assert_eq!(card.seal, Seal::Gold);
+
+        let card = Card::from_str("stone:red").unwrap();
+        assert!(card.is_stone());
+        assert_eq!(card.seal, Seal::Red);
+
+        assert!(Card::from_str("AS:shiny").is_err());
+        assert!(Card::from_str("AS:none").is_err());
+        assert!(Card::from_str("AS:").is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |s: &str| Card::make_hand(s).unwrap_err();
+
+        assert_eq!(
+            error("2H 3D XC"),
+            ParseCardError {
+                token: "XC".to_string(),
+                position: 6,
+                kind: ParseCardErrorKind::InvalidRank,
+            }
+        );
+        assert_eq!(error("2H  4X").kind, ParseCardErrorKind::InvalidSuit);
+        assert_eq!(error("2H  4X").position, 4);
+        assert_eq!(
+            error("AS:shiny").kind,
+            ParseCardErrorKind::UnknownAttribute("shiny".to_string())
+        );
+        assert_eq!(
+            error("AS:foil:holo").kind,
+            ParseCardErrorKind::DuplicateAttribute("holo".to_string())
+        );
+        assert_eq!(
+            error("AS:glass:wild").kind,
+            ParseCardErrorKind::DuplicateAttribute("wild".to_string())
+        );
+        assert_eq!(
+            error("stone:stone").kind,
+            ParseCardErrorKind::DuplicateAttribute("stone".to_string())
+        );
+
+        assert_eq!(error("KS QX").to_string(), "invalid suit in `QX` at 3");
+    }
+
+    #[test]
+    fn test_make_hand() {
+        let hand = Card::make_hand("2H 3D 4C 5S 7H").unwrap();
+        assert_eq!(hand.len(), 5);
+        assert_eq!(hand[0].rank.value, 2);
+        assert_eq!(hand[0].suit.value, 0);
+        assert_eq!(hand[1].rank.value, 3);
+        assert_eq!(hand[1].suit.value, 1);
+        assert_eq!(hand[2].rank.value, 4);
+        assert_eq!(hand[2].suit.value, 2);
+        assert_eq!(hand[3].rank.value, 5);
+        assert_eq!(hand[3].suit.value, 3);
+        assert_eq!(hand[4].rank.value, 7);
+        assert_eq!(hand[4].suit.value, 0);
+
+        assert_eq!(Card::make_hand(""), Ok(vec![]));
+        assert!(Card::make_hand("2H 3D XC").is_err());
+        assert!(Card::make_hand("2H 3D 4X").is_err());
+    }
+
+    #[test]
+    fn test_parse_hand_from_deck() {
+        let deck = Deck::parse("AS KH AS", 0).unwrap();
+
+        assert_eq!(deck.parse_hand("AS  AS").unwrap().len(), 2);
+        assert_eq!(
+            deck.parse_hand("KH AS AS AS").unwrap_err(),
+            ParseCardError {
+                token: "AS".to_string(),
+                position: 9,
+                kind: ParseCardErrorKind::NotInDeck,
+            }
+        );
+        assert!(deck.parse_hand("KX").is_err());
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let notation = "2H TD AS:glass:red:foil KS:wild 9C:gold_seal \
+                        QH:gold:purple:holo stone stone:blue:polychrome \
+                        KS:stone 5D:lucky";
+        let hand = Card::make_hand(notation).unwrap();
+
+        let displayed: Vec<String> =
+            hand.iter().map(|card| card.to_string()).collect();
+        assert_eq!(displayed.join(" "), notation);
+
+        assert_eq!(Card::from_str("10H").unwrap().to_string(), "TH");
+        assert_eq!(Card::from_str("KS+Wild").unwrap().to_string(), "KS:wild");
+    }
+}
@@ -0,0 +1,173 @@
+use rand::{Rng, SeedableRng};
+
+use super::card::Card;
+use super::deck::Deck;
+use super::rank::Rank;
+use super::suit::Suit;
+
+/// Starting decks from Balatro that change the deck composition.
+impl Deck {
+    /// Standard deck without Jacks, Queens and Kings, 40 cards
+    pub fn make_abandoned() -> Self {
+        let cards = Self::make_standard()
+            .cards
+            .into_iter()
+            .filter(|card| !card.is_face())
+            .collect();
+
+        Self { cards }
+    }
+
+    /// 26 Hearts and 26 Spades, every rank twice per suit
+    pub fn make_checkered() -> Self {
+        let hearts = Suit::from_char('H').unwrap();
+        let spades = Suit::from_char('S').unwrap();
+
+        let cards = Self::make_standard()
+            .cards
+            .into_iter()
+            .map(|card| {
+                let suit = match card.suit.value {
+                    0 | 1 => hearts,
+                    _ => spades,
+                };
+                Card::new(card.rank, Suit { value: suit })
+            })
+            .collect();
+
+        Self { cards }
+    }
+
+    /// 52 cards with random ranks and suits
+    pub fn make_erratic(seed: u64) -> Self {
+        let mut rng = rand_pcg::Pcg64::seed_from_u64(seed);
+
+        let cards = (0..52)
+            .map(|_| {
+                Card::new(
+                    Rank {
+                        value: rng.gen_range(2..=14),
+                    },
+                    Suit {
+                        value: rng.gen_range(0..4),
+                    },
+                )
+            })
+            .collect();
+
+        Self { cards }
+    }
+}
+
+/// Builds a deck from a starting deck with cards destroyed and added during
+/// the run.
+#[derive(Clone)]
+pub struct DeckBuilder {
+    cards: Vec<Card>,
+    missing: Vec<Card>,
+}
+
+impl DeckBuilder {
+    pub fn new(deck: Deck) -> Self {
+        Self {
+            cards: deck.cards,
+            missing: Vec::new(),
+        }
+    }
+
+    /// Removes one copy of every card in `cards`
+    pub fn destroy(mut self, cards: &[Card]) -> Self {
+        for card in cards {
+            match self.cards.iter().position(|c| c == card) {
+                Some(index) => {
+                    self.cards.remove(index);
+                }
+                None => self.missing.push(*card),
+            }
+        }
+
+        self
+    }
+
+    pub fn add(mut self, cards: &[Card]) -> Self {
+        self.cards.extend_from_slice(cards);
+        self
+    }
+
+    /// Fails with the destroyed cards that were not in the deck
+    pub fn build(self) -> Result<Deck, Vec<Card>> {
+        if self.missing.is_empty() {
+            Ok(Deck::new(&self.cards))
+        } else {
+            Err(self.missing)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_abandoned() {
+        let deck = Deck::make_abandoned();
+
+        assert_eq!(deck.cards.len(), 40);
+        assert!(deck.cards.iter().all(|card| !card.is_face()));
+    }
+
+    #[test]
+    fn test_checkered() {
+        let deck = Deck::make_checkered();
+        let hearts = deck.cards.iter().filter(|c| c.suit.value == 0).count();
+        let spades = deck.cards.iter().filter(|c| c.suit.value == 3).count();
+
+        assert_eq!(deck.cards.len(), 52);
+        assert_eq!(hearts, 26);
+        assert_eq!(spades, 26);
+
+        for rank in Rank::iter() {
+            let count = deck.cards.iter().filter(|c| c.rank == rank).count();
+            assert_eq!(count, 4);
+        }
+    }
+
+    #[test]
+    fn test_erratic() {
+        let deck = Deck::make_erratic(42);
+
+        assert_eq!(deck.cards.len(), 52);
+        assert_eq!(deck.cards, Deck::make_erratic(42).cards);
+        assert_ne!(deck.cards, Deck::make_erratic(43).cards);
+        assert_ne!(deck.cards, Deck::make_standard().cards);
+    }
+
+    #[test]
+    fn test_builder() {
+        let deck = DeckBuilder::new(Deck::make_standard())
+            .destroy(&Card::make_hand("2S 2H 3C").unwrap())
+            .add(&Card::make_hand("AS AS").unwrap())
+            .build()
+            .unwrap();
+
+        assert_eq!(deck.cards.len(), 51);
+        assert!(!deck.cards.contains(&Card::from_str("2S").unwrap()));
+        assert_eq!(
+            deck.cards
+                .iter()
+                .filter(|&&c| c == Card::from_str("AS").unwrap())
+                .count(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_builder_missing_card() {
+        let result = DeckBuilder::new(Deck::make_abandoned())
+            .destroy(&Card::make_hand("2S KH KH").unwrap())
+            .build();
+
+        assert_eq!(result.err(), Card::make_hand("KH KH").ok());
+    }
+}
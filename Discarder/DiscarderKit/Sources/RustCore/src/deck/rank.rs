@@ -1,4 +1,4 @@
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
 pub struct Rank {
     pub value: u8, // From 2 to 14
 }
@@ -10,6 +10,13 @@ impl Rank {
 }
 
 impl Rank {
+    /// The next rank up, Ace wraps around to Two
+    pub fn next(self) -> Rank {
+        Rank {
+            value: if self.value >= 14 { 2 } else { self.value + 1 },
+        }
+    }
+
     pub fn from_char(c: char) -> Option<u8> {
         match c {
             '2'..='9' => c.to_digit(10).map(|n| n as u8),
@@ -21,6 +28,17 @@ impl Rank {
             _ => None,
         }
     }
+
+    pub fn to_char(self) -> char {
+        match self.value {
+            10 => 'T',
+            11 => 'J',
+            12 => 'Q',
+            13 => 'K',
+            14 => 'A',
+            value => char::from_digit(value as u32, 10).unwrap_or('?'),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -37,4 +55,18 @@ mod tests {
         assert_eq!(Rank::from_char('A'), Some(14));
         assert_eq!(Rank::from_char('X'), None);
     }
+
+    #[test]
+    fn test_rank_to_char() {
+        for rank in Rank::iter() {
+            assert_eq!(Rank::from_char(rank.to_char()), Some(rank.value));
+        }
+    }
+
+    #[test]
+    fn test_rank_next() {
+        assert_eq!(Rank { value: 2 }.next(), Rank { value: 3 });
+        assert_eq!(Rank { value: 13 }.next(), Rank { value: 14 });
+        assert_eq!(Rank { value: 14 }.next(), Rank { value: 2 });
+    }
 }
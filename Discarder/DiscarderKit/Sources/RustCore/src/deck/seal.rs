@@ -0,0 +1,38 @@
+/// Seal stamped on a card by Tarot or Spectral cards.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Default,
+    strum::EnumIter,
+    strum::EnumCount,
+    strum::EnumString,
+    strum::IntoStaticStr,
+)]
+#[strum(serialize_all = "lowercase")]
+#[repr(u8)]
+pub enum Seal {
+    #[default]
+    #[strum(disabled)]
+    None = 0,
+    /// Retriggers the card once
+    Red = 1,
+    /// $3 when the card scores
+    Gold = 2,
+    /// Planet card for the final hand if held at end of round
+    Blue = 3,
+    /// Tarot card when discarded
+    Purple = 4,
+}
+
+impl Seal {
+    pub fn from_raw(value: u8) -> Option<Self> {
+        use strum::IntoEnumIterator;
+        Self::iter().find(|s| *s as u8 == value)
+    }
+}
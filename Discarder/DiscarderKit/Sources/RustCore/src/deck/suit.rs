@@ -1,4 +1,4 @@
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
 pub struct Suit {
     pub value: u8, // From 0 to 3
 }
@@ -19,6 +19,16 @@ impl Suit {
             _ => None,
         }
     }
+
+    pub fn to_char(self) -> char {
+        match self.value {
+            0 => 'H',
+            1 => 'D',
+            2 => 'C',
+            3 => 'S',
+            _ => '?',
+        }
+    }
 }
 
 #[cfg(test)]
@@ -33,4 +43,11 @@ mod tests {
         assert_eq!(Suit::from_char('S'), Some(3));
         assert_eq!(Suit::from_char('X'), None);
     }
+
+    #[test]
+    fn test_suit_to_char() {
+        for suit in Suit::iter() {
+            assert_eq!(Suit::from_char(suit.to_char()), Some(suit.value));
+        }
+    }
 }
@@ -0,0 +1,390 @@
+use std::collections::BTreeMap;
+
+use super::card::Card;
+use super::card_counts::{CardCounts, SUITS};
+use super::deck::Deck;
+use super::suit::Suit;
+use crate::utils::combinations;
+use itertools::Itertools;
+
+/// Permutation of the four suits, `self.0[suit]` is the image of `suit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SuitPermutation(pub [u8; SUITS]);
+
+impl SuitPermutation {
+    pub fn identity() -> Self {
+        Self([0, 1, 2, 3])
+    }
+
+    pub fn all() -> impl Iterator<Item = SuitPermutation> {
+        (0..SUITS as u8).permutations(SUITS).map(|p| {
+            let mut permutation = [0; SUITS];
+            permutation.copy_from_slice(&p);
+            SuitPermutation(permutation)
+        })
+    }
+
+    #[inline]
+    pub fn apply(&self, card: Card) -> Card {
+        Card {
+            suit: Suit {
+                value: self.0[card.suit.value as usize],
+            },
+            ..card
+        }
+    }
+}
+
+impl CardCounts {
+    pub fn permuted(&self, permutation: &SuitPermutation) -> Self {
+        let cards: Vec<Card> = self
+            .iter()
+            .flat_map(|(card, count)| {
+                std::iter::repeat_n(permutation.apply(card), count)
+            })
+            .collect();
+
+        Self::new(&cards)
+    }
+}
+
+/// Suit permutations that leave both the hand and the deck unchanged. Draws
+/// related by one of them produce the same poker hands, so only one draw per
+/// orbit has to be evaluated.
+pub fn suit_symmetries(hand: &[Card], deck: &Deck) -> Vec<SuitPermutation> {
+    let hand_counts = CardCounts::new(hand);
+    let deck_counts = deck.counts();
+
+    SuitPermutation::all()
+        .filter(|p| {
+            hand_counts.permuted(p) == hand_counts
+                && deck_counts.permuted(p) == deck_counts
+        })
+        .collect()
+}
+
+/// Smallest image of `hand` and `deck` under `symmetries`, both sorted, so
+/// that card sets related by a symmetry share one representative. Without
+/// symmetries the sets are only sorted.
+pub fn canonical_cards(
+    hand: &[Card],
+    deck: &[Card],
+    symmetries: &[SuitPermutation],
+) -> (Vec<Card>, Vec<Card>) {
+    let image = |permutation: &SuitPermutation| {
+        let permute = |cards: &[Card]| {
+            let mut cards: Vec<Card> =
+                cards.iter().map(|&card| permutation.apply(card)).collect();
+            cards.sort_unstable();
+            cards
+        };
+        (permute(hand), permute(deck))
+    };
+
+    symmetries
+        .iter()
+        .map(image)
+        .min()
+        .unwrap_or_else(|| image(&SuitPermutation::identity()))
+}
+
+impl Deck {
+    /// Calls `visit` once per orbit of `draw_size`-card draws under
+    /// `symmetries`, with the number of deck combinations the orbit stands
+    /// for. Weights add up to `C(deck size, draw_size)`.
+    ///
+    /// Draws are built row by row and only the lexicographically smallest
+    /// draw of every orbit is kept, pruning as soon as a permutation maps
+    /// the current prefix to a smaller one.
+    pub fn canonical_draws(
+        &self,
+        draw_size: usize,
+        symmetries: &[SuitPermutation],
+        mut visit: impl FnMut(&[Card], usize),
+    ) {
+        // Suit permutations only move cards within a row of cards that
+        // differ by suit alone, so rows can be compared one at a time
+        let mut rows = BTreeMap::<Card, [u8; SUITS]>::new();
+        for (card, count) in self.counts().iter() {
+            let template = Card {
+                suit: Suit { value: 0 },
+                ..card
+            };
+            let row = rows.entry(template).or_default();
+            row[card.suit.value as usize] =
+                u8::try_from(count).expect("too many copies of a card");
+        }
+
+        let rows: Vec<Row> = rows
+            .into_iter()
+            .map(|(template, counts)| Row {
+                template,
+                choices: RowChoice::all(&counts),
+            })
+            .collect();
+
+        // Cards still available from this row onwards, used for pruning
+        let mut available = vec![0usize; rows.len() + 1];
+        for (index, row) in rows.iter().enumerate().rev() {
+            let total = row.choices.last().map_or(0, |c| c.size);
+            available[index] = available[index + 1] + total;
+        }
+
+        if draw_size > available[0] {
+            return;
+        }
+
+        let mut search = CanonicalSearch {
+            rows: &rows,
+            available: &available,
+            drawn: Vec::with_capacity(draw_size),
+            group_size: symmetries.len().max(1),
+            visit: &mut visit,
+        };
+
+        let active = if symmetries.is_empty() {
+            vec![SuitPermutation::identity()]
+        } else {
+            symmetries.to_vec()
+        };
+
+        search.run(0, draw_size, 1, &active);
+    }
+}
+
+/// Cards that only differ by suit, with every way of drawing from them.
+struct Row {
+    template: Card,
+    choices: Vec<RowChoice>,
+}
+
+/// One way of drawing from a row: how many cards of every suit.
+struct RowChoice {
+    counts: [u8; SUITS],
+    size: usize,
+    weight: usize,
+}
+
+impl RowChoice {
+    fn all(available: &[u8; SUITS]) -> Vec<RowChoice> {
+        available
+            .iter()
+            .map(|&count| 0..=count)
+            .multi_cartesian_product()
+            .map(|counts| {
+                let mut row = [0; SUITS];
+                row.copy_from_slice(&counts);
+
+                RowChoice {
+                    counts: row,
+                    size: counts.iter().map(|&c| c as usize).sum(),
+                    weight: counts
+                        .iter()
+                        .zip(available)
+                        .map(|(&c, &a)| {
+                            combinations(a as usize, c as usize).unwrap()
+                        })
+                        .product(),
+                }
+            })
+            .collect()
+    }
+}
+
+struct CanonicalSearch<'a, F: FnMut(&[Card], usize)> {
+    rows: &'a [Row],
+    available: &'a [usize],
+    drawn: Vec<Card>,
+    group_size: usize,
+    visit: &'a mut F,
+}
+
+impl<F: FnMut(&[Card], usize)> CanonicalSearch<'_, F> {
+    fn run(
+        &mut self,
+        row: usize,
+        remaining: usize,
+        weight: usize,
+        active: &[SuitPermutation],
+    ) {
+        if remaining == 0 {
+            // Remaining rows are empty and fixed by every permutation, so
+            // the permutations still active form the stabilizer of the draw
+            (self.visit)(&self.drawn, weight * self.group_size / active.len());
+            return;
+        }
+
+        if row == self.rows.len() || self.available[row] < remaining {
+            return;
+        }
+
+        let mut next_active = Vec::with_capacity(active.len());
+
+        let template = self.rows[row].template;
+
+        for choice in &self.rows[row].choices {
+            if choice.size > remaining {
+                continue;
+            }
+
+            if !Self::filter_canonical(&choice.counts, active, &mut next_active)
+            {
+                continue;
+            }
+
+            let drawn_before = self.drawn.len();
+            for (suit, &count) in choice.counts.iter().enumerate() {
+                for _ in 0..count {
+                    self.drawn.push(Card {
+                        suit: Suit { value: suit as u8 },
+                        ..template
+                    });
+                }
+            }
+
+            self.run(
+                row + 1,
+                remaining - choice.size,
+                weight * choice.weight,
+                &next_active,
+            );
+
+            self.drawn.truncate(drawn_before);
+        }
+    }
+
+    /// Keeps permutations mapping the row onto itself, fails if any of them
+    /// maps it to a lexicographically smaller row.
+    #[inline]
+    fn filter_canonical(
+        counts: &[u8; SUITS],
+        active: &[SuitPermutation],
+        next_active: &mut Vec<SuitPermutation>,
+    ) -> bool {
+        next_active.clear();
+
+        for permutation in active {
+            let mut permuted = [0u8; SUITS];
+            for (suit, &count) in counts.iter().enumerate() {
+                permuted[permutation.0[suit] as usize] = count;
+            }
+
+            match permuted.cmp(counts) {
+                std::cmp::Ordering::Less => return false,
+                std::cmp::Ordering::Equal => next_active.push(*permutation),
+                std::cmp::Ordering::Greater => {}
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::Enhancement;
+    use crate::poker::{PokerHandResolver, PokerHandsCount};
+
+    #[test]
+    fn test_suit_symmetries() {
+        let deck = Deck::make_standard();
+
+        assert_eq!(suit_symmetries(&[], &deck).len(), 24);
+
+        let hand = Card::make_hand("AS").unwrap();
+        assert_eq!(suit_symmetries(&hand, &deck).len(), 6);
+
+        let hand = Card::make_hand("AS KH").unwrap();
+        assert_eq!(suit_symmetries(&hand, &deck).len(), 2);
+
+        let hand = Card::make_hand("AS AH").unwrap();
+        assert_eq!(suit_symmetries(&hand, &deck).len(), 4);
+
+        let deck = Deck::new(&Card::make_hand("2S 3S 4H").unwrap());
+        assert_eq!(suit_symmetries(&[], &deck).len(), 2);
+    }
+
+    #[test]
+    fn test_canonical_cards() {
+        let hand = Card::make_hand("AS KH").unwrap();
+        let deck = Card::make_hand("2S 2D").unwrap();
+        let symmetries: Vec<_> = SuitPermutation::all().collect();
+        let canonical = canonical_cards(&hand, &deck, &symmetries);
+
+        // Spades to Diamonds, Hearts to Clubs, Diamonds to Hearts
+        let hand_image = Card::make_hand("AD KC").unwrap();
+        let deck_image = Card::make_hand("2H 2D").unwrap();
+        assert_eq!(
+            canonical_cards(&hand_image, &deck_image, &symmetries),
+            canonical
+        );
+
+        let sorted = canonical_cards(&hand, &deck, &[]);
+        assert!(sorted.0.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(canonical <= sorted);
+        assert_ne!(canonical_cards(&hand_image, &deck_image, &[]), sorted);
+    }
+
+    #[test]
+    fn test_canonical_draw_weights() {
+        let deck = Deck::make_standard();
+        let symmetries = suit_symmetries(&[], &deck);
+
+        for draw_size in 0..=5 {
+            let mut total = 0;
+            let mut orbits = 0;
+            deck.canonical_draws(draw_size, &symmetries, |draw, weight| {
+                assert_eq!(draw.len(), draw_size);
+                total += weight;
+                orbits += 1;
+            });
+
+            assert_eq!(Some(total), combinations(52, draw_size));
+            assert!(orbits * 24 >= total);
+        }
+    }
+
+    #[test]
+    fn test_canonical_draws_match_combinations() {
+        // Duplicated and enhanced cards with a partial symmetry
+        let mut cards =
+            Card::make_hand("AS AS AH AH KS KH QD QC 2S 2H 2D 2C 5D 5C 7D 7C")
+                .unwrap();
+        for card in Card::make_hand("4S 4H 6D 6C").unwrap() {
+            cards.push(card.with_enhancement(Enhancement::Wild));
+        }
+        let deck = Deck::new(&cards);
+        let hand = Card::make_hand("3S 3H").unwrap();
+        let symmetries = suit_symmetries(&hand, &deck);
+        assert_eq!(symmetries.len(), 4);
+
+        for draw_size in 0..=6 {
+            let mut expected = PokerHandsCount::new();
+            for draw in deck.cards.iter().combinations(draw_size) {
+                let mut cards = hand.clone();
+                cards.extend(draw.into_iter().copied());
+                PokerHandResolver::poker_hands(&cards, &mut expected);
+            }
+
+            let mut actual = PokerHandsCount::new();
+            deck.canonical_draws(draw_size, &symmetries, |draw, weight| {
+                let mut cards = hand.clone();
+                cards.extend_from_slice(draw);
+
+                let mut count = PokerHandsCount::new();
+                PokerHandResolver::poker_hands(&cards, &mut count);
+                actual.merge_scaled(&count, weight as i64);
+            });
+
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_canonical_draws_too_large() {
+        let deck = Deck::new(&Card::make_hand("2S 3S").unwrap());
+
+        deck.canonical_draws(3, &[], |_, _| panic!("No draws expected"));
+    }
+}
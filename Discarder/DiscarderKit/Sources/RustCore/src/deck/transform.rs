@@ -0,0 +1,263 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use strum::IntoEnumIterator;
+
+use super::card::Card;
+use super::enhancement::Enhancement;
+use super::rank::Rank;
+use super::suit::Suit;
+
+/// A change made to the cards in hand by a Tarot or Spectral card. Card
+/// indices refer to the hand as it is before the transform is applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transform {
+    /// Raises the rank by one, Aces wrap around to Twos
+    IncreaseRank {
+        cards: Vec<usize>,
+    },
+    /// Turns `to` into a copy of `from`
+    Copy {
+        from: usize,
+        to: usize,
+    },
+    Destroy {
+        cards: Vec<usize>,
+    },
+    ConvertSuit {
+        cards: Vec<usize>,
+        suit: Suit,
+    },
+    /// Destroys random cards, the others keep their order
+    DestroyRandom {
+        count: usize,
+    },
+    /// Adds face cards with a random enhancement
+    AddRandomFaceCards {
+        count: usize,
+    },
+}
+
+impl Transform {
+    pub fn strength(cards: &[usize]) -> Self {
+        Self::IncreaseRank {
+            cards: cards.to_vec(),
+        }
+    }
+
+    /// The left card becomes a copy of the right one
+    pub fn death(left: usize, right: usize) -> Self {
+        Self::Copy {
+            from: right,
+            to: left,
+        }
+    }
+
+    pub fn the_hanged_man(cards: &[usize]) -> Self {
+        Self::Destroy {
+            cards: cards.to_vec(),
+        }
+    }
+
+    pub fn the_star(cards: &[usize]) -> Self {
+        Self::convert_suit(cards, 'D')
+    }
+
+    pub fn the_moon(cards: &[usize]) -> Self {
+        Self::convert_suit(cards, 'C')
+    }
+
+    pub fn the_sun(cards: &[usize]) -> Self {
+        Self::convert_suit(cards, 'H')
+    }
+
+    pub fn the_world(cards: &[usize]) -> Self {
+        Self::convert_suit(cards, 'S')
+    }
+
+    pub fn familiar() -> Vec<Self> {
+        vec![
+            Self::DestroyRandom { count: 1 },
+            Self::AddRandomFaceCards { count: 3 },
+        ]
+    }
+
+    fn convert_suit(cards: &[usize], suit: char) -> Self {
+        Self::ConvertSuit {
+            cards: cards.to_vec(),
+            suit: Suit {
+                value: Suit::from_char(suit).unwrap(),
+            },
+        }
+    }
+
+    /// Applies the transform to `hand`, returns `None` if it refers to a
+    /// card that is not there.
+    pub fn apply(
+        &self,
+        hand: &mut Vec<Card>,
+        rng: &mut impl Rng,
+    ) -> Option<()> {
+        match self {
+            Self::IncreaseRank { cards } => {
+                for &index in cards {
+                    let card = hand.get_mut(index)?;
+                    card.rank = card.rank.next();
+                }
+            }
+            Self::Copy { from, to } => {
+                let card = *hand.get(*from)?;
+                *hand.get_mut(*to)? = card;
+            }
+            Self::Destroy { cards } => {
+                if cards.iter().any(|&index| index >= hand.len()) {
+                    return None;
+                }
+
+                let mut index = 0;
+                hand.retain(|_| {
+                    index += 1;
+                    !cards.contains(&(index - 1))
+                });
+            }
+            Self::ConvertSuit { cards, suit } => {
+                for &index in cards {
+                    hand.get_mut(index)?.suit = *suit;
+                }
+            }
+            Self::DestroyRandom { count } => {
+                for _ in 0..(*count).min(hand.len()) {
+                    hand.remove(rng.gen_range(0..hand.len()));
+                }
+            }
+            Self::AddRandomFaceCards { count } => {
+                let enhancements: Vec<Enhancement> =
+                    Enhancement::iter().collect();
+
+                for _ in 0..*count {
+                    let rank = Rank {
+                        value: rng.gen_range(11..=13),
+                    };
+                    let suit = Suit {
+                        value: rng.gen_range(0..4),
+                    };
+                    let enhancement = *enhancements.choose(rng).unwrap();
+                    hand.push(
+                        Card::new(rank, suit).with_enhancement(enhancement),
+                    );
+                }
+            }
+        }
+
+        Some(())
+    }
+
+    /// Applies all transforms in order.
+    pub fn apply_all(
+        transforms: &[Transform],
+        hand: &[Card],
+        rng: &mut impl Rng,
+    ) -> Option<Vec<Card>> {
+        let mut result = hand.to_vec();
+
+        for transform in transforms {
+            transform.apply(&mut result, rng)?;
+        }
+
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_pcg::Pcg64;
+
+    fn apply(transforms: &[Transform], hand: &str) -> Option<Vec<Card>> {
+        let mut rng = Pcg64::seed_from_u64(42);
+        Transform::apply_all(
+            transforms,
+            &Card::make_hand(hand).unwrap(),
+            &mut rng,
+        )
+    }
+
+    #[test]
+    fn test_strength() {
+        assert_eq!(
+            apply(&[Transform::strength(&[0, 2])], "9S KH AD"),
+            Card::make_hand("TS KH 2D").ok()
+        );
+        assert_eq!(apply(&[Transform::strength(&[3])], "9S KH AD"), None);
+    }
+
+    #[test]
+    fn test_death() {
+        assert_eq!(
+            apply(&[Transform::death(0, 2)], "9S KH AD"),
+            Card::make_hand("AD KH AD").ok()
+        );
+    }
+
+    #[test]
+    fn test_the_hanged_man() {
+        assert_eq!(
+            apply(&[Transform::the_hanged_man(&[0, 2])], "9S KH AD 2C"),
+            Card::make_hand("KH 2C").ok()
+        );
+        assert_eq!(apply(&[Transform::the_hanged_man(&[4])], "9S"), None);
+    }
+
+    #[test]
+    fn test_suit_conversion() {
+        let hand = "9S KH AD 2C";
+
+        assert_eq!(
+            apply(&[Transform::the_star(&[0, 1])], hand),
+            Card::make_hand("9D KD AD 2C").ok()
+        );
+        assert_eq!(
+            apply(&[Transform::the_moon(&[0])], hand),
+            Card::make_hand("9C KH AD 2C").ok()
+        );
+        assert_eq!(
+            apply(&[Transform::the_sun(&[3])], hand),
+            Card::make_hand("9S KH AD 2H").ok()
+        );
+        assert_eq!(
+            apply(&[Transform::the_world(&[1, 2, 3])], hand),
+            Card::make_hand("9S KS AS 2S").ok()
+        );
+    }
+
+    #[test]
+    fn test_familiar() {
+        let hand = Card::make_hand("9S KH AD 2C").unwrap();
+        let result = apply(&Transform::familiar(), "9S KH AD 2C").unwrap();
+
+        assert_eq!(result.len(), 6);
+        assert_eq!(result[..3].iter().filter(|c| hand.contains(c)).count(), 3);
+        assert!(result[3..].iter().all(|c| {
+            (11..=13).contains(&c.rank.value)
+                && c.enhancement != Enhancement::None
+        }));
+    }
+
+    #[test]
+    fn test_destroy_random_keeps_order() {
+        let hand = Card::make_hand("2S 3S 4S 5S 6S 7S").unwrap();
+        let destroy = Transform::DestroyRandom { count: 2 };
+
+        for seed in 0..20 {
+            let mut rng = Pcg64::seed_from_u64(seed);
+            let mut result = hand.clone();
+            destroy.apply(&mut result, &mut rng).unwrap();
+
+            assert_eq!(result.len(), 4);
+            assert!(result.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+
+        let result = apply(&[Transform::DestroyRandom { count: 3 }], "9S KH");
+        assert_eq!(result, Some(Vec::new()));
+    }
+}
@@ -1,14 +1,17 @@
-use std::cmp::max;
-
-use rand::SeedableRng;
+use std::{cmp::max, marker::PhantomData};
 
 use crate::{
-    deck::Card,
-    discarder::Discarder,
+    deck::{Card, DrawSampler},
+    discarder::{Discarder, Mode},
     montecarlo::{
-        Chunk, MonteCarloAlgorithm, MonteCarloAlgorithmFactory, Progress,
+        Chunk, MonteCarloAlgorithm, MonteCarloAlgorithmFactory,
+        MonteCarloOutput, MonteCarloRng, Pcg64, Progress,
+    },
+    poker::{
+        BestHandsCount, HandBatch, HandBreakdown, HandEvaluator,
+        HandSetHistogram, HandType, PokerHand, PokerHandsCount,
     },
-    poker::PokerHandsCount,
+    utils::wilson_interval,
 };
 
 #[derive(Debug)]
@@ -19,49 +22,164 @@ pub struct DiscardProgress<'a> {
 }
 
 impl<'a> DiscardProgress<'a> {
-    pub fn new(result: &'a Progress<DiscarderAlgorithm>) -> Self {
+    pub fn new<R: MonteCarloRng>(
+        result: &'a Progress<DiscarderAlgorithm<R>>,
+    ) -> Self {
         Self {
-            count: &result.chunk.output,
+            count: &result.chunk.output.count,
             iterations: result.chunk.iterations_done,
             fraction_completed: result.fraction_completed,
         }
     }
 }
 
+/// What the discarder counts while drawing
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiscardCounts {
+    pub count: PokerHandsCount,
+    pub best: BestHandsCount,
+    /// Only recorded with `Discarder::with_hand_sets`
+    pub hand_sets: Option<HandSetHistogram>,
+    /// Only recorded with `Discarder::with_breakdown`
+    pub breakdown: Option<HandBreakdown>,
+}
+
+impl DiscardCounts {
+    pub fn new(hand_sets: bool, breakdown: bool) -> Self {
+        Self {
+            count: PokerHandsCount::new(),
+            best: BestHandsCount::new(),
+            hand_sets: hand_sets.then(HandSetHistogram::new),
+            breakdown: breakdown.then(HandBreakdown::new),
+        }
+    }
+
+    /// Counts a draw containing `hands`, one bit per `HandType`, `weight`
+    /// times
+    #[inline]
+    pub fn add(&mut self, hands: u16, weight: i64) {
+        self.count.add_mask_scaled(hands, weight);
+        self.best.add(hands, weight);
+        if let Some(hand_sets) = &mut self.hand_sets {
+            hand_sets.add(hands, weight);
+        }
+    }
+
+    /// Records the ranks and suits taking part in the hand types `allowed`
+    /// in `hand`, if the breakdown is recorded
+    #[inline]
+    pub fn add_breakdown(&mut self, hand: &[Card], allowed: u16, weight: i64) {
+        if let Some(breakdown) = &mut self.breakdown {
+            breakdown.add(hand, allowed, weight);
+        }
+    }
+}
+
+impl MonteCarloOutput for DiscardCounts {
+    /// Hand sets and breakdowns are only created once a sampler records
+    /// them
+    fn new() -> Self {
+        Self::new(false, false)
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.count.merge(&other.count);
+        self.best.merge(&other.best);
+        if let Some(other) = &other.hand_sets {
+            self.hand_sets
+                .get_or_insert_with(HandSetHistogram::new)
+                .merge(other);
+        }
+        if let Some(other) = &other.breakdown {
+            self.breakdown
+                .get_or_insert_with(HandBreakdown::new)
+                .merge(other);
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct DiscardResult {
     pub count: PokerHandsCount,
+    /// Draws by the highest hand type that can be played from them
+    pub best: BestHandsCount,
+    /// Draws by the full set of hands they contain, see
+    /// `Discarder::with_hand_sets`
+    pub hand_sets: Option<HandSetHistogram>,
+    /// Draws by the ranks and suits taking part in each hand type, see
+    /// `Discarder::with_breakdown`
+    pub breakdown: Option<HandBreakdown>,
     pub iterations: usize,
+    pub mode: Mode,
 }
 
 impl DiscardResult {
-    pub fn from_result(result: Chunk<PokerHandsCount>) -> Self {
+    pub fn from_result(result: Chunk<DiscardCounts>) -> Self {
+        Self::new(result.output, result.iterations_done, Mode::MonteCarlo)
+    }
+
+    pub fn new(counts: DiscardCounts, iterations: usize, mode: Mode) -> Self {
         Self {
-            count: result.output,
-            iterations: result.iterations_done,
+            count: counts.count,
+            best: counts.best,
+            hand_sets: counts.hand_sets,
+            breakdown: counts.breakdown,
+            iterations,
+            mode,
         }
     }
 
-    pub fn new(count: PokerHandsCount, iterations: usize) -> Self {
-        Self { count, iterations }
+    pub fn probability(&self, hand: PokerHand) -> f64 {
+        if self.iterations == 0 {
+            return 0.0;
+        }
+
+        self.count[hand] as f64 / self.iterations as f64
+    }
+
+    /// Probability of `hand` being the best hand type the draw can play
+    pub fn best_probability(&self, hand: HandType) -> f64 {
+        if self.iterations == 0 {
+            return 0.0;
+        }
+
+        self.best[hand] as f64 / self.iterations as f64
+    }
+
+    /// Wilson score interval of `probability` for the normal quantile `z`,
+    /// e.g. 1.96 for 95%. Exact results have no sampling error.
+    pub fn confidence_interval(&self, hand: PokerHand, z: f64) -> (f64, f64) {
+        let p = self.probability(hand);
+        if self.mode == Mode::Exact {
+            return (p, p);
+        }
+
+        wilson_interval(p, self.iterations, z)
     }
 }
 
-pub struct DiscarderFactory {
+/// Samples draws for `hand`, with `R` generating the draws.
+pub struct DiscarderFactory<R: MonteCarloRng = Pcg64> {
     discarder: Discarder,
     hand: Vec<Card>,
+    rng: PhantomData<R>,
 }
 
-impl DiscarderFactory {
+impl<R: MonteCarloRng> DiscarderFactory<R> {
     pub fn new(discarder: Discarder, hand: Vec<Card>) -> Self {
-        Self { discarder, hand }
+        Self {
+            discarder,
+            hand,
+            rng: PhantomData,
+        }
     }
 }
 
-impl MonteCarloAlgorithmFactory for DiscarderFactory {
-    type Algorithm = DiscarderAlgorithm;
+impl<R: MonteCarloRng> MonteCarloAlgorithmFactory for DiscarderFactory<R> {
+    type Algorithm = DiscarderAlgorithm<R>;
+    type Rng = R;
 
-    fn make(&self, seed: u64) -> DiscarderAlgorithm {
+    fn make(&self, seed: u64) -> DiscarderAlgorithm<R> {
         let initial_hand_size = self.hand.len();
 
         assert!(
@@ -73,40 +191,94 @@ impl MonteCarloAlgorithmFactory for DiscarderFactory {
         let mut drawn_hand = vec![Card::invalid(); size];
         drawn_hand[..initial_hand_size].copy_from_slice(&self.hand);
 
+        let draw_size = self
+            .discarder
+            .max_hand_size
+            .saturating_sub(initial_hand_size);
+        let sampler = self.discarder.draw_sampler(draw_size);
+
         DiscarderAlgorithm {
             discarder: self.discarder.clone(),
             initial_hand_size,
+            allowed_hands: self.discarder.blind.allowed_hands(size),
             drawn_hand,
-            rng: rand_pcg::Pcg64::seed_from_u64(seed),
+            sampler,
+            batch: HandBatch::new(),
+            rng: R::seed_from_u64(seed),
         }
     }
 }
 
-pub struct DiscarderAlgorithm {
+pub struct DiscarderAlgorithm<R: MonteCarloRng = Pcg64> {
     discarder: Discarder,
     initial_hand_size: usize,
+    /// Hands the boss blind lets the player play from the drawn hand
+    allowed_hands: u16,
     drawn_hand: Vec<Card>,
-    rng: rand_pcg::Pcg64,
+    sampler: DrawSampler,
+    batch: HandBatch,
+    rng: R,
 }
 
-impl MonteCarloAlgorithm for DiscarderAlgorithm {
-    type Output = PokerHandsCount;
+impl<R: MonteCarloRng> MonteCarloAlgorithm for DiscarderAlgorithm<R> {
+    type Output = DiscardCounts;
 
     fn sample(&mut self, output: &mut Self::Output) {
+        self.record_optional_counts(output);
+        self.draw();
+        output.add_breakdown(&self.drawn_hand, self.allowed_hands, 1);
+
+        // Count poker hands in the complete drawn hand
+        let hands = HandEvaluator::contained(&self.drawn_hand);
+        output.add(hands & self.allowed_hands, 1);
+    }
+
+    /// Evaluates the drawn hands a `HandBatch` at a time
+    fn sample_batch(&mut self, count: usize, output: &mut Self::Output) {
+        self.record_optional_counts(output);
+
+        for _ in 0..count {
+            self.draw();
+            output.add_breakdown(&self.drawn_hand, self.allowed_hands, 1);
+            self.batch.push(&self.drawn_hand);
+
+            if self.batch.is_full() {
+                self.flush_batch(output);
+            }
+        }
+
+        if !self.batch.is_empty() {
+            self.flush_batch(output);
+        }
+    }
+}
+
+impl<R: MonteCarloRng> DiscarderAlgorithm<R> {
+    /// Fills the rest of the hand from the deck
+    #[inline]
+    fn draw(&mut self) {
         assert!(self.discarder.max_hand_size > self.initial_hand_size);
 
         let draw_slice = &mut self.drawn_hand
             [self.initial_hand_size..self.discarder.max_hand_size];
-        self.discarder.deck.sample_draw(&mut self.rng, draw_slice);
+        self.sampler.sample_draw(&mut self.rng, draw_slice);
+    }
 
-        // Count poker hands in the complete drawn hand
-        self.count_poker_hands(&self.drawn_hand, output);
+    fn record_optional_counts(&self, output: &mut DiscardCounts) {
+        if self.discarder.hand_sets && output.hand_sets.is_none() {
+            output.hand_sets = Some(HandSetHistogram::new());
+        }
+        if self.discarder.breakdown && output.breakdown.is_none() {
+            output.breakdown = Some(HandBreakdown::new());
+        }
     }
-}
 
-impl DiscarderAlgorithm {
-    fn count_poker_hands(&self, hand: &[Card], output: &mut PokerHandsCount) {
-        use crate::poker::PokerHandResolver;
-        PokerHandResolver::poker_hands(hand, output);
+    fn flush_batch(&mut self, output: &mut DiscardCounts) {
+        let evaluated = self.batch.evaluate();
+        for &hands in &evaluated[..self.batch.len()] {
+            output.add(hands & self.allowed_hands, 1);
+        }
+
+        self.batch.clear();
     }
 }
@@ -0,0 +1,342 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::{
+    deck::{Deck, DrawMethod, ParseCardError},
+    discarder::{Discarder, Mode},
+    montecarlo::RngKind,
+    poker::{BossBlind, PokerHand},
+};
+
+/// Normal quantile of the reported 95% confidence intervals
+pub(crate) const CONFIDENCE_Z: f64 = 1.96;
+
+/// Everything needed to compute discard odds, as sent by external tools.
+///
+/// ```json
+/// {
+///   "hand": "AS KH 10D",
+///   "deck": "standard",
+///   "hand_size": 8,
+///   "rules": { "boss_blind": { "the_eye": { "played": ["one_pair"] } } },
+///   "iterations": 100000,
+///   "seed": 43,
+///   "mode": "auto",
+///   "time_budget": 0.5,
+///   "rng": "pcg64",
+///   "draw_method": "auto"
+/// }
+/// ```
+///
+/// Only `hand` is required. `deck` takes card notation or a preset name, and
+/// still holds the cards in `hand`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AnalysisRequest {
+    pub hand: String,
+    #[serde(default = "AnalysisRequest::default_deck")]
+    pub deck: String,
+    #[serde(default = "AnalysisRequest::default_hand_size")]
+    pub hand_size: usize,
+    #[serde(default)]
+    pub rules: Rules,
+    #[serde(default = "AnalysisRequest::default_iterations")]
+    pub iterations: usize,
+    #[serde(default)]
+    pub seed: u64,
+    #[serde(default)]
+    pub mode: Mode,
+    /// Seconds
+    #[serde(default)]
+    pub time_budget: Option<f64>,
+    /// Generator for Monte Carlo draws
+    #[serde(default)]
+    pub rng: RngKind,
+    /// Algorithm for Monte Carlo draws
+    #[serde(default)]
+    pub draw_method: DrawMethod,
+}
+
+impl AnalysisRequest {
+    fn default_deck() -> String {
+        "standard".to_string()
+    }
+
+    fn default_hand_size() -> usize {
+        8
+    }
+
+    fn default_iterations() -> usize {
+        100_000
+    }
+}
+
+/// Round modifiers
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rules {
+    #[serde(default)]
+    pub boss_blind: BossBlind,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalysisResponse {
+    pub mode: Mode,
+    pub iterations: usize,
+    pub elapsed_seconds: f64,
+    pub hands: Vec<HandOdds>,
+}
+
+/// Odds of containing one poker hand after the draw
+#[derive(Debug, Clone, Serialize)]
+pub struct HandOdds {
+    pub hand: PokerHand,
+    pub count: i64,
+    pub probability: f64,
+    /// 95% Wilson score interval, a single point for exact results
+    pub confidence_interval: [f64; 2],
+}
+
+/// Why a request could not be answered
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AnalysisError {
+    pub message: String,
+    /// Offending card token, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// Byte offset of the problem in the offending field or document
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<usize>,
+}
+
+impl AnalysisError {
+    fn from_card(field: &str, error: ParseCardError) -> Self {
+        Self {
+            message: format!("{}: {}", field, error),
+            token: Some(error.token),
+            position: Some(error.position),
+        }
+    }
+}
+
+impl AnalysisRequest {
+    pub fn run(&self) -> Result<AnalysisResponse, AnalysisError> {
+        let deck = Deck::parse(&self.deck, self.seed)
+            .map_err(|e| AnalysisError::from_card("deck", e))?;
+        let hand = deck
+            .parse_hand(&self.hand)
+            .map_err(|e| AnalysisError::from_card("hand", e))?;
+
+        let time_budget = self
+            .time_budget
+            .filter(|s| s.is_finite() && *s > 0.0)
+            .map(Duration::try_from_secs_f64)
+            .transpose()
+            .map_err(|e| AnalysisError {
+                message: format!("time_budget: {}", e),
+                token: None,
+                position: None,
+            })?;
+
+        let discarder = Discarder::new(deck, self.hand_size, self.seed)
+            .with_hand_in_deck(true)
+            .with_mode(self.mode)
+            .with_time_budget(time_budget)
+            .with_boss_blind(self.rules.boss_blind.clone())
+            .with_rng(self.rng)
+            .with_draw_method(self.draw_method);
+
+        let (result, elapsed_seconds) =
+            timed(|| discarder.run(&hand, self.iterations, |_| {}));
+
+        let hands = PokerHand::iter()
+            .map(|poker_hand| {
+                let (low, high) =
+                    result.confidence_interval(poker_hand, CONFIDENCE_Z);
+                HandOdds {
+                    hand: poker_hand,
+                    count: result.count[poker_hand],
+                    probability: result.probability(poker_hand),
+                    confidence_interval: [low, high],
+                }
+            })
+            .collect();
+
+        Ok(AnalysisResponse {
+            mode: result.mode,
+            iterations: result.iterations,
+            elapsed_seconds,
+            hands,
+        })
+    }
+}
+
+/// Runs `f` and returns its wall time in seconds
+#[cfg(not(target_arch = "wasm32"))]
+fn timed<T>(f: impl FnOnce() -> T) -> (T, f64) {
+    let start = std::time::Instant::now();
+    let result = f();
+    (result, start.elapsed().as_secs_f64())
+}
+
+/// wasm32 has no `Instant`, use the host's clock instead
+#[cfg(target_arch = "wasm32")]
+fn timed<T>(f: impl FnOnce() -> T) -> (T, f64) {
+    let start = js_sys::Date::now();
+    let result = f();
+    (result, (js_sys::Date::now() - start) / 1000.0)
+}
+
+/// Answers a JSON `AnalysisRequest` with a JSON `AnalysisResponse`, or an
+/// object with a single `error` field holding an `AnalysisError`.
+pub fn run_json(request: &str) -> String {
+    let response = serde_json::from_str::<AnalysisRequest>(request)
+        .map_err(|e| AnalysisError {
+            message: e.to_string(),
+            token: None,
+            position: line_column_offset(request, e.line(), e.column()),
+        })
+        .and_then(|request| request.run());
+
+    match response {
+        Ok(response) => serde_json::to_string(&response),
+        Err(error) => serde_json::to_string(&ErrorResponse { error }),
+    }
+    .expect("responses always serialize")
+}
+
+/// Same as `run_json` for a request that may not be valid UTF-8
+pub fn run_json_bytes(request: &[u8]) -> String {
+    match std::str::from_utf8(request) {
+        Ok(request) => run_json(request),
+        Err(e) => {
+            let position = e.valid_up_to();
+            let error = AnalysisError {
+                message: format!("invalid UTF-8 in request at {}", position),
+                token: None,
+                position: Some(position),
+            };
+
+            serde_json::to_string(&ErrorResponse { error })
+                .expect("responses always serialize")
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: AnalysisError,
+}
+
+/// Byte offset of a 1-based line and column, as reported by serde_json
+fn line_column_offset(s: &str, line: usize, column: usize) -> Option<usize> {
+    if line == 0 {
+        return None;
+    }
+
+    let line_start: usize =
+        s.split_inclusive('\n').take(line - 1).map(str::len).sum();
+    Some((line_start + column.saturating_sub(1)).min(s.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn run(request: &str) -> Value {
+        serde_json::from_str(&run_json(request)).unwrap()
+    }
+
+    #[test]
+    fn test_exact_request() {
+        let response = run(r#"{"hand": "2S 3S 4S 5S 6S 7S 8S", "seed": 43}"#);
+
+        assert_eq!(response["mode"], "exact");
+        assert_eq!(response["iterations"], 45);
+
+        let hands = response["hands"].as_array().unwrap();
+        assert_eq!(hands.len(), 10);
+        assert_eq!(hands[0]["hand"], "high_card");
+        assert_eq!(hands[0]["probability"], 1.0);
+        assert_eq!(hands[1]["hand"], "one_pair");
+        assert_eq!(hands[1]["count"], 21);
+        assert_eq!(hands[1]["confidence_interval"][0], 21.0 / 45.0);
+    }
+
+    #[test]
+    fn test_montecarlo_request() {
+        let response = run(r#"{
+            "hand": "AS 10H",
+            "deck": "abandoned",
+            "iterations": 2000,
+            "mode": "monte_carlo",
+            "rng": "xoshiro256_plus_plus",
+            "rules": { "boss_blind": { "the_mouth": { "hand": "flush" } } }
+        }"#);
+
+        assert_eq!(response["mode"], "monte_carlo");
+        assert_eq!(response["iterations"], 2000);
+        assert!(response["elapsed_seconds"].as_f64().unwrap() >= 0.0);
+
+        let hands = response["hands"].as_array().unwrap();
+        assert_eq!(hands[0]["count"], 0);
+
+        let flush = &hands[5];
+        assert_eq!(flush["hand"], "flush");
+        let p = flush["probability"].as_f64().unwrap();
+        let low = flush["confidence_interval"][0].as_f64().unwrap();
+        let high = flush["confidence_interval"][1].as_f64().unwrap();
+        assert!(p > 0.0);
+        assert!(low < p && p < high);
+    }
+
+    #[test]
+    fn test_errors() {
+        let response = run(r#"{"hand": "AS KX"}"#);
+        assert_eq!(response["error"]["token"], "KX");
+        assert_eq!(response["error"]["position"], 3);
+        assert_eq!(
+            response["error"]["message"],
+            "hand: invalid suit in `KX` at 3"
+        );
+
+        let response = run(r#"{"hand": "AS", "deck": "huge"}"#);
+        assert_eq!(response["error"]["token"], "huge");
+
+        let response = run(r#"{"hand": "AS KH", "deck": "abandoned"}"#);
+        assert_eq!(response["error"]["token"], "KH");
+        assert_eq!(response["error"]["position"], 3);
+        assert_eq!(
+            response["error"]["message"],
+            "hand: not in the deck in `KH` at 3"
+        );
+
+        let response = run("{\n\"hand\": \"AS\",\n\"bogus\": 1}");
+        assert!(response["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("bogus"));
+        assert!(response["error"]["position"].as_u64().unwrap() >= 14);
+
+        let response = run("not json");
+        assert!(response["error"]["position"].as_u64().unwrap() < 8);
+
+        let response: Value =
+            serde_json::from_str(&run_json_bytes(b"{\"hand\": \"A\xff\"}"))
+                .unwrap();
+        assert_eq!(response["error"]["position"], 11);
+        assert_eq!(
+            response["error"]["message"],
+            "invalid UTF-8 in request at 11"
+        );
+
+        let response = run(r#"{"hand": "AS", "time_budget": 1e30}"#);
+        assert!(response["error"]["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("time_budget: "));
+    }
+}
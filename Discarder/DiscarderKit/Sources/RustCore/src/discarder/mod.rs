@@ -1,70 +1,282 @@
 mod algorithm;
-use algorithm::*;
+mod analysis;
+mod mode;
+mod objective;
+mod scoring;
+mod what_if;
+pub use algorithm::*;
+pub use analysis::*;
+pub use mode::*;
+pub use objective::*;
+pub use scoring::Jokers;
 
 use crate::{
-    deck::Card,
-    montecarlo::{MonteCarlo, MonteCarloConfiguration},
-    poker::{PokerHandResolver, PokerHandsCount},
+    deck::{
+        suit_symmetries, Card, DrawMethod, DrawSampler, ParseCardError,
+        SuitPermutation,
+    },
+    montecarlo::{
+        with_rng, MonteCarlo, MonteCarloConfiguration, MonteCarloRng, RngKind,
+    },
+    poker::{BossBlind, PokerHandResolver},
     utils::combinations,
 };
 
 use super::deck::Deck;
-use itertools::Itertools;
+use std::time::Duration;
+
+/// Samples every Monte Carlo thread takes between progress reports
+const CHUNK_SIZE: usize = 1000000;
 
 #[derive(Clone)]
 pub struct Discarder {
     deck: Deck,
     max_hand_size: usize,
     seed: u64,
+    mode: Mode,
+    time_budget: Option<Duration>,
+    precision: Option<f64>,
+    threads: usize,
+    blind: BossBlind,
+    rng: RngKind,
+    draw_method: DrawMethod,
+    hand_sets: bool,
+    breakdown: bool,
+    hand_in_deck: bool,
 }
 
 impl Discarder {
     pub fn new(deck: Deck, hand_size: usize, seed: u64) -> Self {
+        use std::thread::available_parallelism;
+
         Self {
             deck,
             max_hand_size: hand_size,
             seed,
+            mode: Mode::Auto,
+            time_budget: None,
+            precision: None,
+            threads: available_parallelism().map_or(1, |n| n.get()),
+            blind: BossBlind::None,
+            rng: RngKind::default(),
+            draw_method: DrawMethod::default(),
+            hand_sets: false,
+            breakdown: false,
+            hand_in_deck: false,
         }
     }
 
+    pub fn with_mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Limits the time spent on sampling, and lets `Mode::Auto` pick
+    /// enumeration whenever it fits into the budget. The budget is turned
+    /// into iterations with the `CostModel`'s estimated timings rather than
+    /// enforced on the clock, and always allows one sample per thread.
+    pub fn with_time_budget(mut self, time_budget: Option<Duration>) -> Self {
+        self.time_budget = time_budget;
+        self
+    }
+
+    /// Stops sampling once every estimated probability reaches the standard
+    /// error `precision`, so `Mode::Auto` compares enumeration against the
+    /// samples actually needed rather than `max_iterations`.
+    pub fn with_precision(mut self, precision: Option<f64>) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Analyzes the round against `blind`. Debuffs do not change which hands
+    /// are drawn, so suit symmetries still apply.
+    pub fn with_boss_blind(mut self, blind: BossBlind) -> Self {
+        self.blind = blind;
+        self
+    }
+
+    /// Generator for Monte Carlo draws, exact mode does not sample.
+    pub fn with_rng(mut self, rng: RngKind) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    /// Algorithm drawing the cards in Monte Carlo mode, `DrawMethod::Auto`
+    /// by default. `DrawMethod::Reservoir` replays seeded results recorded
+    /// before. Draws `method` cannot take fall back to `DrawMethod::Auto`.
+    pub fn with_draw_method(mut self, method: DrawMethod) -> Self {
+        self.draw_method = method;
+        self
+    }
+
+    /// Also records which poker hands each draw contains together, see
+    /// `DiscardResult::hand_sets`.
+    pub fn with_hand_sets(mut self, hand_sets: bool) -> Self {
+        self.hand_sets = hand_sets;
+        self
+    }
+
+    /// Also records which ranks and suits can take part in each hand type,
+    /// see `DiscardResult::breakdown`. Exact mode then enumerates every
+    /// draw, as suit symmetries would mix up the suits, and the cost model
+    /// adds the work of breaking down each drawn hand.
+    pub fn with_breakdown(mut self, breakdown: bool) -> Self {
+        self.breakdown = breakdown;
+        self
+    }
+
+    /// Whether the deck still holds the cards in hand, as when both are
+    /// written in card notation. Every run then draws from the deck without
+    /// the held cards, see `parse_hand`.
+    pub fn with_hand_in_deck(mut self, hand_in_deck: bool) -> Self {
+        self.hand_in_deck = hand_in_deck;
+        self
+    }
+
+    /// Parses a hand in card notation. If the deck still holds the hand,
+    /// every card has to be in it; runs only take the cards found out of
+    /// the deck.
+    pub fn parse_hand(&self, s: &str) -> Result<Vec<Card>, ParseCardError> {
+        match self.hand_in_deck {
+            true => self.deck.parse_hand(s),
+            false => Card::make_hand(s),
+        }
+    }
+
+    /// Copy drawing from the deck without the cards of `hand`
+    fn without_hand(&self, hand: &[Card]) -> Self {
+        let mut discarder = self.clone();
+        discarder.hand_in_deck = false;
+
+        let cards = &mut discarder.deck.cards;
+        for card in hand {
+            if let Some(index) = cards.iter().position(|c| c == card) {
+                cards.remove(index);
+            }
+        }
+
+        discarder
+    }
+
+    pub fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+
+    pub fn set_time_budget(&mut self, time_budget: Option<Duration>) {
+        self.time_budget = time_budget;
+    }
+
+    pub fn set_precision(&mut self, precision: Option<f64>) {
+        self.precision = precision;
+    }
+
+    pub fn set_rng(&mut self, rng: RngKind) {
+        self.rng = rng;
+    }
+
+    pub fn set_boss_blind(&mut self, blind: BossBlind) {
+        self.blind = blind;
+    }
+
     pub fn run(
         &self,
         hand: &[Card],
         max_iterations: usize,
         progress_handler: impl Fn(&DiscardProgress),
     ) -> DiscardResult {
+        if self.hand_in_deck {
+            return self.without_hand(hand).run(
+                hand,
+                max_iterations,
+                progress_handler,
+            );
+        }
+
         let hand_size = hand.len();
 
         if hand_size >= self.max_hand_size {
             // No need to draw anything, just count the hands
-            let mut count = PokerHandsCount::default();
-            PokerHandResolver::poker_hands(hand, &mut count);
-            return DiscardResult::new(count, 1);
+            let mut counts = DiscardCounts::new(self.hand_sets, self.breakdown);
+            counts.add(
+                PokerHandResolver::contained_with_blind(hand, &self.blind),
+                1,
+            );
+            counts.add_breakdown(hand, self.blind.allowed_hands(hand_size), 1);
+            return DiscardResult::new(counts, 1, Mode::Exact);
+        }
+
+        let mut symmetries = suit_symmetries(hand, &self.deck);
+        if self.breakdown {
+            symmetries.retain(|p| *p == SuitPermutation::identity());
         }
 
+        // Exact mode only evaluates one draw per suit symmetry orbit
         let combinations =
-            combinations(self.deck.cards.len(), self.max_hand_size - hand_size);
-
-        let max_combinations = 30_000;
-
-        if let Some(combinations) = combinations {
-            if combinations <= max_combinations {
-                println!("Combinations: {}", combinations);
-                return self.run_combinations(hand);
-            } else {
-                println!(
-                    "Too many combinations ({} > {}), doing montecarlo",
-                    combinations, max_combinations
-                );
-            }
-        } else {
-            println!("Too many combinations, not fitting in usize");
+            combinations(self.deck.cards.len(), self.max_hand_size - hand_size)
+                .map(|c| c / symmetries.len());
+
+        let mut cost_model = CostModel::new(self.threads);
+        if self.breakdown {
+            let nanos = CostModel::breakdown_nanos(self.max_hand_size);
+            cost_model = cost_model.with_evaluation(nanos);
         }
+        let (mode, iterations) = self.select_mode(
+            hand_size,
+            combinations,
+            max_iterations,
+            cost_model,
+        );
 
-        self.run_montecarlo(hand, max_iterations, progress_handler)
+        match mode {
+            Mode::Exact => self.run_combinations(hand, &symmetries),
+            _ => self.run_montecarlo(hand, iterations, progress_handler),
+        }
     }
 
-    fn run_combinations(&self, hand: &[Card]) -> DiscardResult {
+    /// Resolves the mode and the number of Monte Carlo iterations for a hand
+    /// of `hand_size`, given how many draws exact mode would evaluate.
+    fn select_mode(
+        &self,
+        hand_size: usize,
+        exact_draws: Option<usize>,
+        max_iterations: usize,
+        cost_model: CostModel,
+    ) -> (Mode, usize) {
+        let mut iterations = max_iterations;
+        if let Some(precision) = self.precision {
+            iterations =
+                iterations.min(CostModel::precision_iterations(precision));
+        }
+        if let Some(budget) = self.time_budget {
+            let budget_iterations =
+                cost_model.montecarlo_iterations(budget).max(self.threads);
+            iterations = iterations.min(budget_iterations);
+        }
+
+        let mode = match self.mode {
+            // Not enough cards left to sample from, draw the whole deck
+            _ if self.deck.cards.len() < self.max_hand_size - hand_size => {
+                Mode::Exact
+            }
+            Mode::Auto => {
+                cost_model.select(exact_draws, iterations, self.time_budget)
+            }
+            mode => mode,
+        };
+
+        (mode, iterations)
+    }
+
+    fn run_combinations(
+        &self,
+        hand: &[Card],
+        symmetries: &[SuitPermutation],
+    ) -> DiscardResult {
         let hand_size = hand.len();
         assert!(hand_size < self.max_hand_size);
 
@@ -73,26 +285,59 @@ impl Discarder {
             self.deck.cards.len(),
         );
 
-        let mut result = PokerHandsCount::default();
-        let mut drawn_hand = vec![Card::invalid(); hand_size + cards_to_draw];
-
-        // Copy initial hand
-        drawn_hand[..hand_size].copy_from_slice(hand);
+        let allowed = self.blind.allowed_hands(hand_size + cards_to_draw);
+        let mut result = DiscardCounts::new(self.hand_sets, self.breakdown);
+        let mut drawn_hand = Vec::with_capacity(hand_size + cards_to_draw);
 
-        // Iterate over all possible combinations
+        // Iterate over one draw per orbit, weighted by the orbit size
         let mut iterations = 0;
-        for combination in self.deck.cards.iter().combinations(cards_to_draw) {
-            // Draw cards according to current combination
-            for (i, &card) in combination.iter().enumerate() {
-                drawn_hand[hand_size + i] = *card;
-            }
+        self.deck
+            .canonical_draws(cards_to_draw, symmetries, |draw, weight| {
+                drawn_hand.clear();
+                drawn_hand.extend_from_slice(hand);
+                drawn_hand.extend_from_slice(draw);
+
+                // Count poker hands
+                let hands = PokerHandResolver::contained_with_blind(
+                    &drawn_hand,
+                    &self.blind,
+                );
+                result.add(hands, weight as i64);
+                result.add_breakdown(&drawn_hand, allowed, weight as i64);
+                iterations += weight;
+            });
 
-            // Count poker hands
-            PokerHandResolver::poker_hands(&drawn_hand, &mut result);
-            iterations += 1;
-        }
+        DiscardResult::new(result, iterations, Mode::Exact)
+    }
+
+    /// Monte Carlo engine sampling draws for `hand`, for callers stepping
+    /// through the sampling themselves. Draws come from `R`, regardless of
+    /// `with_rng`.
+    pub fn montecarlo<R: MonteCarloRng>(
+        &self,
+        hand: &[Card],
+    ) -> MonteCarlo<DiscarderFactory<R>> {
+        let discarder = match self.hand_in_deck {
+            true => self.without_hand(hand),
+            false => self.clone(),
+        };
+        let factory = DiscarderFactory::new(discarder, hand.to_vec());
 
-        DiscardResult::new(result, iterations)
+        let configuration = MonteCarloConfiguration {
+            threads: self.threads,
+            chunk_size: CHUNK_SIZE,
+        };
+
+        MonteCarlo::new(factory, configuration, self.seed)
+    }
+
+    /// Sampler drawing `draw_size` cards from the deck
+    fn draw_sampler(&self, draw_size: usize) -> DrawSampler {
+        if self.draw_method.supports(draw_size) {
+            DrawSampler::with_method(&self.deck, draw_size, self.draw_method)
+        } else {
+            DrawSampler::new(&self.deck, draw_size)
+        }
     }
 
     fn run_montecarlo(
@@ -101,16 +346,20 @@ impl Discarder {
         max_iterations: usize,
         progress_handler: impl Fn(&DiscardProgress),
     ) -> DiscardResult {
-        use std::thread::available_parallelism;
-
-        let factory = DiscarderFactory::new(self.clone(), hand.to_vec());
-
-        let configuration = MonteCarloConfiguration {
-            threads: available_parallelism().unwrap().get(),
-            chunk_size: 1000000,
-        };
+        with_rng!(self.rng, R => self.run_montecarlo_with::<R>(
+            hand,
+            max_iterations,
+            progress_handler,
+        ))
+    }
 
-        let mut mc = MonteCarlo::new(factory, configuration, self.seed);
+    fn run_montecarlo_with<R: MonteCarloRng>(
+        &self,
+        hand: &[Card],
+        max_iterations: usize,
+        progress_handler: impl Fn(&DiscardProgress),
+    ) -> DiscardResult {
+        let mut mc = self.montecarlo::<R>(hand);
 
         let result = mc.run(max_iterations, |progress| {
             progress_handler(&DiscardProgress::new(progress));
@@ -122,7 +371,10 @@ impl Discarder {
 
 #[cfg(test)]
 mod tests {
-    use crate::poker::PokerHandsCount;
+    use crate::{
+        deck::{Rank, Suit},
+        poker::{BestHandsCount, HandType, PokerHand, PokerHandsCount},
+    };
 
     use super::*;
 
@@ -133,7 +385,34 @@ mod tests {
             "",
             10000,
             10000,
-            &[10000, 8865, 4380, 1167, 1020, 758, 609, 29, 6, 0],
+            &[10000, 8862, 4399, 1174, 987, 686, 613, 32, 7, 1],
+        );
+    }
+
+    #[test]
+    fn test_full_draw_exact() {
+        // Every eight card hand, one per suit orbit weighted by its size.
+        // E.g. 4 x C(47, 3) hands hold a royal flush.
+        let discarder =
+            Discarder::new(Deck::make_standard(), 8, 43).with_mode(Mode::Exact);
+        let result = discarder.run(&[], 10000, |_| {});
+
+        assert_eq!(result.mode, Mode::Exact);
+        assert_eq!(result.iterations, 752_538_150);
+        assert_eq!(
+            result.count,
+            PokerHandsCount::from_array(&[
+                752_538_150,
+                668_193_318,
+                330_813_990,
+                90_361_206,
+                73_870_336,
+                52_406_640,
+                46_541_430,
+                2_529_462,
+                611_340,
+                64_860
+            ])
         );
     }
 
@@ -166,7 +445,7 @@ mod tests {
             "2S 3S 4S 5S",
             10000,
             10000,
-            &[10000, 8784, 4077, 1093, 5026, 6940, 514, 32, 1479, 0],
+            &[10000, 8784, 4236, 1061, 5042, 6951, 494, 32, 1502, 0],
         );
     }
 
@@ -230,6 +509,368 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_full_deck_exact() {
+        // Textbook five card poker frequencies, counted by containment
+        let discarder =
+            Discarder::new(Deck::make_standard(), 5, 43).with_mode(Mode::Exact);
+        let result = discarder.run(&[], 10000, |_| {});
+
+        assert_eq!(result.mode, Mode::Exact);
+        assert_eq!(result.iterations, 2_598_960);
+        assert_eq!(
+            result.count,
+            PokerHandsCount::from_array(&[
+                2_598_960, 1_281_072, 127_920, 59_280, 10_240, 5_148, 3_744,
+                624, 40, 4
+            ])
+        );
+    }
+
+    #[test]
+    fn test_mode_override() {
+        let hand = Card::make_hand("2S 3S 4S 5S 6S 7S 8S").unwrap();
+        let discarder =
+            Discarder::new(Deck::make_standard(), 8, 43).with_threads(4);
+
+        let result = discarder.run(&hand, 10000, |_| {});
+        assert_eq!(result.mode, Mode::Exact);
+        assert_eq!(result.iterations, 52);
+
+        let result = discarder.clone().with_mode(Mode::MonteCarlo).run(
+            &hand,
+            10000,
+            |_| {},
+        );
+        assert_eq!(result.mode, Mode::MonteCarlo);
+        assert_eq!(result.iterations, 10000);
+
+        let hand = Card::make_hand("2S 3S 4S 5S 6S 7S").unwrap();
+        let result = discarder.run(&hand, 10, |_| {});
+        assert_eq!(result.mode, Mode::MonteCarlo);
+
+        let result =
+            discarder
+                .clone()
+                .with_mode(Mode::Exact)
+                .run(&hand, 10, |_| {});
+        assert_eq!(result.mode, Mode::Exact);
+        assert_eq!(result.iterations, 1326);
+    }
+
+    #[test]
+    fn test_time_budget_limits_iterations() {
+        let discarder = Discarder::new(Deck::make_standard(), 8, 43)
+            .with_threads(4)
+            .with_time_budget(Some(Duration::from_millis(1)));
+
+        let expected =
+            CostModel::new(4).montecarlo_iterations(Duration::from_millis(1));
+        let result = discarder.run(&[], CHUNK_SIZE, |_| {});
+        assert_eq!(result.mode, Mode::MonteCarlo);
+        assert_eq!(result.iterations, expected);
+        assert!(expected < CHUNK_SIZE / 100);
+
+        // Even an empty budget samples once per thread
+        let result = discarder
+            .with_time_budget(Some(Duration::from_nanos(1)))
+            .run(&[], CHUNK_SIZE, |_| {});
+        assert_eq!(result.iterations, 4);
+    }
+
+    #[test]
+    fn test_breakdown_cost() {
+        let budget = Duration::from_millis(1);
+        let discarder = Discarder::new(Deck::make_standard(), 8, 43)
+            .with_threads(4)
+            .with_time_budget(Some(budget));
+
+        // Breaking down every draw leaves time for fewer of them
+        let plain = discarder.run(&[], CHUNK_SIZE, |_| {});
+        let result =
+            discarder.with_breakdown(true).run(&[], CHUNK_SIZE, |_| {});
+        let expected = CostModel::new(4)
+            .with_evaluation(CostModel::breakdown_nanos(8))
+            .montecarlo_iterations(budget);
+        assert_eq!(result.iterations, expected);
+        assert!(result.iterations < plain.iterations / 10);
+    }
+
+    #[test]
+    fn test_precision_limits_iterations() {
+        let discarder = Discarder::new(Deck::make_standard(), 8, 43)
+            .with_threads(4)
+            .with_precision(Some(0.01));
+
+        let result = discarder.run(&[], CHUNK_SIZE, |_| {});
+        assert_eq!(result.mode, Mode::MonteCarlo);
+        assert_eq!(result.iterations, 2500);
+    }
+
+    #[test]
+    fn test_boss_blind() {
+        let hand = Card::make_hand("2S 3S 4S 5S 6S 7S").unwrap();
+        let discarder = Discarder::new(Deck::make_standard(), 8, 43)
+            .with_threads(4)
+            .with_mode(Mode::Exact);
+
+        let result = discarder.run(&hand, 10000, |_| {});
+        let blind = BossBlind::TheMouth {
+            hand: Some(PokerHand::Flush),
+        };
+        let restricted =
+            discarder.with_boss_blind(blind).run(&hand, 10000, |_| {});
+
+        let mut expected = PokerHandsCount::new();
+        expected[PokerHand::Flush] = result.count[PokerHand::Flush];
+        assert_eq!(restricted.iterations, 1326);
+        assert_eq!(restricted.count, expected);
+    }
+
+    #[test]
+    fn test_hand_sets() {
+        let hand = Card::make_hand("2S 3S 4S 5S 9H").unwrap();
+        let discarder = Discarder::new(Deck::make_standard(), 8, 43)
+            .with_threads(4)
+            .with_hand_sets(true);
+
+        for mode in [Mode::Exact, Mode::MonteCarlo] {
+            let result =
+                discarder.clone().with_mode(mode).run(&hand, 20000, |_| {});
+            let hand_sets = result.hand_sets.unwrap();
+
+            assert_eq!(hand_sets.total(), result.iterations as i64);
+            assert_eq!(hand_sets.marginals(), result.count);
+
+            // Straight flushes are both straights and flushes
+            let either = hand_sets
+                .count_any(PokerHand::Straight.bit() | PokerHand::Flush.bit());
+            assert_eq!(
+                either,
+                result.count[PokerHand::Straight]
+                    + result.count[PokerHand::Flush]
+                    - hand_sets.count_all(
+                        PokerHand::Straight.bit() | PokerHand::Flush.bit()
+                    )
+            );
+            assert_eq!(
+                hand_sets.count_matching(
+                    PokerHand::Flush.bit(),
+                    PokerHand::StraightFlush.bit()
+                ),
+                result.count[PokerHand::Flush]
+                    - result.count[PokerHand::StraightFlush]
+            );
+        }
+
+        let result = discarder.with_hand_sets(false).run(&hand, 100, |_| {});
+        assert!(result.hand_sets.is_none());
+    }
+
+    #[test]
+    fn test_breakdown() {
+        let rank = |c| Rank {
+            value: Rank::from_char(c).unwrap(),
+        };
+        let suit = |c| Suit {
+            value: Suit::from_char(c).unwrap(),
+        };
+
+        let hand = Card::make_hand("2S 3S 4S 5S 9H").unwrap();
+        let discarder = Discarder::new(Deck::make_standard(), 6, 43)
+            .with_mode(Mode::Exact)
+            .with_breakdown(true);
+
+        // An Ace or a Six completes the straight, a Spade the flush
+        let result = discarder.run(&hand, 100, |_| {});
+        let breakdown = result.breakdown.unwrap();
+        assert_eq!(result.iterations, 52);
+        assert_eq!(result.count[PokerHand::Straight], 8);
+        assert_eq!(breakdown.with_rank(HandType::Straight, rank('2')), 8);
+        assert_eq!(breakdown.with_rank(HandType::Straight, rank('A')), 4);
+        assert_eq!(breakdown.with_rank(HandType::Straight, rank('9')), 0);
+        assert_eq!(breakdown.with_suit(HandType::Flush, suit('S')), 13);
+        assert_eq!(breakdown.with_suit(HandType::Flush, suit('H')), 0);
+        assert_eq!(breakdown.with_suit(HandType::Straight, suit('H')), 2);
+
+        // Sampled draws break down the same way
+        let result = discarder.with_mode(Mode::MonteCarlo).with_threads(4).run(
+            &hand,
+            5000,
+            |_| {},
+        );
+        let breakdown = result.breakdown.unwrap();
+        assert_eq!(
+            breakdown.with_suit(HandType::Flush, suit('S')),
+            result.count[PokerHand::Flush]
+        );
+        assert_eq!(
+            breakdown.with_rank(HandType::Straight, rank('3')),
+            result.count[PokerHand::Straight]
+        );
+    }
+
+    #[test]
+    fn test_best_hands() {
+        use strum::IntoEnumIterator;
+
+        let hand = Card::make_hand("2S 3S 4S 5S 9H").unwrap();
+        let discarder = Discarder::new(Deck::make_standard(), 8, 43)
+            .with_threads(4)
+            .with_hand_sets(true);
+
+        for mode in [Mode::Exact, Mode::MonteCarlo] {
+            let result =
+                discarder.clone().with_mode(mode).run(&hand, 20000, |_| {});
+
+            // A standard deck holds no secret hands
+            let best = result.hand_sets.as_ref().unwrap().best_hands();
+            for hand in PokerHand::iter() {
+                assert_eq!(result.best[HandType::from(hand)], best[hand]);
+            }
+            assert_eq!(result.best.total(), result.iterations as i64);
+
+            let total: f64 =
+                HandType::iter().map(|h| result.best_probability(h)).sum();
+            assert!((total - 1.0).abs() < 1e-9);
+        }
+
+        // Duplicates of the Ace of Spades make secret hands
+        let deck = Deck::new(&Card::make_hand("AS AS AS KS KH QD").unwrap());
+        let hand = Card::make_hand("AS AS").unwrap();
+        let result = Discarder::new(deck, 5, 43).with_mode(Mode::Exact).run(
+            &hand,
+            100,
+            |_| {},
+        );
+
+        assert_eq!(result.iterations, 20);
+        assert_eq!(
+            result.best,
+            BestHandsCount::from_array(&[
+                0, 0, 1, 6, 0, 0, 3, 9, 0, 0, 0, 0, 1
+            ])
+        );
+
+        // The Mouth locks secret hands out along with every other type
+        let blind = BossBlind::TheMouth {
+            hand: Some(PokerHand::ThreeOfAKind),
+        };
+        let deck = Deck::new(&Card::make_hand("AS AS AS AS AS").unwrap());
+        let result = Discarder::new(deck, 5, 43).with_boss_blind(blind).run(
+            &[],
+            100,
+            |_| {},
+        );
+        assert_eq!(result.best.total(), 1);
+        assert_eq!(result.best[HandType::ThreeOfAKind], 1);
+    }
+
+    #[test]
+    fn test_rng_kinds() {
+        use crate::utils::wilson_interval;
+        use strum::IntoEnumIterator;
+
+        let hand = Card::make_hand("2S 3S 4S 5S").unwrap();
+        let discarder = Discarder::new(Deck::make_standard(), 8, 43)
+            .with_threads(4)
+            .with_mode(Mode::MonteCarlo);
+
+        let exact = discarder.clone().with_mode(Mode::Exact);
+        let exact = exact.run(&hand, 0, |_| {});
+
+        let run =
+            |rng| discarder.clone().with_rng(rng).run(&hand, 20000, |_| {});
+        let reference = run(RngKind::Pcg64);
+
+        for rng in RngKind::iter() {
+            let result = run(rng);
+            if rng != RngKind::Pcg64 {
+                assert_ne!(result.count, reference.count, "{:?}", rng);
+            }
+
+            // A royal flush is out of reach, and never drawn
+            for hand in PokerHand::iter() {
+                let sampled = result.probability(hand);
+                let (low, high) = wilson_interval(sampled, 20000, 3.29);
+                let p = exact.probability(hand);
+                assert!(
+                    p == sampled || low <= p && p <= high,
+                    "{:?} {:?}",
+                    rng,
+                    hand
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_draw_methods() {
+        use strum::IntoEnumIterator;
+
+        let discarder = Discarder::new(Deck::make_standard(), 8, 43)
+            .with_threads(4)
+            .with_mode(Mode::MonteCarlo);
+        let run = |hand: &str, method| {
+            let hand = Card::make_hand(hand).unwrap();
+            discarder
+                .clone()
+                .with_draw_method(method)
+                .run(&hand, 20000, |_| {})
+        };
+
+        for hand in ["", "2S 3S 4S 5S"] {
+            let reference = run(hand, DrawMethod::Reservoir);
+
+            for method in
+                [DrawMethod::Auto, DrawMethod::FisherYates, DrawMethod::Floyd]
+            {
+                let result = run(hand, method);
+                assert_eq!(result.iterations, 20000);
+                assert_ne!(result.count, reference.count);
+
+                for poker_hand in PokerHand::iter() {
+                    let delta = result.probability(poker_hand)
+                        - reference.probability(poker_hand);
+                    assert!(
+                        delta.abs() < 0.02,
+                        "{:?} {:?}",
+                        method,
+                        poker_hand
+                    );
+                }
+            }
+        }
+    }
+
+    /// Sampling throughput per generator, run with
+    /// `cargo test --release rng_throughput -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn rng_throughput() {
+        use std::time::Instant;
+        use strum::IntoEnumIterator;
+
+        let hand = Card::make_hand("2S 3S 4S 5S").unwrap();
+        let iterations = 10_000_000;
+
+        for rng in RngKind::iter() {
+            let discarder = Discarder::new(Deck::make_standard(), 8, 43)
+                .with_mode(Mode::MonteCarlo)
+                .with_rng(rng);
+
+            let start = Instant::now();
+            discarder.run(&hand, iterations, |_| {});
+            let seconds = start.elapsed().as_secs_f64();
+
+            println!(
+                "{:?}: {:.1}M samples/s",
+                rng,
+                iterations as f64 / seconds / 1e6
+            );
+        }
+    }
+
     fn perform_test(
         discarder: &Discarder,
         hand_string: &str,
@@ -239,6 +880,9 @@ mod tests {
     ) {
         let hand = Card::make_hand(hand_string).unwrap();
 
+        // Pin the thread count so Monte Carlo results are reproducible
+        let discarder = discarder.clone().with_threads(14);
+
         let result = discarder.run(&hand, max_iterations, |_progress| {
             // println!("Result: {:?}", result);
         });
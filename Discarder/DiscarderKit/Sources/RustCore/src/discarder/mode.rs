@@ -0,0 +1,205 @@
+use std::time::Duration;
+
+use crate::{scoring::MAX_PLAYED_CARDS, utils::combinations};
+
+/// Strategy used to compute discard odds.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    /// Pick between exact enumeration and sampling using the cost model.
+    #[default]
+    Auto,
+    /// Enumerate every possible draw.
+    Exact,
+    /// Sample draws with the multithreaded Monte Carlo engine.
+    MonteCarlo,
+}
+
+/// Rough per-hand timings used to choose between enumeration and sampling.
+///
+/// Exact enumeration runs on a single thread and allocates a combination
+/// per draw, while sampling is allocation free but spread over `threads`.
+/// A sample is roughly an order of magnitude slower than an enumerated hand
+/// because of the reservoir draw; the absolute numbers match ~28M samples per
+/// second on a 12-core machine.
+///
+/// Runs doing more than counting poker hands add their own work on every
+/// drawn hand, which costs the same in both modes.
+#[derive(Debug, Clone, Copy)]
+pub struct CostModel {
+    pub exact_nanos_per_hand: f64,
+    pub montecarlo_nanos_per_sample: f64,
+    pub evaluation_nanos_per_hand: f64,
+    pub threads: usize,
+}
+
+impl CostModel {
+    pub fn new(threads: usize) -> Self {
+        Self {
+            exact_nanos_per_hand: 40.0,
+            montecarlo_nanos_per_sample: 420.0,
+            evaluation_nanos_per_hand: 0.0,
+            threads: threads.max(1),
+        }
+    }
+
+    /// Adds `nanos` of work on every drawn hand
+    pub fn with_evaluation(mut self, nanos: f64) -> Self {
+        self.evaluation_nanos_per_hand += nanos;
+        self
+    }
+
+    /// Work of `Scorer::best_play`, which scores every play of a hand of
+    /// `hand_size` cards
+    pub fn scoring_nanos(hand_size: usize) -> f64 {
+        340.0 * Self::plays(hand_size) as f64
+    }
+
+    /// Work of `HandBreakdown::add`, which looks at every play of a hand of
+    /// `hand_size` cards
+    pub fn breakdown_nanos(hand_size: usize) -> f64 {
+        36.0 * Self::plays(hand_size) as f64
+    }
+
+    pub fn exact_time(&self, combinations: usize) -> Duration {
+        let nanos = self.exact_nanos_per_hand + self.evaluation_nanos_per_hand;
+        Self::duration(combinations as f64 * nanos)
+    }
+
+    pub fn montecarlo_time(&self, iterations: usize) -> Duration {
+        Self::duration(
+            iterations as f64 * self.nanos_per_sample() / self.threads as f64,
+        )
+    }
+
+    /// Number of samples estimated to fit into `budget` on all threads.
+    pub fn montecarlo_iterations(&self, budget: Duration) -> usize {
+        let nanos = budget.as_nanos() as f64 * self.threads as f64;
+        (nanos / self.nanos_per_sample()) as usize
+    }
+
+    /// Number of samples needed for every estimated probability to have at
+    /// most `std_error` standard error, using the worst case `p = 0.5`.
+    pub fn precision_iterations(std_error: f64) -> usize {
+        (0.25 / (std_error * std_error))
+            .ceil()
+            .min(usize::MAX as f64) as usize
+    }
+
+    /// Resolves `Mode::Auto` into a concrete mode. Enumeration wins when it
+    /// is not slower than sampling `iterations` hands, or when it fits into
+    /// the caller's time budget anyway.
+    pub fn select(
+        &self,
+        combinations: Option<usize>,
+        iterations: usize,
+        time_budget: Option<Duration>,
+    ) -> Mode {
+        let Some(combinations) = combinations else {
+            return Mode::MonteCarlo;
+        };
+
+        let exact_time = self.exact_time(combinations);
+
+        if exact_time <= self.montecarlo_time(iterations) {
+            return Mode::Exact;
+        }
+
+        match time_budget {
+            Some(budget) if exact_time <= budget => Mode::Exact,
+            _ => Mode::MonteCarlo,
+        }
+    }
+
+    fn nanos_per_sample(&self) -> f64 {
+        self.montecarlo_nanos_per_sample + self.evaluation_nanos_per_hand
+    }
+
+    /// Selections of one to five cards from `hand_size` cards
+    fn plays(hand_size: usize) -> usize {
+        (1..=hand_size.min(MAX_PLAYED_CARDS))
+            .map(|size| combinations(hand_size, size).unwrap_or(usize::MAX))
+            .fold(0, usize::saturating_add)
+    }
+
+    fn duration(nanos: f64) -> Duration {
+        Duration::from_nanos(nanos.min(u64::MAX as f64) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_prefers_cheaper_mode() {
+        let model = CostModel::new(8);
+
+        assert_eq!(model.select(Some(52), 10_000, None), Mode::Exact);
+        assert_eq!(
+            model.select(Some(1_000_000), 10_000, None),
+            Mode::MonteCarlo
+        );
+        assert_eq!(model.select(None, 10_000, None), Mode::MonteCarlo);
+    }
+
+    #[test]
+    fn test_evaluation_cost() {
+        assert_eq!(CostModel::plays(8), 218);
+        assert_eq!(CostModel::plays(3), 7);
+
+        // Scoring dominates both modes, enumerating on one thread only wins
+        // over sampling on 8 once that takes 8 times as many draws
+        let model = CostModel::new(8);
+        let scoring = model.with_evaluation(CostModel::scoring_nanos(8));
+        assert_eq!(model.select(Some(16_215), 100_000, None), Mode::Exact);
+        assert_eq!(
+            scoring.select(Some(16_215), 100_000, None),
+            Mode::MonteCarlo
+        );
+        assert_eq!(scoring.select(Some(16_215), 200_000, None), Mode::Exact);
+        assert!(
+            scoring.montecarlo_iterations(Duration::from_secs(1))
+                < model.montecarlo_iterations(Duration::from_secs(1)) / 100
+        );
+    }
+
+    #[test]
+    fn test_precision_iterations() {
+        assert_eq!(CostModel::precision_iterations(0.005), 10_000);
+        assert_eq!(CostModel::precision_iterations(0.5), 1);
+    }
+
+    #[test]
+    fn test_select_respects_time_budget() {
+        let model = CostModel::new(8);
+        let combinations = Some(1_000_000);
+
+        assert_eq!(
+            model.select(combinations, 10_000, Some(Duration::from_secs(1))),
+            Mode::Exact
+        );
+        assert_eq!(
+            model.select(combinations, 10_000, Some(Duration::from_millis(1))),
+            Mode::MonteCarlo
+        );
+    }
+
+    #[test]
+    fn test_montecarlo_iterations() {
+        let model = CostModel::new(4);
+        let iterations = model.montecarlo_iterations(Duration::from_millis(1));
+
+        assert!(model.montecarlo_time(iterations) <= Duration::from_millis(1));
+        assert!(iterations > 0);
+    }
+}
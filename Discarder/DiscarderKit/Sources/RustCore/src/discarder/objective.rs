@@ -0,0 +1,411 @@
+use std::{cmp::max, marker::PhantomData};
+
+use crate::{
+    deck::{suit_symmetries, Card, DrawSampler},
+    discarder::{CostModel, Discarder, Mode},
+    montecarlo::{
+        with_rng, MonteCarlo, MonteCarloAlgorithm, MonteCarloAlgorithmFactory,
+        MonteCarloConfiguration, MonteCarloOutput, MonteCarloRng, Pcg64,
+    },
+    poker::{BossBlind, HandTarget},
+    utils::{combinations, wilson_interval},
+};
+
+/// Outcomes a draw should reach, e.g. "a Flush or a Straight". The
+/// objective is met when the drawn hand meets any of its targets.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Objective {
+    targets: Vec<HandTarget>,
+}
+
+impl Objective {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn any(targets: impl IntoIterator<Item = HandTarget>) -> Self {
+        Self {
+            targets: targets.into_iter().collect(),
+        }
+    }
+
+    pub fn with_target(mut self, target: HandTarget) -> Self {
+        self.push(target);
+        self
+    }
+
+    pub fn push(&mut self, target: HandTarget) {
+        self.targets.push(target);
+    }
+
+    pub fn targets(&self) -> &[HandTarget] {
+        &self.targets
+    }
+
+    /// Targets of hand types `blind` does not allow are never met
+    pub fn is_met_by(&self, hand: &[Card], blind: &BossBlind) -> bool {
+        let allowed = blind.allowed_hands(hand.len());
+        self.targets.iter().any(|target| {
+            allowed & target.hand.bit() != 0 && target.is_met_by(hand)
+        })
+    }
+}
+
+/// Draws meeting the objective
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ObjectiveHits {
+    pub hits: i64,
+}
+
+impl MonteCarloOutput for ObjectiveHits {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.hits += other.hits;
+    }
+}
+
+#[derive(Debug)]
+pub struct ObjectiveResult {
+    pub hits: i64,
+    pub iterations: usize,
+    pub mode: Mode,
+}
+
+impl ObjectiveResult {
+    pub fn probability(&self) -> f64 {
+        if self.iterations == 0 {
+            return 0.0;
+        }
+
+        self.hits as f64 / self.iterations as f64
+    }
+
+    /// Wilson score interval of `probability` for the normal quantile `z`.
+    /// Exact results have no sampling error.
+    pub fn confidence_interval(&self, z: f64) -> (f64, f64) {
+        let p = self.probability();
+        if self.mode == Mode::Exact {
+            return (p, p);
+        }
+
+        wilson_interval(p, self.iterations, z)
+    }
+}
+
+impl Discarder {
+    /// Probability of the hand drawn up to the hand size meeting
+    /// `objective`. Exact mode only folds draws by the suit symmetries that
+    /// keep every suit the targets ask for.
+    pub fn run_objective(
+        &self,
+        hand: &[Card],
+        objective: &Objective,
+        max_iterations: usize,
+    ) -> ObjectiveResult {
+        if self.hand_in_deck {
+            return self.without_hand(hand).run_objective(
+                hand,
+                objective,
+                max_iterations,
+            );
+        }
+
+        let hand_size = hand.len();
+
+        if hand_size >= self.max_hand_size {
+            let met = objective.is_met_by(hand, &self.blind);
+            return ObjectiveResult {
+                hits: met as i64,
+                iterations: 1,
+                mode: Mode::Exact,
+            };
+        }
+
+        let symmetries: Vec<_> = suit_symmetries(hand, &self.deck)
+            .into_iter()
+            .filter(|permutation| {
+                objective
+                    .targets()
+                    .iter()
+                    .filter_map(|target| target.suit)
+                    .all(|suit| {
+                        permutation.0[suit.value as usize] == suit.value
+                    })
+            })
+            .collect();
+
+        let cards_to_draw = self.max_hand_size - hand_size;
+        let exact_draws = combinations(self.deck.cards.len(), cards_to_draw)
+            .map(|c| c / symmetries.len().max(1));
+        let (mode, iterations) = self.select_mode(
+            hand_size,
+            exact_draws,
+            max_iterations,
+            CostModel::new(self.threads),
+        );
+
+        if mode == Mode::Exact {
+            let cards_to_draw = cards_to_draw.min(self.deck.cards.len());
+            let mut drawn_hand = Vec::with_capacity(hand_size + cards_to_draw);
+            let mut hits = 0;
+            let mut iterations = 0;
+
+            self.deck.canonical_draws(
+                cards_to_draw,
+                &symmetries,
+                |draw, weight| {
+                    drawn_hand.clear();
+                    drawn_hand.extend_from_slice(hand);
+                    drawn_hand.extend_from_slice(draw);
+
+                    if objective.is_met_by(&drawn_hand, &self.blind) {
+                        hits += weight as i64;
+                    }
+                    iterations += weight;
+                },
+            );
+
+            return ObjectiveResult {
+                hits,
+                iterations,
+                mode,
+            };
+        }
+
+        let configuration = MonteCarloConfiguration {
+            threads: self.threads,
+            chunk_size: 100000,
+        };
+
+        let result = with_rng!(self.rng, R => {
+            let factory = ObjectiveFactory::<R> {
+                discarder: self.clone(),
+                hand: hand.to_vec(),
+                objective: objective.clone(),
+                rng: PhantomData,
+            };
+
+            MonteCarlo::new(factory, configuration, self.seed)
+                .run(iterations, |_| {})
+        });
+
+        ObjectiveResult {
+            hits: result.output.hits,
+            iterations: result.iterations_done,
+            mode,
+        }
+    }
+}
+
+pub struct ObjectiveFactory<R: MonteCarloRng = Pcg64> {
+    discarder: Discarder,
+    hand: Vec<Card>,
+    objective: Objective,
+    rng: PhantomData<R>,
+}
+
+impl<R: MonteCarloRng> MonteCarloAlgorithmFactory for ObjectiveFactory<R> {
+    type Algorithm = ObjectiveAlgorithm<R>;
+    type Rng = R;
+
+    fn make(&self, seed: u64) -> ObjectiveAlgorithm<R> {
+        let initial_hand_size = self.hand.len();
+
+        let size = max(self.discarder.max_hand_size, initial_hand_size);
+        let mut drawn_hand = vec![Card::invalid(); size];
+        drawn_hand[..initial_hand_size].copy_from_slice(&self.hand);
+
+        let draw_size = self
+            .discarder
+            .max_hand_size
+            .saturating_sub(initial_hand_size);
+        let sampler = self.discarder.draw_sampler(draw_size);
+
+        ObjectiveAlgorithm {
+            blind: self.discarder.blind.clone(),
+            objective: self.objective.clone(),
+            initial_hand_size,
+            drawn_hand,
+            sampler,
+            rng: R::seed_from_u64(seed),
+        }
+    }
+}
+
+pub struct ObjectiveAlgorithm<R: MonteCarloRng = Pcg64> {
+    blind: BossBlind,
+    objective: Objective,
+    initial_hand_size: usize,
+    drawn_hand: Vec<Card>,
+    sampler: DrawSampler,
+    rng: R,
+}
+
+impl<R: MonteCarloRng> MonteCarloAlgorithm for ObjectiveAlgorithm<R> {
+    type Output = ObjectiveHits;
+
+    fn sample(&mut self, output: &mut Self::Output) {
+        let draw_slice = &mut self.drawn_hand[self.initial_hand_size..];
+        self.sampler.sample_draw(&mut self.rng, draw_slice);
+
+        if self.objective.is_met_by(&self.drawn_hand, &self.blind) {
+            output.hits += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        deck::{Deck, Rank, Suit},
+        poker::{HandType, PokerHand},
+    };
+
+    fn hearts() -> Suit {
+        Suit {
+            value: Suit::from_char('H').unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_no_draw() {
+        let discarder = Discarder::new(Deck::make_standard(), 5, 43);
+        let hand = Card::make_hand("AS AH KC 7D 2S").unwrap();
+        let aces = HandTarget::new(HandType::OnePair).with_rank(Rank {
+            value: Rank::from_char('A').unwrap(),
+        });
+
+        let result = discarder.run_objective(&hand, &Objective::new(), 1000);
+        assert_eq!(result.hits, 0);
+
+        let objective = Objective::new().with_target(aces);
+        let result = discarder.run_objective(&hand, &objective, 1000);
+        assert_eq!(result.iterations, 1);
+        assert_eq!(result.probability(), 1.0);
+
+        // The Eye does not allow a second pair this round
+        let blind = BossBlind::TheEye {
+            played: vec![PokerHand::OnePair],
+        };
+        let result = discarder
+            .with_boss_blind(blind)
+            .run_objective(&hand, &objective, 1000);
+        assert_eq!(result.probability(), 0.0);
+    }
+
+    #[test]
+    fn test_flush_or_straight() {
+        let discarder =
+            Discarder::new(Deck::make_standard(), 5, 43).with_mode(Mode::Exact);
+        let hand = Card::make_hand("9H 10H JH QH").unwrap();
+
+        // All 13 hearts complete the flush, 6 more cards the straight
+        let objective = Objective::any([
+            HandTarget::new(HandType::Flush),
+            HandTarget::new(HandType::Straight),
+        ]);
+        let result = discarder.run_objective(&hand, &objective, 1000);
+        assert_eq!(result.mode, Mode::Exact);
+        assert_eq!(result.iterations, 52);
+        assert_eq!(result.hits, 19);
+        assert_eq!(
+            result.confidence_interval(1.96),
+            (19.0 / 52.0, 19.0 / 52.0)
+        );
+
+        // The same as counting the hand sets
+        let counts =
+            discarder.clone().with_hand_sets(true).run(&hand, 0, |_| {});
+        let hands = PokerHand::Flush.bit() | PokerHand::Straight.bit();
+        assert_eq!(counts.hand_sets.unwrap().count_any(hands), 19);
+
+        let objective = Objective::new()
+            .with_target(HandTarget::new(HandType::Flush).with_suit(hearts()));
+        let result = discarder.run_objective(&hand, &objective, 1000);
+        assert_eq!(result.hits, 13);
+    }
+
+    #[test]
+    fn test_suit_constraints() {
+        // A flush in hearts out of an empty hand, 13 choose 5 of 52 choose 5
+        let discarder =
+            Discarder::new(Deck::make_standard(), 5, 43).with_mode(Mode::Exact);
+        let flush = HandTarget::new(HandType::Flush);
+
+        let result = discarder.run_objective(
+            &[],
+            &Objective::new().with_target(flush.with_suit(hearts())),
+            1000,
+        );
+        assert_eq!(result.iterations, 2_598_960);
+        assert_eq!(result.hits, 1287);
+
+        let result = discarder.run_objective(
+            &[],
+            &Objective::new().with_target(flush),
+            0,
+        );
+        assert_eq!(result.hits, 4 * 1287);
+    }
+
+    #[test]
+    fn test_montecarlo() {
+        let discarder = Discarder::new(Deck::make_standard(), 8, 43)
+            .with_threads(4)
+            .with_mode(Mode::MonteCarlo);
+        let hand = Card::make_hand("2H 5H 9H").unwrap();
+        let objective = Objective::new()
+            .with_target(HandTarget::new(HandType::Flush).with_suit(hearts()));
+
+        let exact = discarder
+            .clone()
+            .with_mode(Mode::Exact)
+            .run_objective(&hand, &objective, 0);
+        let result = discarder.run_objective(&hand, &objective, 20000);
+        assert_eq!(result.mode, Mode::MonteCarlo);
+        assert_eq!(result.iterations, 20000);
+
+        let (low, high) = result.confidence_interval(3.29);
+        assert!(low <= exact.probability() && exact.probability() <= high);
+    }
+
+    #[test]
+    fn test_rng_kinds() {
+        use crate::montecarlo::RngKind;
+        use strum::IntoEnumIterator;
+
+        let hand = Card::make_hand("2H 5H 9H").unwrap();
+        let objective = Objective::new()
+            .with_target(HandTarget::new(HandType::Flush).with_suit(hearts()));
+        let discarder = Discarder::new(Deck::make_standard(), 8, 43)
+            .with_threads(4)
+            .with_mode(Mode::MonteCarlo);
+        let exact = discarder
+            .clone()
+            .with_mode(Mode::Exact)
+            .run_objective(&hand, &objective, 0);
+
+        let run = |rng| {
+            discarder
+                .clone()
+                .with_rng(rng)
+                .run_objective(&hand, &objective, 20000)
+        };
+        let reference = run(RngKind::Pcg64);
+
+        for rng in RngKind::iter() {
+            let result = run(rng);
+            assert_eq!(result.hits, run(rng).hits);
+            if rng != RngKind::Pcg64 {
+                assert_ne!(result.hits, reference.hits, "{:?}", rng);
+            }
+
+            let (low, high) = result.confidence_interval(3.29);
+            assert!(low <= exact.probability() && exact.probability() <= high);
+        }
+    }
+}
@@ -0,0 +1,377 @@
+use std::{cmp::max, marker::PhantomData, sync::Arc};
+
+use crate::{
+    deck::{Card, DrawSampler},
+    discarder::{CostModel, Discarder, Mode},
+    jokers::JokerEffect,
+    montecarlo::{
+        with_rng, MonteCarlo, MonteCarloAlgorithm, MonteCarloAlgorithmFactory,
+        MonteCarloConfiguration, MonteCarloOutput, MonteCarloRng, Pcg64,
+    },
+    scoring::{Play, Scorer, ScoringCardHistogram},
+    utils::combinations,
+};
+
+/// Jokers in play, left to right
+pub type Jokers = Arc<Vec<Box<dyn JokerEffect>>>;
+
+/// Distribution of the best score that can be played from the drawn hand.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScoreSummary {
+    pub total: f64,
+    pub total_squared: f64,
+    pub max: f64,
+    /// Draws scoring at least the target
+    pub hits: i64,
+    /// Scoring cards of the best play by rank and suit
+    pub cards: ScoringCardHistogram,
+}
+
+impl ScoreSummary {
+    /// Counts the best `play` of a draw, draws without a play score
+    /// nothing
+    pub fn add(&mut self, play: Option<&Play>, target: f64, weight: usize) {
+        let score = play.map_or(0.0, |play| play.score.total());
+        let scoring = play.map_or(&[][..], |play| &play.scoring);
+        self.cards.add(scoring, weight as i64);
+
+        let weight_f = weight as f64;
+
+        self.total += score * weight_f;
+        self.total_squared += score * score * weight_f;
+        self.max = self.max.max(score);
+
+        if score >= target {
+            self.hits += weight as i64;
+        }
+    }
+}
+
+impl MonteCarloOutput for ScoreSummary {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.total += other.total;
+        self.total_squared += other.total_squared;
+        self.max = self.max.max(other.max);
+        self.hits += other.hits;
+        self.cards.merge(&other.cards);
+    }
+}
+
+#[derive(Debug)]
+pub struct ScoreResult {
+    pub summary: ScoreSummary,
+    pub iterations: usize,
+    pub mode: Mode,
+}
+
+impl ScoreResult {
+    pub fn mean(&self) -> f64 {
+        if self.iterations == 0 {
+            return 0.0;
+        }
+
+        self.summary.total / self.iterations as f64
+    }
+
+    pub fn variance(&self) -> f64 {
+        if self.iterations == 0 {
+            return 0.0;
+        }
+
+        let mean = self.mean();
+        (self.summary.total_squared / self.iterations as f64 - mean * mean)
+            .max(0.0)
+    }
+
+    /// Probability of scoring at least the target
+    pub fn hit_probability(&self) -> f64 {
+        if self.iterations == 0 {
+            return 0.0;
+        }
+
+        self.summary.hits as f64 / self.iterations as f64
+    }
+}
+
+impl Discarder {
+    /// Draws up to the hand size and scores the best play of every drawn
+    /// hand with `jokers` applied. Draws are not reduced by suit symmetry
+    /// because jokers like Blackboard care about suits.
+    pub fn run_scoring(
+        &self,
+        hand: &[Card],
+        jokers: Jokers,
+        target: f64,
+        max_iterations: usize,
+    ) -> ScoreResult {
+        if self.hand_in_deck {
+            return self.without_hand(hand).run_scoring(
+                hand,
+                jokers,
+                target,
+                max_iterations,
+            );
+        }
+
+        let hand_size = hand.len();
+        let mut summary = ScoreSummary::default();
+
+        if hand_size >= self.max_hand_size {
+            let play = self.best_play(hand, &jokers);
+            summary.add(play.as_ref(), target, 1);
+            return ScoreResult {
+                summary,
+                iterations: 1,
+                mode: Mode::Exact,
+            };
+        }
+
+        let cards_to_draw = self.max_hand_size - hand_size;
+        let exact_draws = combinations(self.deck.cards.len(), cards_to_draw);
+        let (mode, iterations) = self.select_mode(
+            hand_size,
+            exact_draws,
+            max_iterations,
+            CostModel::new(self.threads)
+                .with_evaluation(CostModel::scoring_nanos(self.max_hand_size)),
+        );
+
+        if mode == Mode::Exact {
+            let cards_to_draw = cards_to_draw.min(self.deck.cards.len());
+            let mut drawn_hand = Vec::with_capacity(hand_size + cards_to_draw);
+            let mut iterations = 0;
+
+            self.deck
+                .canonical_draws(cards_to_draw, &[], |draw, weight| {
+                    drawn_hand.clear();
+                    drawn_hand.extend_from_slice(hand);
+                    drawn_hand.extend_from_slice(draw);
+
+                    let play = self.best_play(&drawn_hand, &jokers);
+                    summary.add(play.as_ref(), target, weight);
+                    iterations += weight;
+                });
+
+            return ScoreResult {
+                summary,
+                iterations,
+                mode,
+            };
+        }
+
+        let configuration = MonteCarloConfiguration {
+            threads: self.threads,
+            chunk_size: 100000,
+        };
+
+        let result = with_rng!(self.rng, R => {
+            let factory = ScoringFactory::<R> {
+                discarder: self.clone(),
+                hand: hand.to_vec(),
+                jokers,
+                target,
+                rng: PhantomData,
+            };
+
+            MonteCarlo::new(factory, configuration, self.seed)
+                .run(iterations, |_| {})
+        });
+
+        ScoreResult {
+            summary: result.output,
+            iterations: result.iterations_done,
+            mode,
+        }
+    }
+
+    /// Hands the blind does not allow to be played have no play
+    fn best_play(
+        &self,
+        hand: &[Card],
+        jokers: &[Box<dyn JokerEffect>],
+    ) -> Option<Play> {
+        Scorer::best_play(hand, jokers, &self.blind)
+    }
+}
+
+pub struct ScoringFactory<R: MonteCarloRng = Pcg64> {
+    discarder: Discarder,
+    hand: Vec<Card>,
+    jokers: Jokers,
+    target: f64,
+    rng: PhantomData<R>,
+}
+
+impl<R: MonteCarloRng> MonteCarloAlgorithmFactory for ScoringFactory<R> {
+    type Algorithm = ScoringAlgorithm<R>;
+    type Rng = R;
+
+    fn make(&self, seed: u64) -> ScoringAlgorithm<R> {
+        let initial_hand_size = self.hand.len();
+
+        let size = max(self.discarder.max_hand_size, initial_hand_size);
+        let mut drawn_hand = vec![Card::invalid(); size];
+        drawn_hand[..initial_hand_size].copy_from_slice(&self.hand);
+
+        let draw_size = self
+            .discarder
+            .max_hand_size
+            .saturating_sub(initial_hand_size);
+        let sampler = self.discarder.draw_sampler(draw_size);
+
+        ScoringAlgorithm {
+            discarder: self.discarder.clone(),
+            initial_hand_size,
+            drawn_hand,
+            sampler,
+            jokers: self.jokers.clone(),
+            target: self.target,
+            rng: R::seed_from_u64(seed),
+        }
+    }
+}
+
+pub struct ScoringAlgorithm<R: MonteCarloRng = Pcg64> {
+    discarder: Discarder,
+    initial_hand_size: usize,
+    drawn_hand: Vec<Card>,
+    sampler: DrawSampler,
+    jokers: Jokers,
+    target: f64,
+    rng: R,
+}
+
+impl<R: MonteCarloRng> MonteCarloAlgorithm for ScoringAlgorithm<R> {
+    type Output = ScoreSummary;
+
+    fn sample(&mut self, output: &mut Self::Output) {
+        let draw_slice = &mut self.drawn_hand
+            [self.initial_hand_size..self.discarder.max_hand_size];
+        self.sampler.sample_draw(&mut self.rng, draw_slice);
+
+        let play = self.discarder.best_play(&self.drawn_hand, &self.jokers);
+        output.add(play.as_ref(), self.target, 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        deck::{Deck, Rank, Suit},
+        jokers::joker_by_name,
+    };
+
+    fn jokers(names: &[&str]) -> Jokers {
+        Arc::new(names.iter().map(|n| joker_by_name(n).unwrap()).collect())
+    }
+
+    #[test]
+    fn test_no_draw() {
+        let discarder = Discarder::new(Deck::make_standard(), 5, 43);
+        let hand = Card::make_hand("AS AH KC 7D 2S").unwrap();
+
+        // Pair of aces: (10 + 11 + 11) x 2
+        let result = discarder.run_scoring(&hand, jokers(&[]), 64.0, 1000);
+        assert_eq!(result.iterations, 1);
+        assert_eq!(result.mean(), 64.0);
+        assert_eq!(result.hit_probability(), 1.0);
+
+        let cards = &result.summary.cards;
+        let ace = Rank { value: 14 };
+        assert_eq!(cards.rank_count(ace, 2), 1);
+        assert_eq!(cards.rank_mean(Rank { value: 13 }), 0.0);
+        assert_eq!(cards.suit_mean(Suit { value: 3 }), 1.0);
+
+        // Jolly Joker adds +8 mult
+        let result =
+            discarder.run_scoring(&hand, jokers(&["Jolly Joker"]), 65.0, 1000);
+        assert_eq!(result.mean(), 320.0);
+        assert_eq!(result.hit_probability(), 1.0);
+    }
+
+    #[test]
+    fn test_exact_draw() {
+        let discarder = Discarder::new(Deck::make_standard(), 5, 43)
+            .with_mode(Mode::Exact)
+            .with_hand_in_deck(true);
+        let hand = Card::make_hand("AS AH KC 7D").unwrap();
+
+        let result = discarder.run_scoring(&hand, jokers(&[]), 100.0, 1000);
+        assert_eq!(result.mode, Mode::Exact);
+        assert_eq!(result.iterations, 48);
+        assert!(result.mean() >= 64.0);
+        assert!(result.hit_probability() > 0.0);
+
+        // Both aces score in every draw, a third one whenever it is drawn
+        let cards = &result.summary.cards;
+        let ace = Rank { value: 14 };
+        assert_eq!(cards.total(), 48);
+        assert_eq!(cards.rank_count(ace, 0) + cards.rank_count(ace, 1), 0);
+        assert_eq!(cards.rank_count(ace, 3), 2);
+    }
+
+    #[test]
+    fn test_montecarlo_draw() {
+        let discarder = Discarder::new(Deck::make_standard(), 8, 43)
+            .with_threads(4)
+            .with_mode(Mode::MonteCarlo);
+
+        let without = discarder.run_scoring(&[], jokers(&[]), 300.0, 2000);
+        let with = discarder.run_scoring(
+            &[],
+            jokers(&["Joker", "Scary Face", "Blackboard"]),
+            300.0,
+            2000,
+        );
+
+        assert_eq!(without.iterations, 2000);
+        assert_eq!(with.iterations, 2000);
+        assert!(with.mean() > without.mean());
+        assert!(with.hit_probability() > without.hit_probability());
+        assert!(with.variance() > 0.0);
+        assert_eq!(with.summary.cards.total(), 2000);
+    }
+
+    #[test]
+    fn test_rng_kinds() {
+        use crate::{montecarlo::RngKind, utils::wilson_interval};
+        use strum::IntoEnumIterator;
+
+        let hand = Card::make_hand("AS AH KC 7D").unwrap();
+        let discarder = Discarder::new(Deck::make_standard(), 5, 43)
+            .with_threads(4)
+            .with_mode(Mode::MonteCarlo)
+            .with_hand_in_deck(true);
+        let exact = discarder
+            .clone()
+            .with_mode(Mode::Exact)
+            .run_scoring(&hand, jokers(&["Joker"]), 100.0, 0)
+            .hit_probability();
+
+        let run = |rng| {
+            discarder.clone().with_rng(rng).run_scoring(
+                &hand,
+                jokers(&["Joker"]),
+                100.0,
+                2000,
+            )
+        };
+        let reference = run(RngKind::Pcg64);
+
+        for rng in RngKind::iter() {
+            let result = run(rng);
+            if rng != RngKind::Pcg64 {
+                assert_ne!(result.summary, reference.summary, "{:?}", rng);
+            }
+
+            let (low, high) =
+                wilson_interval(result.hit_probability(), 2000, 3.29);
+            assert!(low <= exact && exact <= high, "{:?}", rng);
+        }
+    }
+}
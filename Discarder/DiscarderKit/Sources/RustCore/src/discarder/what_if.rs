@@ -0,0 +1,96 @@
+use rand::SeedableRng;
+
+use crate::{
+    deck::{Card, Transform},
+    discarder::{DiscardResult, Discarder},
+    poker::PokerHandsCount,
+};
+
+#[derive(Debug)]
+pub struct WhatIfResult {
+    pub hand: Vec<Card>,
+    pub before: DiscardResult,
+    pub after: DiscardResult,
+}
+
+impl WhatIfResult {
+    /// Change in counts caused by the transforms, with `before` rescaled to
+    /// the number of iterations of `after`.
+    pub fn delta(&self) -> PokerHandsCount {
+        let before = self.before.iterations.max(1) as i128;
+        let after = self.after.iterations as i128;
+
+        let delta: Vec<i64> = self
+            .after
+            .count
+            .iter()
+            .zip(self.before.count.iter())
+            .map(|(&a, &b)| a - (b as i128 * after / before) as i64)
+            .collect();
+
+        PokerHandsCount::from_array(&delta)
+    }
+}
+
+impl Discarder {
+    /// Runs the analysis for `hand` as it is and after applying `transforms`
+    /// to it, e.g. using Strength or Death before discarding. Returns `None`
+    /// if a transform refers to a card that is not in the hand.
+    pub fn what_if(
+        &self,
+        hand: &[Card],
+        transforms: &[Transform],
+        max_iterations: usize,
+    ) -> Option<WhatIfResult> {
+        let mut rng = rand_pcg::Pcg64::seed_from_u64(self.seed);
+        let transformed = Transform::apply_all(transforms, hand, &mut rng)?;
+
+        // Both hands draw from the deck the original hand was taken from
+        let discarder = match self.hand_in_deck {
+            true => self.without_hand(hand),
+            false => self.clone(),
+        };
+        let before = discarder.run(hand, max_iterations, |_| {});
+        let after = discarder.run(&transformed, max_iterations, |_| {});
+
+        Some(WhatIfResult {
+            hand: transformed,
+            before,
+            after,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{deck::Deck, discarder::Mode};
+
+    #[test]
+    fn test_what_if_flush() {
+        let discarder =
+            Discarder::new(Deck::make_standard(), 8, 43).with_mode(Mode::Exact);
+        let hand = Card::make_hand("2S 5S 7S 9S JH QD").unwrap();
+
+        let result = discarder
+            .what_if(&hand, &[Transform::the_world(&[4, 5])], 10000)
+            .unwrap();
+
+        assert_eq!(result.hand, Card::make_hand("2S 5S 7S 9S JS QS").unwrap());
+        assert_eq!(result.before.iterations, 1326);
+        assert_eq!(result.after.iterations, 1326);
+        assert_eq!(result.before.count.iter().nth(5), Some(&585));
+        assert_eq!(result.after.count.iter().nth(5), Some(&1326));
+        assert_eq!(result.delta().iter().nth(5), Some(&741));
+    }
+
+    #[test]
+    fn test_what_if_invalid_transform() {
+        let discarder = Discarder::new(Deck::make_standard(), 8, 43);
+        let hand = Card::make_hand("2S 5S").unwrap();
+
+        assert!(discarder
+            .what_if(&hand, &[Transform::strength(&[2])], 10000)
+            .is_none());
+    }
+}
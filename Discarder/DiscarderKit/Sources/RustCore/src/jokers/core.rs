@@ -0,0 +1,293 @@
+use crate::{
+    deck::{Card, Suit},
+    poker::PokerHand,
+    scoring::{Score, ScoringContext},
+};
+
+use super::JokerEffect;
+
+/// +4 mult
+pub struct Joker;
+
+impl JokerEffect for Joker {
+    fn name(&self) -> &'static str {
+        "Joker"
+    }
+
+    fn on_hand_scored(&self, _context: &ScoringContext, score: &mut Score) {
+        score.mult += 4.0;
+    }
+}
+
+/// Adds chips or mult if the played hand contains a poker hand, e.g. Jolly
+/// Joker or Sly Joker.
+pub struct HandTypeJoker {
+    name: &'static str,
+    hand: PokerHand,
+    chips: f64,
+    mult: f64,
+}
+
+impl HandTypeJoker {
+    pub fn chips(name: &'static str, hand: PokerHand, chips: f64) -> Self {
+        Self {
+            name,
+            hand,
+            chips,
+            mult: 0.0,
+        }
+    }
+
+    pub fn mult(name: &'static str, hand: PokerHand, mult: f64) -> Self {
+        Self {
+            name,
+            hand,
+            chips: 0.0,
+            mult,
+        }
+    }
+}
+
+impl JokerEffect for HandTypeJoker {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn on_hand_scored(&self, context: &ScoringContext, score: &mut Score) {
+        if context.contained[self.hand] > 0 {
+            score.chips += self.chips;
+            score.mult += self.mult;
+        }
+    }
+}
+
+/// +20 mult if the played hand has at most 3 cards
+pub struct HalfJoker;
+
+impl JokerEffect for HalfJoker {
+    fn name(&self) -> &'static str {
+        "Half Joker"
+    }
+
+    fn on_hand_scored(&self, context: &ScoringContext, score: &mut Score) {
+        if context.played.len() <= 3 {
+            score.mult += 20.0;
+        }
+    }
+}
+
+/// +30 chips for every scored face card
+pub struct ScaryFace;
+
+impl JokerEffect for ScaryFace {
+    fn name(&self) -> &'static str {
+        "Scary Face"
+    }
+
+    fn on_card_scored(&self, card: &Card, score: &mut Score) {
+        if card.is_face() {
+            score.chips += 30.0;
+        }
+    }
+}
+
+/// +4 mult for every scored 10, 8, 6, 4 or 2
+pub struct EvenSteven;
+
+impl JokerEffect for EvenSteven {
+    fn name(&self) -> &'static str {
+        "Even Steven"
+    }
+
+    fn on_card_scored(&self, card: &Card, score: &mut Score) {
+        if !card.is_stone()
+            && card.rank.value <= 10
+            && card.rank.value.is_multiple_of(2)
+        {
+            score.mult += 4.0;
+        }
+    }
+}
+
+/// +31 chips for every scored Ace, 9, 7, 5 or 3
+pub struct OddTodd;
+
+impl JokerEffect for OddTodd {
+    fn name(&self) -> &'static str {
+        "Odd Todd"
+    }
+
+    fn on_card_scored(&self, card: &Card, score: &mut Score) {
+        if !card.is_stone() && matches!(card.rank.value, 3 | 5 | 7 | 9 | 14) {
+            score.chips += 31.0;
+        }
+    }
+}
+
+/// +8 mult for every scored Ace, 2, 3, 5 or 8
+pub struct Fibonacci;
+
+impl JokerEffect for Fibonacci {
+    fn name(&self) -> &'static str {
+        "Fibonacci"
+    }
+
+    fn on_card_scored(&self, card: &Card, score: &mut Score) {
+        if !card.is_stone() && matches!(card.rank.value, 2 | 3 | 5 | 8 | 14) {
+            score.mult += 8.0;
+        }
+    }
+}
+
+/// x3 mult if every card held in hand is a Spade or a Club
+pub struct Blackboard;
+
+impl JokerEffect for Blackboard {
+    fn name(&self) -> &'static str {
+        "Blackboard"
+    }
+
+    fn is_suit_sensitive(&self) -> bool {
+        true
+    }
+
+    fn on_hand_scored(&self, context: &ScoringContext, score: &mut Score) {
+        let clubs = Suit {
+            value: Suit::from_char('C').unwrap(),
+        };
+        let spades = Suit {
+            value: Suit::from_char('S').unwrap(),
+        };
+
+        if context
+            .held
+            .iter()
+            .all(|card| card.is_suit(clubs) || card.is_suit(spades))
+        {
+            score.mult *= 3.0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        deck::Enhancement,
+        poker::{HandType, PokerHandResolver, PokerHandsCount},
+    };
+
+    fn card_scored(joker: &dyn JokerEffect, card: &str) -> Score {
+        let card = Card::make_hand(card).unwrap()[0];
+        let mut score = Score::new(0.0, 0.0);
+        joker.on_card_scored(&card, &mut score);
+        score
+    }
+
+    fn hand_scored(joker: &dyn JokerEffect, played: &str, held: &str) -> Score {
+        let played = Card::make_hand(played).unwrap();
+        let held = Card::make_hand(held).unwrap();
+        let mut contained = PokerHandsCount::new();
+        PokerHandResolver::poker_hands(&played, &mut contained);
+        let context = ScoringContext {
+            hand: HandType::HighCard,
+            contained: &contained,
+            played: &played,
+            scoring: &played,
+            held: &held,
+        };
+
+        let mut score = Score::new(10.0, 1.0);
+        joker.on_hand_scored(&context, &mut score);
+        score
+    }
+
+    fn stone() -> Card {
+        Card::make_hand("2S").unwrap()[0].with_enhancement(Enhancement::Stone)
+    }
+
+    #[test]
+    fn test_scary_face() {
+        assert_eq!(card_scored(&ScaryFace, "JS").chips, 30.0);
+        assert_eq!(card_scored(&ScaryFace, "KD").chips, 30.0);
+        assert_eq!(card_scored(&ScaryFace, "AS").chips, 0.0);
+        assert_eq!(card_scored(&ScaryFace, "TS").chips, 0.0);
+    }
+
+    #[test]
+    fn test_even_steven() {
+        for card in ["2S", "4H", "6C", "8D", "TS"] {
+            assert_eq!(card_scored(&EvenSteven, card).mult, 4.0, "{card}");
+        }
+        for card in ["3S", "9H", "QC", "AD"] {
+            assert_eq!(card_scored(&EvenSteven, card).mult, 0.0, "{card}");
+        }
+
+        let mut score = Score::new(0.0, 0.0);
+        EvenSteven.on_card_scored(&stone(), &mut score);
+        assert_eq!(score.mult, 0.0);
+    }
+
+    #[test]
+    fn test_odd_todd() {
+        for card in ["AS", "3H", "5C", "7D", "9S"] {
+            assert_eq!(card_scored(&OddTodd, card).chips, 31.0, "{card}");
+        }
+        for card in ["2S", "TH", "JC", "KD"] {
+            assert_eq!(card_scored(&OddTodd, card).chips, 0.0, "{card}");
+        }
+
+        let mut score = Score::new(0.0, 0.0);
+        OddTodd.on_card_scored(&stone(), &mut score);
+        assert_eq!(score.chips, 0.0);
+    }
+
+    #[test]
+    fn test_fibonacci() {
+        for card in ["AS", "2H", "3C", "5D", "8S"] {
+            assert_eq!(card_scored(&Fibonacci, card).mult, 8.0, "{card}");
+        }
+        for card in ["4S", "7H", "TC", "KD"] {
+            assert_eq!(card_scored(&Fibonacci, card).mult, 0.0, "{card}");
+        }
+
+        let mut score = Score::new(0.0, 0.0);
+        Fibonacci.on_card_scored(&stone(), &mut score);
+        assert_eq!(score.mult, 0.0);
+    }
+
+    #[test]
+    fn test_hand_type_joker() {
+        let jolly = HandTypeJoker::mult("Jolly Joker", PokerHand::OnePair, 8.0);
+        let sly = HandTypeJoker::chips("Sly Joker", PokerHand::OnePair, 50.0);
+
+        // A full house contains a pair
+        assert_eq!(
+            hand_scored(&jolly, "AS AH AD KS KH", ""),
+            Score::new(10.0, 9.0)
+        );
+        assert_eq!(
+            hand_scored(&sly, "AS AH AD KS KH", ""),
+            Score::new(60.0, 1.0)
+        );
+        assert_eq!(hand_scored(&jolly, "AS KH", ""), Score::new(10.0, 1.0));
+    }
+
+    #[test]
+    fn test_half_joker() {
+        assert_eq!(hand_scored(&HalfJoker, "AS AH AD", "").mult, 21.0);
+        assert_eq!(hand_scored(&HalfJoker, "AS AH AD KS", "").mult, 1.0);
+    }
+
+    #[test]
+    fn test_blackboard() {
+        assert_eq!(hand_scored(&Blackboard, "AH", "2S 3C").mult, 3.0);
+        assert_eq!(hand_scored(&Blackboard, "AH", "").mult, 3.0);
+        assert_eq!(hand_scored(&Blackboard, "AS", "2S 3D").mult, 1.0);
+    }
+
+    #[test]
+    fn test_joker() {
+        assert_eq!(hand_scored(&Joker, "AS", "").mult, 5.0);
+    }
+}
@@ -0,0 +1,96 @@
+mod core;
+
+pub use self::core::*;
+
+use crate::{
+    deck::Card,
+    scoring::{Score, ScoringContext},
+};
+
+/// Effect of a joker on the score of a played hand. Jokers are applied left
+/// to right, first for every scoring card and then once for the hand.
+pub trait JokerEffect: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Called after `card` added its own chips and enhancement.
+    fn on_card_scored(&self, _card: &Card, _score: &mut Score) {}
+
+    /// Called once after all cards have been scored.
+    fn on_hand_scored(&self, _context: &ScoringContext, _score: &mut Score) {}
+
+    /// Whether the effect tells suits apart beyond forming Flushes, so that
+    /// swapping suits can change the score.
+    fn is_suit_sensitive(&self) -> bool {
+        false
+    }
+}
+
+/// Every joker with a known effect, in collection order.
+pub fn all_jokers() -> Vec<Box<dyn JokerEffect>> {
+    use crate::poker::PokerHand::*;
+
+    vec![
+        Box::new(Joker),
+        Box::new(HandTypeJoker::mult("Jolly Joker", OnePair, 8.0)),
+        Box::new(HandTypeJoker::mult("Zany Joker", ThreeOfAKind, 12.0)),
+        Box::new(HandTypeJoker::mult("Mad Joker", TwoPair, 10.0)),
+        Box::new(HandTypeJoker::mult("Crazy Joker", Straight, 12.0)),
+        Box::new(HandTypeJoker::mult("Droll Joker", Flush, 10.0)),
+        Box::new(HandTypeJoker::chips("Sly Joker", OnePair, 50.0)),
+        Box::new(HandTypeJoker::chips("Wily Joker", ThreeOfAKind, 100.0)),
+        Box::new(HandTypeJoker::chips("Clever Joker", TwoPair, 80.0)),
+        Box::new(HandTypeJoker::chips("Devious Joker", Straight, 100.0)),
+        Box::new(HandTypeJoker::chips("Crafty Joker", Flush, 80.0)),
+        Box::new(HalfJoker),
+        Box::new(ScaryFace),
+        Box::new(EvenSteven),
+        Box::new(OddTodd),
+        Box::new(Fibonacci),
+        Box::new(Blackboard),
+    ]
+}
+
+/// Looks a joker up by its in-game name, ignoring case.
+pub fn joker_by_name(name: &str) -> Option<Box<dyn JokerEffect>> {
+    all_jokers()
+        .into_iter()
+        .find(|joker| joker.name().eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{poker::BossBlind, scoring::Scorer};
+
+    fn total(played: &str, held: &str, joker: &str) -> f64 {
+        let played = Card::make_hand(played).unwrap();
+        let held = Card::make_hand(held).unwrap();
+        let jokers = vec![joker_by_name(joker).unwrap()];
+
+        Scorer::score(&played, &held, &jokers, &BossBlind::None)
+            .1
+            .total()
+    }
+
+    #[test]
+    fn test_joker_by_name() {
+        assert_eq!(joker_by_name("jolly joker").unwrap().name(), "Jolly Joker");
+        assert!(joker_by_name("Not A Joker").is_none());
+    }
+
+    #[test]
+    fn test_joker_effects() {
+        // Pair of aces scores 32 chips x 2 mult
+        assert_eq!(total("AS AH", "", "Joker"), 32.0 * 6.0);
+        assert_eq!(total("AS AH", "", "Jolly Joker"), 32.0 * 10.0);
+        assert_eq!(total("AS AH", "", "Sly Joker"), 82.0 * 2.0);
+        assert_eq!(total("AS AH", "", "Zany Joker"), 64.0);
+        assert_eq!(total("AS AH", "", "Half Joker"), 32.0 * 22.0);
+        assert_eq!(total("AS AH", "", "Odd Todd"), 94.0 * 2.0);
+        assert_eq!(total("AS AH", "", "Fibonacci"), 32.0 * 18.0);
+        assert_eq!(total("KS KH", "", "Scary Face"), 90.0 * 2.0);
+        assert_eq!(total("8S 8H", "", "Even Steven"), 26.0 * 10.0);
+        assert_eq!(total("AS AH", "2S 3C", "Blackboard"), 32.0 * 6.0);
+        assert_eq!(total("AS AH", "2S 3H", "Blackboard"), 64.0);
+    }
+}
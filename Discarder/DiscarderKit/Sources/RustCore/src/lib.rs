@@ -1,6 +1,17 @@
+// The C API validates raw pointers itself and panics on null.
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+#![cfg_attr(feature = "simd", feature(portable_simd))]
+
 pub mod api;
 mod deck;
 mod discarder;
+mod jokers;
 mod montecarlo;
 mod poker;
-mod utils;
\ No newline at end of file
+#[cfg(feature = "python")]
+mod python;
+mod scoring;
+mod strategy;
+mod utils;
+#[cfg(feature = "wasm")]
+mod wasm;
@@ -1,5 +1,6 @@
 mod config;
 mod message;
+mod rng;
 mod thread;
 mod traits;
 
@@ -8,10 +9,10 @@ mod tests;
 
 pub use config::*;
 pub use message::*;
+pub use rng::*;
 pub use traits::*;
 
 use rand::{RngCore, SeedableRng};
-use rand_pcg::Pcg64;
 use std::{collections::HashSet, sync::mpsc, thread as std_thread};
 use thread::MonteCarloThread;
 
@@ -20,7 +21,10 @@ pub struct MonteCarlo<Factory: MonteCarloAlgorithmFactory> {
         Chunk<<<Factory as MonteCarloAlgorithmFactory>::Algorithm as MonteCarloAlgorithm>::Output>,
     factory: Factory,
     configuration: MonteCarloConfiguration,
-    rng: Pcg64,
+    /// Derives the seeds of the per thread algorithms
+    rng: Factory::Rng,
+    /// Algorithm sampling on the calling thread, see `step`
+    local: Option<Factory::Algorithm>,
 }
 
 pub struct Progress<'a, Algorithm: MonteCarloAlgorithm> {
@@ -38,10 +42,37 @@ impl<Factory: MonteCarloAlgorithmFactory> MonteCarlo<Factory> {
             chunk: Chunk::new(),
             factory,
             configuration,
-            rng: Pcg64::seed_from_u64(seed),
+            rng: Factory::Rng::seed_from_u64(seed),
+            local: None,
         }
     }
 
+    /// Everything sampled so far
+    pub fn chunk(
+        &self,
+    ) -> &Chunk<<<Factory as MonteCarloAlgorithmFactory>::Algorithm as MonteCarloAlgorithm>::Output>{
+        &self.chunk
+    }
+
+    /// Samples `iterations` more draws on the calling thread and returns
+    /// everything sampled so far. Hosts without threads, like the browser,
+    /// call this repeatedly and yield in between.
+    pub fn step(
+        &mut self,
+        iterations: usize,
+    ) -> &Chunk<<<Factory as MonteCarloAlgorithmFactory>::Algorithm as MonteCarloAlgorithm>::Output>{
+        let algorithm = self
+            .local
+            .get_or_insert_with(|| self.factory.make(self.rng.next_u64()));
+
+        let mut chunk = Chunk::new();
+        algorithm.sample_batch(iterations, &mut chunk.output);
+        chunk.iterations_done += iterations;
+
+        self.chunk.merge(&chunk);
+        &self.chunk
+    }
+
     pub fn run(
         &mut self,
         iterations: usize,
@@ -51,6 +82,24 @@ impl<Factory: MonteCarloAlgorithmFactory> MonteCarlo<Factory> {
     ) -> Chunk<<<Factory as MonteCarloAlgorithmFactory>::Algorithm as MonteCarloAlgorithm>::Output>{
         let initial_iterations = self.chunk.iterations_done;
 
+        // wasm32 has no threads, sample in chunks on the calling thread
+        if cfg!(target_arch = "wasm32") {
+            let mut remaining = iterations;
+            while remaining > 0 {
+                let batch_size = remaining.min(self.configuration.chunk_size);
+                self.step(batch_size);
+                remaining -= batch_size;
+
+                progress_handler(&Progress {
+                    fraction_completed: 1.0
+                        - remaining as f64 / iterations as f64,
+                    chunk: &self.chunk,
+                });
+            }
+
+            return self.chunk.clone();
+        }
+
         println!(
             "Running Monte Carlo with {} threads",
             self.configuration.threads
@@ -107,7 +156,7 @@ impl<Factory: MonteCarloAlgorithmFactory> MonteCarlo<Factory> {
                             fraction_completed: (self.chunk.iterations_done
                                 - initial_iterations)
                                 as f64
-                                / (iterations - initial_iterations) as f64,
+                                / iterations as f64,
                             chunk: &self.chunk,
                         };
 
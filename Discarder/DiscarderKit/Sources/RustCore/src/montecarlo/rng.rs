@@ -0,0 +1,85 @@
+use rand::{RngCore, SeedableRng};
+
+pub use rand_pcg::{Pcg64, Pcg64Mcg};
+pub use rand_xoshiro::{Xoshiro128PlusPlus, Xoshiro256PlusPlus};
+
+/// Generators the engine can sample with, seeded per thread from the
+/// engine's own generator of the same type.
+pub trait MonteCarloRng: RngCore + SeedableRng + Send + 'static {}
+
+impl<T: RngCore + SeedableRng + Send + 'static> MonteCarloRng for T {}
+
+/// Generator picked at runtime. Streams differ between kinds, so results
+/// are only reproducible for the same kind and seed.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    strum::EnumIter,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum RngKind {
+    /// PCG XSL RR 128/64, the reference stream for tests
+    #[default]
+    Pcg64,
+    /// PCG with a 128 bit multiplicative step, cheaper than `Pcg64`
+    Pcg64Mcg,
+    Xoshiro256PlusPlus,
+    /// Works in 32 bit lanes, the fastest on wasm32 and easy to vectorize
+    Xoshiro128PlusPlus,
+}
+
+/// Evaluates `$body` with the type alias `$rng` naming the generator of
+/// `$kind`, for engines generic over `MonteCarloRng`.
+macro_rules! with_rng {
+    ($kind:expr, $rng:ident => $body:expr) => {
+        match $kind {
+            $crate::montecarlo::RngKind::Pcg64 => {
+                type $rng = $crate::montecarlo::Pcg64;
+                $body
+            }
+            $crate::montecarlo::RngKind::Pcg64Mcg => {
+                type $rng = $crate::montecarlo::Pcg64Mcg;
+                $body
+            }
+            $crate::montecarlo::RngKind::Xoshiro256PlusPlus => {
+                type $rng = $crate::montecarlo::Xoshiro256PlusPlus;
+                $body
+            }
+            $crate::montecarlo::RngKind::Xoshiro128PlusPlus => {
+                type $rng = $crate::montecarlo::Xoshiro128PlusPlus;
+                $body
+            }
+        }
+    };
+}
+pub(crate) use with_rng;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_rng_kinds() {
+        // Every kind names a generator of its own, reproducible by seed
+        let first: Vec<u64> = RngKind::iter()
+            .map(|kind| {
+                with_rng!(kind, R => {
+                    let value = R::seed_from_u64(43).next_u64();
+                    assert_eq!(R::seed_from_u64(43).next_u64(), value);
+                    value
+                })
+            })
+            .collect();
+
+        for (i, value) in first.iter().enumerate() {
+            assert!(!first[i + 1..].contains(value), "{:?}", first);
+        }
+    }
+}
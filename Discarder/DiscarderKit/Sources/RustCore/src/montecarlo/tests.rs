@@ -19,6 +19,7 @@ pub struct PiEstimatorFactory;
 
 impl MonteCarloAlgorithmFactory for PiEstimatorFactory {
     type Algorithm = PiEstimator;
+    type Rng = Pcg64;
 
     fn make(&self, seed: u64) -> Self::Algorithm {
         PiEstimator::new_with_seed(seed)
@@ -102,3 +103,48 @@ fn test_pi_estimation_seeded() {
     // Pi should be approximately 3.14159, allow for Monte Carlo variance
     assert!((pi_estimate - std::f64::consts::PI).abs() < 0.1);
 }
+
+#[test]
+fn test_pi_estimation_step() {
+    let mut monte_carlo =
+        MonteCarlo::new(PiEstimatorFactory, config_of(1), 12345);
+    for _ in 0..10 {
+        monte_carlo.step(500);
+    }
+
+    let chunk = monte_carlo.step(0);
+    assert_eq!(chunk.iterations_done, 5000);
+    assert_eq!(chunk.output.total_points, 5000);
+    assert!((chunk.output.estimate_pi() - std::f64::consts::PI).abs() < 0.1);
+
+    // Same seed, same samples
+    let mut again = MonteCarlo::new(PiEstimatorFactory, config_of(1), 12345);
+    assert_eq!(
+        again.step(5000).output.points_inside_circle,
+        monte_carlo.step(0).output.points_inside_circle
+    );
+}
+
+#[test]
+fn test_progress_after_step() {
+    // Progress counts the iterations of this run only
+    let mut monte_carlo =
+        MonteCarlo::new(PiEstimatorFactory, config_of(4), 12345);
+    monte_carlo.step(300);
+
+    let fractions = std::cell::RefCell::new(Vec::new());
+    monte_carlo.run(1000, |progress| {
+        fractions.borrow_mut().push(progress.fraction_completed)
+    });
+
+    let fractions = fractions.into_inner();
+    assert!(fractions.windows(2).all(|pair| pair[0] < pair[1]));
+    assert_eq!(fractions.last(), Some(&1.0));
+}
+
+fn config_of(threads: usize) -> MonteCarloConfiguration {
+    MonteCarloConfiguration {
+        threads,
+        chunk_size: 100,
+    }
+}
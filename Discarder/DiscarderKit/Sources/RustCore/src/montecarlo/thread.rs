@@ -31,10 +31,8 @@ impl<Algorithm: MonteCarloAlgorithm> MonteCarloThread<Algorithm> {
             let batch_size = remaining.min(self.chunk_size);
             let mut chunk = Chunk::<Algorithm::Output>::new();
 
-            for _ in 0..batch_size {
-                self.algorithm.sample(&mut chunk.output);
-                chunk.iterations_done += 1;
-            }
+            self.algorithm.sample_batch(batch_size, &mut chunk.output);
+            chunk.iterations_done += batch_size;
 
             let message = Message {
                 thread_id: self.thread_id,
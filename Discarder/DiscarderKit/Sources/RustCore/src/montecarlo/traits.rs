@@ -1,11 +1,22 @@
+use super::MonteCarloRng;
+
 pub trait MonteCarloAlgorithm: Send {
     type Output: MonteCarloOutput;
 
     fn sample(&mut self, output: &mut Self::Output);
+
+    /// Takes `count` samples, algorithms override this to amortize work
+    /// across samples.
+    fn sample_batch(&mut self, count: usize, output: &mut Self::Output) {
+        for _ in 0..count {
+            self.sample(output);
+        }
+    }
 }
 
 pub trait MonteCarloAlgorithmFactory: Send + 'static {
     type Algorithm: MonteCarloAlgorithm;
+    type Rng: MonteCarloRng;
 
     fn make(&self, seed: u64) -> Self::Algorithm;
 }
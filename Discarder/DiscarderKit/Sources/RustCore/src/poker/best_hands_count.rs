@@ -0,0 +1,77 @@
+use crate::poker::hand_type::HandType;
+use std::ops::Index;
+use strum::EnumCount;
+
+/// Draws by the highest hand type they contain, so unlike `PokerHandsCount`
+/// the counts sum up to the draws, apart from those no hand can be played
+/// from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BestHandsCount {
+    counts: [i64; HandType::COUNT],
+}
+
+impl BestHandsCount {
+    pub fn new() -> Self {
+        BestHandsCount {
+            counts: [0; HandType::COUNT],
+        }
+    }
+
+    /// Create from a C array (slice)
+    pub fn from_array(array: &[i64]) -> Self {
+        let mut counts = [0i64; HandType::COUNT];
+        let len = array.len().min(HandType::COUNT);
+        counts[..len].copy_from_slice(&array[..len]);
+        BestHandsCount { counts }
+    }
+
+    /// Counts a draw containing `hands`, one bit per `HandType`, `weight`
+    /// times under its highest hand type
+    #[inline]
+    pub fn add(&mut self, hands: u16, weight: i64) {
+        if hands != 0 {
+            let highest = u16::BITS - 1 - hands.leading_zeros();
+            self.counts[highest as usize] += weight;
+        }
+    }
+
+    pub fn merge(&mut self, other: &Self) {
+        for (count, &other) in self.counts.iter_mut().zip(&other.counts) {
+            *count += other;
+        }
+    }
+
+    /// Draws counted
+    pub fn total(&self) -> i64 {
+        self.counts.iter().sum()
+    }
+}
+
+impl Index<HandType> for BestHandsCount {
+    type Output = i64;
+
+    fn index(&self, hand: HandType) -> &Self::Output {
+        &self.counts[hand.raw_value()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add() {
+        let mut best = BestHandsCount::new();
+        best.add(HandType::HighCard.bit() | HandType::Flush.bit(), 3);
+        best.add(HandType::FullHouse.bit() | HandType::FlushHouse.bit(), 1);
+        best.add(0, 5);
+
+        assert_eq!(best[HandType::Flush], 3);
+        assert_eq!(best[HandType::FlushHouse], 1);
+        assert_eq!(best.total(), 4);
+        assert_eq!(
+            best,
+            BestHandsCount::from_array(&[0, 0, 0, 0, 0, 3, 0, 0, 0, 0, 0, 1])
+        );
+    }
+}
@@ -0,0 +1,223 @@
+use crate::deck::{Card, Suit};
+use crate::poker::hand_type::HandType;
+use crate::poker::poker_hand::PokerHand;
+use strum::IntoEnumIterator;
+
+/// Boss blind of the current round, with whatever it remembers about the
+/// hands already played.
+///
+/// Debuffed cards still form poker hands, but score nothing and trigger no
+/// enhancement or joker.
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum BossBlind {
+    #[default]
+    None,
+    /// Clubs are debuffed
+    TheClub,
+    /// Spades are debuffed
+    TheGoad,
+    /// Diamonds are debuffed
+    TheWindow,
+    /// Hearts are debuffed
+    TheHead,
+    /// Face cards are debuffed
+    ThePlant,
+    /// Every play must be 5 cards
+    ThePsychic,
+    /// No hand type can be played twice in the round
+    TheEye { played: Vec<PokerHand> },
+    /// Only the first hand type played this round can be played again
+    TheMouth { hand: Option<PokerHand> },
+    /// Base chips and mult are halved
+    TheFlint,
+}
+
+impl BossBlind {
+    pub fn is_debuffed(&self, card: &Card) -> bool {
+        let suit = match self {
+            BossBlind::TheClub => 'C',
+            BossBlind::TheGoad => 'S',
+            BossBlind::TheWindow => 'D',
+            BossBlind::TheHead => 'H',
+            BossBlind::ThePlant => return card.is_face(),
+            _ => return false,
+        };
+
+        card.is_suit(Suit {
+            value: Suit::from_char(suit).unwrap(),
+        })
+    }
+
+    /// Whether a hand of type `hand` can be played at all. Secret hands are
+    /// never locked in by The Mouth, so they are ruled out by any other
+    /// hand it locked in, and The Eye never forbids them.
+    pub fn allows(&self, hand: HandType) -> bool {
+        let poker_hand = hand.poker_hand();
+
+        match self {
+            BossBlind::TheEye { played } => {
+                poker_hand.is_none_or(|hand| !played.contains(&hand))
+            }
+            BossBlind::TheMouth {
+                hand: Some(allowed),
+            } => poker_hand == Some(*allowed),
+            _ => true,
+        }
+    }
+
+    /// Remembers that `hand` was played this round, The Eye forbids it from
+    /// then on and The Mouth allows nothing else. Secret hands are not
+    /// remembered.
+    pub fn record_play(&mut self, hand: HandType) {
+        let Some(hand) = hand.poker_hand() else {
+            return;
+        };
+
+        match self {
+            BossBlind::TheEye { played } if !played.contains(&hand) => {
+                played.push(hand)
+            }
+            BossBlind::TheMouth { hand: allowed } if allowed.is_none() => {
+                *allowed = Some(hand)
+            }
+            _ => {}
+        }
+    }
+
+    /// Hands that can be played from `hand_size` cards, one bit per
+    /// `HandType`. Extra cards can always be added to reach the minimum
+    /// play size, so only hands smaller than that rule out everything.
+    pub fn allowed_hands(&self, hand_size: usize) -> u16 {
+        if hand_size < self.min_played() {
+            return 0;
+        }
+
+        HandType::iter()
+            .filter(|&hand| self.allows(hand))
+            .fold(0, |mask, hand| mask | hand.bit())
+    }
+
+    /// Fewest cards a play must have
+    pub fn min_played(&self) -> usize {
+        match self {
+            BossBlind::ThePsychic => 5,
+            _ => 1,
+        }
+    }
+
+    /// Base chips and mult of `hand` in this round
+    pub fn base_score(&self, hand: HandType) -> (f64, f64) {
+        let (chips, mult) = hand.base_score();
+
+        match self {
+            // Rounded half up, as the game does
+            BossBlind::TheFlint => (
+                (chips * 0.5 + 0.5).floor(),
+                (mult * 0.5 + 0.5).floor().max(1.0),
+            ),
+            _ => (chips, mult),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::Enhancement;
+
+    #[test]
+    fn test_debuffs() {
+        let cards = Card::make_hand("2C KS 5D 7H").unwrap();
+
+        let debuffed = |blind: BossBlind| -> Vec<bool> {
+            cards.iter().map(|c| blind.is_debuffed(c)).collect()
+        };
+
+        assert_eq!(debuffed(BossBlind::None), [false, false, false, false]);
+        assert_eq!(debuffed(BossBlind::TheClub), [true, false, false, false]);
+        assert_eq!(debuffed(BossBlind::TheGoad), [false, true, false, false]);
+        assert_eq!(debuffed(BossBlind::TheWindow), [false, false, true, false]);
+        assert_eq!(debuffed(BossBlind::TheHead), [false, false, false, true]);
+        assert_eq!(debuffed(BossBlind::ThePlant), [false, true, false, false]);
+
+        // Wild cards have every suit, Stone cards none
+        let wild = cards[1].with_enhancement(Enhancement::Wild);
+        assert!(BossBlind::TheHead.is_debuffed(&wild));
+        let stone = cards[1].with_enhancement(Enhancement::Stone);
+        assert!(!BossBlind::TheGoad.is_debuffed(&stone));
+        assert!(!BossBlind::ThePlant.is_debuffed(&stone));
+    }
+
+    #[test]
+    fn test_hand_restrictions() {
+        let eye = BossBlind::TheEye {
+            played: vec![PokerHand::OnePair],
+        };
+        assert!(!eye.allows(HandType::OnePair));
+        assert!(eye.allows(HandType::Flush));
+        assert!(eye.allows(HandType::FiveOfAKind));
+
+        let mouth = BossBlind::TheMouth {
+            hand: Some(PokerHand::Flush),
+        };
+        assert!(mouth.allows(HandType::Flush));
+        assert!(!mouth.allows(HandType::HighCard));
+        assert!(!mouth.allows(HandType::FlushFive));
+        assert!(BossBlind::TheMouth { hand: None }.allows(HandType::HighCard));
+
+        assert_eq!(BossBlind::ThePsychic.min_played(), 5);
+        assert_eq!(BossBlind::None.min_played(), 1);
+    }
+
+    #[test]
+    fn test_record_play() {
+        let mut eye = BossBlind::TheEye { played: vec![] };
+        eye.record_play(HandType::OnePair);
+        eye.record_play(HandType::OnePair);
+        eye.record_play(HandType::FlushHouse);
+        assert_eq!(
+            eye,
+            BossBlind::TheEye {
+                played: vec![PokerHand::OnePair]
+            }
+        );
+
+        let mut mouth = BossBlind::TheMouth { hand: None };
+        mouth.record_play(HandType::FiveOfAKind);
+        assert_eq!(mouth, BossBlind::TheMouth { hand: None });
+        mouth.record_play(HandType::Flush);
+        mouth.record_play(HandType::OnePair);
+        assert!(mouth.allows(HandType::Flush));
+        assert!(!mouth.allows(HandType::OnePair));
+
+        let mut plant = BossBlind::ThePlant;
+        plant.record_play(HandType::Flush);
+        assert_eq!(plant, BossBlind::ThePlant);
+    }
+
+    #[test]
+    fn test_flint() {
+        assert_eq!(
+            BossBlind::TheFlint.base_score(HandType::HighCard),
+            (3.0, 1.0)
+        );
+        assert_eq!(
+            BossBlind::TheFlint.base_score(HandType::Flush),
+            (18.0, 2.0)
+        );
+        assert_eq!(
+            BossBlind::TheFlint.base_score(HandType::FourOfAKind),
+            (30.0, 4.0)
+        );
+    }
+}
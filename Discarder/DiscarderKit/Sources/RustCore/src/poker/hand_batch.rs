@@ -0,0 +1,438 @@
+use crate::deck::Card;
+use crate::poker::hand_evaluator::HandMasks;
+use crate::poker::poker_hand::PokerHand;
+
+use lanes::*;
+
+/// Hands evaluated at once by `HandBatch`, one per SIMD lane
+const BATCH_SIZE: usize = 16;
+
+/// Lanes of `std::simd`, needs a nightly toolchain
+#[cfg(feature = "simd")]
+mod lanes {
+    use super::BATCH_SIZE;
+    use std::simd::{
+        cmp::{SimdPartialEq, SimdPartialOrd},
+        Mask, Select, Simd,
+    };
+
+    pub type Lanes = Simd<u16, BATCH_SIZE>;
+
+    #[inline]
+    fn ones(mask: Mask<i16, BATCH_SIZE>) -> Lanes {
+        mask.select(Simd::splat(u16::MAX), Simd::splat(0))
+    }
+
+    #[inline]
+    pub fn load(values: &[u16; BATCH_SIZE]) -> Lanes {
+        Simd::from_array(*values)
+    }
+
+    #[inline]
+    pub fn store(lanes: Lanes) -> [u16; BATCH_SIZE] {
+        lanes.to_array()
+    }
+
+    #[inline]
+    pub fn splat(value: u16) -> Lanes {
+        Simd::splat(value)
+    }
+
+    #[inline]
+    pub fn shl(lanes: Lanes, n: u16) -> Lanes {
+        lanes << Simd::splat(n)
+    }
+
+    #[inline]
+    pub fn shr(lanes: Lanes, n: u16) -> Lanes {
+        lanes >> Simd::splat(n)
+    }
+
+    /// Clears the lowest set bit of every lane
+    #[inline]
+    pub fn clear_lowest(lanes: Lanes) -> Lanes {
+        lanes & (lanes - Simd::splat(1))
+    }
+
+    /// All ones in lanes that are not zero
+    #[inline]
+    pub fn nonzero(lanes: Lanes) -> Lanes {
+        ones(lanes.simd_ne(Simd::splat(0)))
+    }
+
+    /// All ones in lanes of at least `value`
+    #[inline]
+    pub fn at_least(lanes: Lanes, value: u16) -> Lanes {
+        ones(lanes.simd_ge(Simd::splat(value)))
+    }
+}
+
+/// Plain arrays with the same operations, written lane by lane so the
+/// compiler can vectorize them on stable
+#[cfg(not(feature = "simd"))]
+mod lanes {
+    use super::BATCH_SIZE;
+    use std::ops::{BitAnd, BitOr, Not};
+
+    #[derive(Clone, Copy)]
+    pub struct Lanes([u16; BATCH_SIZE]);
+
+    impl Lanes {
+        #[inline]
+        fn map(self, f: impl Fn(u16) -> u16) -> Self {
+            Self(self.0.map(f))
+        }
+
+        #[inline]
+        fn zip(self, other: Self, f: impl Fn(u16, u16) -> u16) -> Self {
+            let mut lanes = self.0;
+            for (lane, other) in lanes.iter_mut().zip(other.0) {
+                *lane = f(*lane, other);
+            }
+            Self(lanes)
+        }
+    }
+
+    impl BitAnd for Lanes {
+        type Output = Self;
+
+        #[inline]
+        fn bitand(self, other: Self) -> Self {
+            self.zip(other, |a, b| a & b)
+        }
+    }
+
+    impl BitOr for Lanes {
+        type Output = Self;
+
+        #[inline]
+        fn bitor(self, other: Self) -> Self {
+            self.zip(other, |a, b| a | b)
+        }
+    }
+
+    impl Not for Lanes {
+        type Output = Self;
+
+        #[inline]
+        fn not(self) -> Self {
+            self.map(|a| !a)
+        }
+    }
+
+    #[inline]
+    pub fn load(values: &[u16; BATCH_SIZE]) -> Lanes {
+        Lanes(*values)
+    }
+
+    #[inline]
+    pub fn store(lanes: Lanes) -> [u16; BATCH_SIZE] {
+        lanes.0
+    }
+
+    #[inline]
+    pub fn splat(value: u16) -> Lanes {
+        Lanes([value; BATCH_SIZE])
+    }
+
+    #[inline]
+    pub fn shl(lanes: Lanes, n: u16) -> Lanes {
+        lanes.map(|a| a << n)
+    }
+
+    #[inline]
+    pub fn shr(lanes: Lanes, n: u16) -> Lanes {
+        lanes.map(|a| a >> n)
+    }
+
+    /// Clears the lowest set bit of every lane
+    #[inline]
+    pub fn clear_lowest(lanes: Lanes) -> Lanes {
+        lanes.map(|a| a & a.wrapping_sub(1))
+    }
+
+    /// All ones in lanes that are not zero
+    #[inline]
+    pub fn nonzero(lanes: Lanes) -> Lanes {
+        lanes.map(|a| 0u16.wrapping_sub((a != 0) as u16))
+    }
+
+    /// All ones in lanes of at least `value`
+    #[inline]
+    pub fn at_least(lanes: Lanes, value: u16) -> Lanes {
+        lanes.map(|a| 0u16.wrapping_sub((a >= value) as u16))
+    }
+}
+
+/// All ones in lanes whose ranks hold five in a row, Aces also count low
+#[inline]
+fn straights(ranks: Lanes) -> Lanes {
+    // Bit 0 is the low Ace, bits 1 to 13 are Two to Ace
+    let ranks = shl(ranks, 1) | (shr(ranks, 12) & splat(1));
+    let runs =
+        ranks & shr(ranks, 1) & shr(ranks, 2) & shr(ranks, 3) & shr(ranks, 4);
+
+    nonzero(runs)
+}
+
+/// Rank bits of T-J-Q-K-A
+const ROYAL_RANKS: u16 = 0b11111 << 8;
+
+/// Up to `BATCH_SIZE` hands as structure of arrays, classified lane by lane
+/// with the same answers as `HandEvaluator::contained`.
+#[derive(Clone)]
+pub struct HandBatch {
+    len: usize,
+    cards: [u16; BATCH_SIZE],
+    at_least: [[u16; BATCH_SIZE]; 5],
+    suit_ranks: [[u16; BATCH_SIZE]; 4],
+    suit_counts: [[u16; BATCH_SIZE]; 4],
+    /// Secret hands are rare, so they are found while pushing
+    secret_hands: [u16; BATCH_SIZE],
+}
+
+impl Default for HandBatch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HandBatch {
+    pub fn new() -> Self {
+        Self {
+            len: 0,
+            cards: [0; BATCH_SIZE],
+            at_least: [[0; BATCH_SIZE]; 5],
+            suit_ranks: [[0; BATCH_SIZE]; 4],
+            suit_counts: [[0; BATCH_SIZE]; 4],
+            secret_hands: [0; BATCH_SIZE],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == BATCH_SIZE
+    }
+
+    /// Empties the batch, lanes are overwritten as hands are pushed
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    pub fn push(&mut self, hand: &[Card]) {
+        assert!(!self.is_full(), "batch is full");
+
+        let masks = HandMasks::of(hand);
+        let lane = self.len;
+
+        self.cards[lane] = masks.cards;
+        for (lanes, mask) in self.at_least.iter_mut().zip(masks.at_least) {
+            lanes[lane] = mask;
+        }
+        for suit in 0..4 {
+            self.suit_ranks[suit][lane] = masks.suit_ranks[suit];
+            self.suit_counts[suit][lane] = masks.suit_counts[suit];
+        }
+        self.secret_hands[lane] = masks.secret_hands(hand);
+
+        self.len += 1;
+    }
+
+    /// Hands contained in each pushed hand, in push order, one bit per
+    /// `HandType`
+    pub fn evaluate(&self) -> [u16; BATCH_SIZE] {
+        let bit = |hand: PokerHand| splat(hand.bit());
+        let [_, pairs, trips, quads, fives] = self.at_least.map(|l| load(&l));
+
+        let two_groups = nonzero(clear_lowest(pairs));
+        let three = nonzero(trips);
+        let four = nonzero(quads);
+
+        let mut hands = (nonzero(load(&self.cards)) & bit(PokerHand::HighCard))
+            | load(&self.secret_hands)
+            | (nonzero(pairs) & bit(PokerHand::OnePair))
+            | ((two_groups | four) & bit(PokerHand::TwoPair))
+            | (three & bit(PokerHand::ThreeOfAKind))
+            | (four & bit(PokerHand::FourOfAKind))
+            | (((three & two_groups) | nonzero(fives))
+                & bit(PokerHand::FullHouse))
+            | (straights(load(&self.at_least[0])) & bit(PokerHand::Straight));
+
+        for suit in 0..4 {
+            let ranks = load(&self.suit_ranks[suit]);
+            let flush = at_least(load(&self.suit_counts[suit]), 5);
+            let royal = !nonzero(!ranks & splat(ROYAL_RANKS));
+
+            hands = hands
+                | (flush & bit(PokerHand::Flush))
+                | (flush & straights(ranks) & bit(PokerHand::StraightFlush))
+                | (flush & royal & bit(PokerHand::RoyalFlush));
+        }
+
+        let mut hands = store(hands);
+        hands[self.len..].fill(0);
+        hands
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::{Deck, Enhancement, Rank, Suit};
+    use crate::poker::HandEvaluator;
+    use rand::{seq::SliceRandom, Rng, SeedableRng};
+    use rand_pcg::Pcg64;
+
+    fn assert_matches_evaluator(hands: &[Vec<Card>]) {
+        let mut batch = HandBatch::new();
+        for hand in hands {
+            batch.push(hand);
+        }
+
+        let evaluated = batch.evaluate();
+        for (lane, hand) in hands.iter().enumerate() {
+            assert_eq!(
+                evaluated[lane],
+                HandEvaluator::contained(hand),
+                "{:?}",
+                hand
+            );
+        }
+        assert!(evaluated[hands.len()..].iter().all(|&h| h == 0));
+    }
+
+    #[test]
+    fn test_batch() {
+        let hands: Vec<_> = [
+            "",
+            "2S",
+            "AS AH",
+            "2S 2H 3C 3D",
+            "KS KH KC 5D 5H",
+            "AS 2H 3C 4D 5H",
+            "TS JH QC KD AH",
+            "2S 5S 7S 9S JS",
+            "AS KS QS JS TS",
+            "AS 2S 3S 4S 5S",
+            "9H TH JH QH KH TD JD QD KD AD",
+            "AS AH AC AD AS",
+            "KS KH KC KD QS QH QC",
+        ]
+        .iter()
+        .map(|hand| Card::make_hand(hand).unwrap())
+        .collect();
+
+        assert_matches_evaluator(&hands);
+    }
+
+    #[test]
+    fn test_partial_batches() {
+        let mut batch = HandBatch::new();
+        assert!(batch.is_empty());
+        assert_eq!(batch.evaluate(), [0; BATCH_SIZE]);
+
+        let hand = Card::make_hand("AS AH").unwrap();
+        for _ in 0..BATCH_SIZE {
+            batch.push(&hand);
+        }
+        assert!(batch.is_full());
+
+        batch.clear();
+        batch.push(&Card::make_hand("2S").unwrap());
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch.evaluate()[0], PokerHand::HighCard.bit());
+        assert_eq!(batch.evaluate()[1], 0);
+    }
+
+    #[test]
+    fn test_random_hands() {
+        let mut rng = Pcg64::seed_from_u64(42);
+
+        for _ in 0..20_000 {
+            let hands: Vec<Vec<Card>> = (0..rng.gen_range(1..=BATCH_SIZE))
+                .map(|_| {
+                    (0..rng.gen_range(0..=12))
+                        .map(|_| {
+                            let card = Card::new(
+                                Rank {
+                                    value: rng.gen_range(2..=14),
+                                },
+                                Suit {
+                                    value: rng.gen_range(0..4),
+                                },
+                            );
+
+                            match rng.gen_range(0..20) {
+                                0 => card.with_enhancement(Enhancement::Wild),
+                                1 => card.with_enhancement(Enhancement::Stone),
+                                _ => card,
+                            }
+                        })
+                        .collect()
+                })
+                .collect();
+
+            assert_matches_evaluator(&hands);
+        }
+    }
+
+    #[test]
+    fn test_random_draws() {
+        let mut rng = Pcg64::seed_from_u64(43);
+        let mut cards = Deck::make_standard().cards;
+
+        for _ in 0..10_000 {
+            let hands: Vec<Vec<Card>> = (0..BATCH_SIZE)
+                .map(|_| cards.partial_shuffle(&mut rng, 8).0.to_vec())
+                .collect();
+
+            assert_matches_evaluator(&hands);
+        }
+    }
+
+    /// Batched against one by one evaluation, run with
+    /// `cargo test --release batch_throughput -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn batch_throughput() {
+        use std::time::Instant;
+
+        let mut rng = Pcg64::seed_from_u64(42);
+        let mut cards = Deck::make_standard().cards;
+        let hands: Vec<Vec<Card>> = (0..1_000_000)
+            .map(|_| cards.partial_shuffle(&mut rng, 8).0.to_vec())
+            .collect();
+
+        let mut total = 0u64;
+        let start = Instant::now();
+        for hand in &hands {
+            total += HandEvaluator::contained(hand) as u64;
+        }
+        let single = start.elapsed().as_secs_f64();
+
+        let mut batch = HandBatch::new();
+        let start = Instant::now();
+        for chunk in hands.chunks(BATCH_SIZE) {
+            batch.clear();
+            for hand in chunk {
+                batch.push(hand);
+            }
+            total += batch.evaluate().iter().map(|&h| h as u64).sum::<u64>();
+        }
+        let batched = start.elapsed().as_secs_f64();
+
+        std::hint::black_box(total);
+        println!(
+            "HandEvaluator: {:.1}M hands/s, HandBatch: {:.1}M hands/s",
+            hands.len() as f64 / single / 1e6,
+            hands.len() as f64 / batched / 1e6
+        );
+    }
+}
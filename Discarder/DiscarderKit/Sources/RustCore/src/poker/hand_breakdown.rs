@@ -0,0 +1,207 @@
+use crate::deck::{Card, Rank, Suit};
+use crate::poker::hand_evaluator::HandEvaluator;
+use crate::poker::hand_type::HandType;
+use crate::poker::poker_hand_resolver::PokerHandResolver;
+use strum::{EnumCount, IntoEnumIterator};
+
+/// Ranks from Two to Ace
+const RANKS: usize = 13;
+const SUITS: usize = 4;
+
+/// Ranks and suits of the cards that can take part in each hand type of a
+/// hand, as masks of `1 << rank` and `1 << suit`. Wild cards take part with
+/// every suit, Stone cards with no rank or suit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HandComposition {
+    ranks: [u16; HandType::COUNT],
+    suits: [u8; HandType::COUNT],
+}
+
+impl HandComposition {
+    /// Tries every subset of as many cards as a hand type takes, so a card
+    /// takes part in a hand type if some play of it uses the card, e.g. an
+    /// Ace completing a straight.
+    pub fn of(hand: &[Card]) -> Self {
+        let mut composition = Self::default();
+        let contained = HandEvaluator::contained(hand);
+
+        for size in 1..=5 {
+            let types: Vec<_> = HandType::iter()
+                .filter(|t| t.size() == size && contained & t.bit() != 0)
+                .collect();
+            if types.is_empty() {
+                continue;
+            }
+
+            for (cards, hands) in PokerHandResolver::plays(hand, size) {
+                let (ranks, suits) =
+                    cards.fold((0, 0), |(ranks, suits), card| {
+                        (ranks | rank_bit(card), suits | suit_bits(card))
+                    });
+
+                for &hand_type in &types {
+                    if hands & hand_type.bit() != 0 {
+                        composition.ranks[hand_type as usize] |= ranks;
+                        composition.suits[hand_type as usize] |= suits;
+                    }
+                }
+            }
+        }
+
+        composition
+    }
+
+    pub fn ranks(&self, hand: HandType) -> u16 {
+        self.ranks[hand as usize]
+    }
+
+    pub fn suits(&self, hand: HandType) -> u8 {
+        self.suits[hand as usize]
+    }
+}
+
+fn rank_bit(card: &Card) -> u16 {
+    if card.is_stone() {
+        0
+    } else {
+        1 << card.rank.value
+    }
+}
+
+fn suit_bits(card: &Card) -> u8 {
+    Suit::iter()
+        .filter(|&suit| card.is_suit(suit))
+        .fold(0, |bits, suit| bits | 1 << suit.value)
+}
+
+/// Draws by the ranks and suits of the cards that can take part in each
+/// hand type, e.g. how often a Flush can be played with a Heart or a Pair
+/// with face cards, for jokers scoring particular cards.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HandBreakdown {
+    ranks: [[i64; RANKS]; HandType::COUNT],
+    suits: [[i64; SUITS]; HandType::COUNT],
+}
+
+impl HandBreakdown {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts the hand types in `allowed` of `hand`, `weight` times
+    pub fn add(&mut self, hand: &[Card], allowed: u16, weight: i64) {
+        let composition = HandComposition::of(hand);
+
+        for hand_type in HandType::iter() {
+            if allowed & hand_type.bit() == 0 {
+                continue;
+            }
+
+            let ranks = composition.ranks(hand_type);
+            let suits = composition.suits(hand_type);
+
+            let t = hand_type as usize;
+            for (rank, count) in self.ranks[t].iter_mut().enumerate() {
+                if ranks & 1 << (rank + 2) != 0 {
+                    *count += weight;
+                }
+            }
+            for (suit, count) in self.suits[t].iter_mut().enumerate() {
+                if suits & 1 << suit != 0 {
+                    *count += weight;
+                }
+            }
+        }
+    }
+
+    pub fn merge(&mut self, other: &Self) {
+        for (counts, other) in self.ranks.iter_mut().zip(&other.ranks) {
+            for (count, &other) in counts.iter_mut().zip(other) {
+                *count += other;
+            }
+        }
+        for (counts, other) in self.suits.iter_mut().zip(&other.suits) {
+            for (count, &other) in counts.iter_mut().zip(other) {
+                *count += other;
+            }
+        }
+    }
+
+    /// Draws where `hand` can be played with a card of `rank`
+    pub fn with_rank(&self, hand: HandType, rank: Rank) -> i64 {
+        match rank.value {
+            2..=14 => self.ranks[hand as usize][rank.value as usize - 2],
+            _ => 0,
+        }
+    }
+
+    /// Draws where `hand` can be played with a card of `suit`
+    pub fn with_suit(&self, hand: HandType, suit: Suit) -> i64 {
+        self.suits[hand as usize]
+            .get(suit.value as usize)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Copy counts to C arrays, indexed by hand type then by rank from Two
+    /// or by suit
+    pub fn to_arrays(
+        &self,
+        ranks: &mut [[i64; RANKS]; HandType::COUNT],
+        suits: &mut [[i64; SUITS]; HandType::COUNT],
+    ) {
+        *ranks = self.ranks;
+        *suits = self.suits;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rank(c: char) -> Rank {
+        Rank {
+            value: Rank::from_char(c).unwrap(),
+        }
+    }
+
+    fn suit(c: char) -> Suit {
+        Suit {
+            value: Suit::from_char(c).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_composition() {
+        let hand = Card::make_hand("AS 2H 3C 4D 5S 9H KH KD").unwrap();
+        let composition = HandComposition::of(&hand);
+        let ranks = |hand: HandType| composition.ranks(hand);
+
+        assert_eq!(ranks(HandType::OnePair), 1 << 13);
+        assert_eq!(ranks(HandType::Straight), 0b111100 | 1 << 14);
+        assert_eq!(ranks(HandType::Flush), 0);
+        assert_eq!(composition.suits(HandType::OnePair), 0b0011);
+        assert_eq!(composition.suits(HandType::HighCard), 0b1111);
+        assert_eq!(ranks(HandType::HighCard).count_ones(), 7);
+    }
+
+    #[test]
+    fn test_breakdown() {
+        let mut breakdown = HandBreakdown::new();
+        let hand = Card::make_hand("KH QH 9H 5H 2H 2S").unwrap();
+        let all = u16::MAX;
+
+        breakdown.add(&hand, all, 2);
+        breakdown.add(&hand, HandType::Flush.bit(), 1);
+        assert_eq!(breakdown.with_suit(HandType::Flush, suit('H')), 3);
+        assert_eq!(breakdown.with_suit(HandType::Flush, suit('S')), 0);
+        assert_eq!(breakdown.with_rank(HandType::Flush, rank('K')), 3);
+        assert_eq!(breakdown.with_rank(HandType::OnePair, rank('2')), 2);
+        assert_eq!(breakdown.with_rank(HandType::OnePair, rank('K')), 0);
+        assert_eq!(breakdown.with_suit(HandType::OnePair, suit('S')), 2);
+
+        let mut merged = breakdown.clone();
+        merged.merge(&breakdown);
+        assert_eq!(merged.with_suit(HandType::Flush, suit('H')), 6);
+    }
+}
@@ -0,0 +1,437 @@
+use crate::deck::{Card, Enhancement};
+use crate::poker::hand_type::HandType;
+use crate::poker::poker_hand::PokerHand;
+
+/// Rank bitmasks have one bit per rank, Two is bit 0 and Ace is bit 12
+const RANK_MASKS: usize = 1 << 13;
+
+const STRAIGHT_MASKS: [u16; 10] = [
+    0b11111,            // 2-3-4-5-6
+    0b11111 << 1,       // 3-4-5-6-7
+    0b11111 << 2,       // 4-5-6-7-8
+    0b11111 << 3,       // 5-6-7-8-9
+    0b11111 << 4,       // 6-7-8-9-T
+    0b11111 << 5,       // 7-8-9-T-J
+    0b11111 << 6,       // 8-9-T-J-Q
+    0b11111 << 7,       // 9-T-J-Q-K
+    0b11111 << 8,       // T-J-Q-K-A
+    0b1111 | 0b1 << 12, // A-2-3-4-5 (wheel)
+];
+
+const ROYAL_MASK: u16 = 0b11111 << 8;
+
+/// Flags of `STRAIGHTS` entries
+const STRAIGHT: u8 = 1;
+const ROYAL: u8 = 2;
+
+/// Straight classes of every rank bitmask, `ROYAL` is set for T-J-Q-K-A.
+/// Applied to the ranks of a single suit it tells straight and royal
+/// flushes apart.
+static STRAIGHTS: [u8; RANK_MASKS] = straight_table();
+
+/// Rank groups are clamped to what changes the outcome: up to two pairs,
+/// trips and quads, and one rank of five or more
+const GROUP_CLASSES: usize = 3 * 3 * 3 * 2;
+
+/// Hands formed by groups of equal ranks, indexed by `group_index`
+static GROUPS: [u16; GROUP_CLASSES] = group_table();
+
+const fn straight_table() -> [u8; RANK_MASKS] {
+    let mut table = [0; RANK_MASKS];
+
+    let mut mask = 0;
+    while mask < RANK_MASKS {
+        let ranks = mask as u16;
+
+        let mut i = 0;
+        while i < STRAIGHT_MASKS.len() {
+            if ranks & STRAIGHT_MASKS[i] == STRAIGHT_MASKS[i] {
+                table[mask] |= STRAIGHT;
+            }
+            i += 1;
+        }
+
+        if ranks & ROYAL_MASK == ROYAL_MASK {
+            table[mask] |= ROYAL;
+        }
+
+        mask += 1;
+    }
+
+    table
+}
+
+const fn clamp(n: u32, max: u32) -> usize {
+    (if n < max { n } else { max }) as usize
+}
+
+const fn group_index(pairs: u32, trips: u32, quads: u32, fives: u32) -> usize {
+    ((clamp(pairs, 2) * 3 + clamp(trips, 2)) * 3 + clamp(quads, 2)) * 2
+        + clamp(fives, 1)
+}
+
+const fn group_table() -> [u16; GROUP_CLASSES] {
+    let mut table = [0; GROUP_CLASSES];
+
+    let mut pairs = 0;
+    while pairs < 3 {
+        let mut trips = 0;
+        while trips < 3 {
+            let mut quads = 0;
+            while quads < 3 {
+                let mut fives = 0;
+                while fives < 2 {
+                    // Every group of two or more cards holds a pair, two
+                    // groups or a group of four hold two pairs, and so on
+                    let at_least_three = trips + quads + fives;
+                    let at_least_four = quads + fives;
+                    let groups = pairs + at_least_three;
+
+                    let mut hands = 0;
+                    if groups > 0 {
+                        hands |= PokerHand::OnePair.bit();
+                    }
+                    if groups >= 2 || at_least_four > 0 {
+                        hands |= PokerHand::TwoPair.bit();
+                    }
+                    if at_least_three > 0 {
+                        hands |= PokerHand::ThreeOfAKind.bit();
+                    }
+                    if at_least_four > 0 {
+                        hands |= PokerHand::FourOfAKind.bit();
+                    }
+                    if (at_least_three > 0 && groups >= 2) || fives > 0 {
+                        hands |= PokerHand::FullHouse.bit();
+                    }
+
+                    table[group_index(pairs, trips, quads, fives)] = hands;
+                    fives += 1;
+                }
+                quads += 1;
+            }
+            trips += 1;
+        }
+        pairs += 1;
+    }
+
+    table
+}
+
+/// Rank and suit bitmasks of a hand, what both evaluators classify
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct HandMasks {
+    pub cards: u16,
+    /// `at_least[k]` holds the ranks with more than `k` cards
+    pub at_least: [u16; 5],
+    pub suit_ranks: [u16; 4],
+    pub suit_counts: [u16; 4],
+}
+
+impl HandMasks {
+    #[inline]
+    pub fn of<'a>(hand: impl IntoIterator<Item = &'a Card>) -> Self {
+        let mut masks = Self::default();
+
+        for card in hand {
+            if card.rank.value < 2 {
+                panic!("Invalid card detected!")
+            }
+
+            masks.cards += 1;
+            let bit = 1u16 << (card.rank.value - 2);
+
+            match card.enhancement {
+                // Stone cards have no rank or suit
+                Enhancement::Stone => continue,
+                // Wild cards count towards every suit
+                Enhancement::Wild => {
+                    for suit in 0..4 {
+                        masks.suit_ranks[suit] |= bit;
+                        masks.suit_counts[suit] += 1;
+                    }
+                }
+                _ => {
+                    let suit = card.suit.value as usize;
+                    masks.suit_ranks[suit] |= bit;
+                    masks.suit_counts[suit] += 1;
+                }
+            }
+
+            let at_least = &mut masks.at_least;
+            at_least[4] |= at_least[3] & bit;
+            at_least[3] |= at_least[2] & bit;
+            at_least[2] |= at_least[1] & bit;
+            at_least[1] |= at_least[0] & bit;
+            at_least[0] |= bit;
+        }
+
+        masks
+    }
+
+    /// Secret hands in `hand`, which these masks were made of. They all hold
+    /// a Full House, rare enough to look at the cards of each flush.
+    pub fn secret_hands<'a>(
+        &self,
+        hand: impl IntoIterator<Item = &'a Card> + Clone,
+    ) -> u16 {
+        let [_, pairs, trips, _, fives] = self.at_least;
+        let two_groups = pairs & pairs.wrapping_sub(1) != 0;
+        if fives == 0 && (trips == 0 || !two_groups) {
+            return 0;
+        }
+
+        let mut hands = 0;
+        if fives != 0 {
+            hands |= HandType::FiveOfAKind.bit();
+        }
+
+        for suit in 0..4 {
+            if self.suit_counts[suit] < 5 {
+                continue;
+            }
+
+            let mut rank_counts = [0u8; 13];
+            for card in hand.clone() {
+                let in_suit = match card.enhancement {
+                    Enhancement::Stone => false,
+                    Enhancement::Wild => true,
+                    _ => card.suit.value as usize == suit,
+                };
+                if in_suit {
+                    rank_counts[card.rank.value as usize - 2] += 1;
+                }
+            }
+
+            let ranks = |n| rank_counts.iter().filter(|&&c| c >= n).count();
+            if ranks(5) > 0 {
+                hands |= HandType::FlushFive.bit();
+            }
+            if ranks(3) > 0 && ranks(2) >= 2 {
+                hands |= HandType::FlushHouse.bit();
+            }
+        }
+
+        hands
+    }
+}
+
+/// Table driven counterpart of `PokerHandResolver`, replacing the scans
+/// over ranks and straight masks with bit operations and lookups in tables
+/// built at compile time.
+pub struct HandEvaluator;
+
+impl HandEvaluator {
+    /// Every hand type contained in `hand`, one bit per `HandType`. The
+    /// poker hands are the bits of `PokerHand`.
+    #[inline]
+    pub fn contained(hand: &[Card]) -> u16 {
+        Self::contained_in(hand)
+    }
+
+    /// Like `contained`, for the cards picked out of a hand by an iterator
+    #[inline]
+    pub fn contained_in<'a>(
+        hand: impl IntoIterator<Item = &'a Card> + Clone,
+    ) -> u16 {
+        let masks = HandMasks::of(hand.clone());
+        if masks.cards == 0 {
+            return 0;
+        }
+
+        let HandMasks {
+            at_least,
+            suit_ranks,
+            suit_counts,
+            ..
+        } = masks;
+
+        let exactly = |k: usize| (at_least[k - 1] & !at_least[k]).count_ones();
+        let mut hands = PokerHand::HighCard.bit()
+            | masks.secret_hands(hand)
+            | GROUPS[group_index(
+                exactly(2),
+                exactly(3),
+                exactly(4),
+                at_least[4].count_ones(),
+            )];
+
+        if STRAIGHTS[at_least[0] as usize] & STRAIGHT != 0 {
+            hands |= PokerHand::Straight.bit();
+        }
+
+        for suit in 0..4 {
+            if suit_counts[suit] < 5 {
+                continue;
+            }
+
+            hands |= PokerHand::Flush.bit();
+
+            let straight = STRAIGHTS[suit_ranks[suit] as usize];
+            if straight & STRAIGHT != 0 {
+                hands |= PokerHand::StraightFlush.bit();
+            }
+            if straight & ROYAL != 0 {
+                hands |= PokerHand::RoyalFlush.bit();
+            }
+        }
+
+        hands
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::{Deck, Rank, Suit, SuitPermutation};
+    use crate::poker::{PokerHandResolver, PokerHandsCount};
+    use rand::{seq::SliceRandom, Rng, SeedableRng};
+    use rand_pcg::Pcg64;
+
+    fn assert_matches_resolver(hand: &[Card]) {
+        let mut expected = PokerHandsCount::new();
+        PokerHandResolver::poker_hands(hand, &mut expected);
+
+        let mut actual = PokerHandsCount::new();
+        actual.add_mask(HandEvaluator::contained(hand));
+
+        assert_eq!(actual, expected, "{:?}", hand);
+    }
+
+    #[test]
+    fn test_tables() {
+        assert_eq!(STRAIGHTS[0b11111], STRAIGHT);
+        assert_eq!(STRAIGHTS[0b1_0000_0000_1111], STRAIGHT);
+        assert_eq!(STRAIGHTS[0b1_1111_0000_0000], STRAIGHT | ROYAL);
+        assert_eq!(STRAIGHTS[0b1_1110_1111_0000], 0);
+
+        assert_eq!(GROUPS[group_index(0, 0, 0, 0)], 0);
+        assert_eq!(
+            GROUPS[group_index(1, 1, 0, 0)],
+            PokerHand::OnePair.bit()
+                | PokerHand::TwoPair.bit()
+                | PokerHand::ThreeOfAKind.bit()
+                | PokerHand::FullHouse.bit()
+        );
+        assert_eq!(group_index(5, 7, 2, 3), group_index(2, 2, 2, 1));
+    }
+
+    #[test]
+    fn test_every_five_card_hand() {
+        // Both evaluators ignore suit names, one hand per orbit covers all
+        let deck = Deck::make_standard();
+        let symmetries: Vec<_> = SuitPermutation::all().collect();
+
+        let mut hands = 0;
+        deck.canonical_draws(5, &symmetries, |draw, weight| {
+            assert_matches_resolver(draw);
+            hands += weight;
+        });
+
+        assert_eq!(hands, 2_598_960);
+    }
+
+    #[test]
+    fn test_random_hands() {
+        let mut rng = Pcg64::seed_from_u64(42);
+
+        for _ in 0..200_000 {
+            let size = rng.gen_range(0..=12);
+            let hand: Vec<_> = (0..size)
+                .map(|_| {
+                    let card = Card::new(
+                        Rank {
+                            value: rng.gen_range(2..=14),
+                        },
+                        Suit {
+                            value: rng.gen_range(0..4),
+                        },
+                    );
+
+                    match rng.gen_range(0..20) {
+                        0 => card.with_enhancement(Enhancement::Wild),
+                        1 => card.with_enhancement(Enhancement::Stone),
+                        _ => card,
+                    }
+                })
+                .collect();
+
+            assert_matches_resolver(&hand);
+        }
+    }
+
+    #[test]
+    fn test_random_draws() {
+        // Without duplicates, like the draws sampled from a standard deck
+        let mut rng = Pcg64::seed_from_u64(43);
+        let mut cards = Deck::make_standard().cards;
+
+        for _ in 0..100_000 {
+            let size = rng.gen_range(5..=12);
+            let (hand, _) = cards.partial_shuffle(&mut rng, size);
+            assert_matches_resolver(hand);
+        }
+    }
+
+    #[test]
+    fn test_secret_hands() {
+        let secret = |hand: &str| {
+            HandEvaluator::contained(&Card::make_hand(hand).unwrap())
+                & HandType::SECRET_HANDS
+        };
+
+        assert_eq!(secret("AS AH AC AD KS KH KC"), 0);
+        assert_eq!(secret("AS AH AC AD AS"), HandType::FiveOfAKind.bit());
+        assert_eq!(secret("AS AS AS KS KS"), HandType::FlushHouse.bit());
+        assert_eq!(secret("AS AS AH KS KS KS"), HandType::FlushHouse.bit());
+        assert_eq!(secret("AS AS AS KS KH QS"), 0);
+        assert_eq!(
+            secret("AS AS AS AS AS"),
+            HandType::FiveOfAKind.bit() | HandType::FlushFive.bit()
+        );
+
+        // Wild cards join every suit, stone cards none
+        assert_eq!(
+            secret("AS AS AH:wild KS KD:wild"),
+            HandType::FlushHouse.bit()
+        );
+        assert_eq!(
+            secret("AS AS AS KS KS:stone KS"),
+            HandType::FlushHouse.bit()
+        );
+        assert_eq!(secret("AS AS AS KS:stone KS"), 0);
+    }
+
+    /// Evaluations per second of both evaluators, run with
+    /// `cargo test --release evaluator_throughput -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn evaluator_throughput() {
+        use std::time::Instant;
+
+        let mut rng = Pcg64::seed_from_u64(42);
+        let mut cards = Deck::make_standard().cards;
+        let hands: Vec<Vec<Card>> = (0..1_000_000)
+            .map(|_| cards.partial_shuffle(&mut rng, 8).0.to_vec())
+            .collect();
+
+        let mut count = PokerHandsCount::new();
+        let start = Instant::now();
+        for hand in &hands {
+            PokerHandResolver::poker_hands(hand, &mut count);
+        }
+        let resolver = start.elapsed().as_secs_f64();
+
+        let start = Instant::now();
+        for hand in &hands {
+            count.add_mask(HandEvaluator::contained(hand));
+        }
+        let evaluator = start.elapsed().as_secs_f64();
+
+        std::hint::black_box(&count);
+        println!(
+            "PokerHandResolver: {:.1}M hands/s, HandEvaluator: {:.1}M hands/s",
+            hands.len() as f64 / resolver / 1e6,
+            hands.len() as f64 / evaluator / 1e6
+        );
+    }
+}
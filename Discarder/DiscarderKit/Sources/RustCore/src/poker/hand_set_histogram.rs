@@ -0,0 +1,184 @@
+use crate::poker::poker_hand::PokerHand;
+use crate::poker::poker_hands_count::PokerHandsCount;
+use strum::EnumCount;
+
+/// Number of distinct sets of poker hands
+const HAND_SETS: usize = 1 << PokerHand::COUNT;
+
+/// How often each set of poker hands was contained in a draw, indexed by
+/// the set as a mask of `PokerHand::bit`s. Unlike the marginals counted by
+/// `PokerHandsCount`, this answers questions about several hands at once,
+/// e.g. "a Flush or a Full House" or "a Flush but no Straight Flush".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandSetHistogram {
+    counts: Vec<i64>,
+}
+
+impl Default for HandSetHistogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HandSetHistogram {
+    pub fn new() -> Self {
+        Self {
+            counts: vec![0; HAND_SETS],
+        }
+    }
+
+    /// Create from a C array (slice)
+    pub fn from_array(array: &[i64]) -> Self {
+        let mut histogram = Self::new();
+        let len = array.len().min(HAND_SETS);
+        histogram.counts[..len].copy_from_slice(&array[..len]);
+        histogram
+    }
+
+    /// Copy counts to a C array
+    pub fn to_array(&self, array: &mut [i64]) {
+        let len = array.len().min(HAND_SETS);
+        array[..len].copy_from_slice(&self.counts[..len]);
+    }
+
+    /// Counts a draw containing exactly `hands`, `weight` times. Bits past
+    /// the poker hands, like those of secret hands, are ignored.
+    #[inline]
+    pub fn add(&mut self, hands: u16, weight: i64) {
+        self.counts[hands as usize & (HAND_SETS - 1)] += weight;
+    }
+
+    pub fn merge(&mut self, other: &Self) {
+        for (count, &other) in self.counts.iter_mut().zip(&other.counts) {
+            *count += other;
+        }
+    }
+
+    /// Draws counted
+    pub fn total(&self) -> i64 {
+        self.counts.iter().sum()
+    }
+
+    /// Draws containing exactly the hands in `hands` and no other
+    pub fn count(&self, hands: u16) -> i64 {
+        self.counts.get(hands as usize).copied().unwrap_or(0)
+    }
+
+    /// Draws containing every hand in `required` and none in `excluded`
+    pub fn count_matching(&self, required: u16, excluded: u16) -> i64 {
+        self.sum_where(|set| set & required == required && set & excluded == 0)
+    }
+
+    /// Draws containing at least one of `hands`
+    pub fn count_any(&self, hands: u16) -> i64 {
+        self.sum_where(|set| set & hands != 0)
+    }
+
+    /// Draws containing all of `hands`
+    pub fn count_all(&self, hands: u16) -> i64 {
+        self.count_matching(hands, 0)
+    }
+
+    /// Draws by the highest hand they contain, these sum up to the draws
+    /// containing any hand at all.
+    pub fn best_hands(&self) -> PokerHandsCount {
+        let mut best = PokerHandsCount::new();
+        for (set, &count) in self.counts.iter().enumerate().skip(1) {
+            let highest = u16::BITS - 1 - (set as u16).leading_zeros();
+            best.add_mask_scaled(1 << highest, count);
+        }
+        best
+    }
+
+    /// Draws containing each hand, as `PokerHandsCount` would count them
+    pub fn marginals(&self) -> PokerHandsCount {
+        let mut marginals = PokerHandsCount::new();
+        for (set, &count) in self.counts.iter().enumerate() {
+            marginals.add_mask_scaled(set as u16, count);
+        }
+        marginals
+    }
+
+    fn sum_where(&self, predicate: impl Fn(u16) -> bool) -> i64 {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|&(set, _)| predicate(set as u16))
+            .map(|(_, &count)| count)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::{Deck, SuitPermutation};
+    use crate::poker::{HandEvaluator, PokerHand::*};
+
+    #[test]
+    fn test_queries() {
+        let mut histogram = HandSetHistogram::new();
+        let flush = HighCard.bit() | Flush.bit();
+        let straight_flush = flush | Straight.bit() | StraightFlush.bit();
+        let full_house = HighCard.bit()
+            | OnePair.bit()
+            | TwoPair.bit()
+            | ThreeOfAKind.bit()
+            | FullHouse.bit();
+
+        histogram.add(flush, 5);
+        histogram.add(straight_flush, 2);
+        histogram.add(full_house, 3);
+        histogram.add(0, 1);
+
+        assert_eq!(histogram.total(), 11);
+        assert_eq!(histogram.count(flush), 5);
+        assert_eq!(histogram.count(Flush.bit()), 0);
+        assert_eq!(histogram.count_all(Flush.bit()), 7);
+        assert_eq!(
+            histogram.count_matching(Flush.bit(), StraightFlush.bit()),
+            5
+        );
+        assert_eq!(histogram.count_any(Flush.bit() | FullHouse.bit()), 10);
+        assert_eq!(histogram.count_all(Flush.bit() | FullHouse.bit()), 0);
+
+        let best = histogram.best_hands();
+        assert_eq!(best[Flush], 5);
+        assert_eq!(best[StraightFlush], 2);
+        assert_eq!(best[FullHouse], 3);
+        assert_eq!(best.iter().sum::<i64>(), 10);
+
+        let marginals = histogram.marginals();
+        assert_eq!(marginals[HighCard], 10);
+        assert_eq!(marginals[Flush], 7);
+        assert_eq!(marginals[Straight], 2);
+
+        let mut array = [0; 1024];
+        histogram.to_array(&mut array);
+        assert_eq!(HandSetHistogram::from_array(&array), histogram);
+    }
+
+    #[test]
+    fn test_five_card_hands() {
+        // Best hands of all five card draws are the textbook frequencies
+        let deck = Deck::make_standard();
+        let symmetries: Vec<_> = SuitPermutation::all().collect();
+
+        let mut histogram = HandSetHistogram::new();
+        let mut marginals = PokerHandsCount::new();
+        deck.canonical_draws(5, &symmetries, |draw, weight| {
+            let hands = HandEvaluator::contained(draw);
+            histogram.add(hands, weight as i64);
+            marginals.add_mask_scaled(hands, weight as i64);
+        });
+
+        assert_eq!(histogram.marginals(), marginals);
+        assert_eq!(
+            histogram.best_hands(),
+            PokerHandsCount::from_array(&[
+                1_302_540, 1_098_240, 123_552, 54_912, 10_200, 5_108, 3_744,
+                624, 36, 4
+            ])
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use crate::deck::{Card, Rank, Suit};
+use crate::poker::hand_type::HandType;
+use crate::poker::poker_hand_resolver::PokerHandResolver;
+
+/// A hand type to draw into, optionally made of cards of a single rank or
+/// suit only, like a pair of Aces or a flush in Hearts. Wild cards belong to
+/// every suit, Stone cards to no rank or suit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandTarget {
+    pub hand: HandType,
+    pub rank: Option<Rank>,
+    pub suit: Option<Suit>,
+}
+
+impl HandTarget {
+    pub fn new(hand: HandType) -> Self {
+        Self {
+            hand,
+            rank: None,
+            suit: None,
+        }
+    }
+
+    pub fn with_rank(mut self, rank: Rank) -> Self {
+        self.rank = Some(rank);
+        self
+    }
+
+    pub fn with_suit(mut self, suit: Suit) -> Self {
+        self.suit = Some(suit);
+        self
+    }
+
+    /// Whether `card` may be part of the hand
+    fn admits(&self, card: &Card) -> bool {
+        self.rank
+            .is_none_or(|rank| !card.is_stone() && card.rank == rank)
+            && self.suit.is_none_or(|suit| card.is_suit(suit))
+    }
+
+    /// Whether the cards of `hand` the target admits form its hand type
+    pub fn is_met_by(&self, hand: &[Card]) -> bool {
+        PokerHandResolver::contains_made_of(hand, self.hand, |card| {
+            self.admits(card)
+        })
+    }
+
+    /// Whether the hand type can be played from `hand` with a card the
+    /// target admits among the cards forming it, e.g. a straight containing
+    /// an Ace
+    pub fn is_met_including(&self, hand: &[Card]) -> bool {
+        PokerHandResolver::contains_including(hand, self.hand, |card| {
+            self.admits(card)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn rank(c: char) -> Rank {
+        Rank {
+            value: Rank::from_char(c).unwrap(),
+        }
+    }
+
+    fn suit(c: char) -> Suit {
+        Suit {
+            value: Suit::from_char(c).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_targets() {
+        let hand = Card::make_hand("AS AH KH QH 9H 2H 2C").unwrap();
+        let met = |target: HandTarget| target.is_met_by(&hand);
+
+        assert!(met(HandTarget::new(HandType::Flush)));
+        assert!(met(HandTarget::new(HandType::Flush).with_suit(suit('H'))));
+        assert!(!met(HandTarget::new(HandType::Flush).with_suit(suit('S'))));
+
+        let pair = HandTarget::new(HandType::OnePair);
+        assert!(met(pair.with_rank(rank('A'))));
+        assert!(met(pair.with_rank(rank('2'))));
+        assert!(!met(pair.with_rank(rank('K'))));
+        assert!(!met(pair.with_rank(rank('A')).with_suit(suit('H'))));
+        assert!(met(HandTarget::new(HandType::TwoPair)));
+        assert!(!met(HandTarget::new(HandType::TwoPair).with_rank(rank('A'))));
+    }
+
+    #[test]
+    fn test_wild_and_stone_cards() {
+        let wild = Card::from_str("AD:wild").unwrap();
+        let stone = Card::from_str("AS:stone").unwrap();
+        let hand = [wild, stone, Card::from_str("AH").unwrap()];
+
+        let pair = HandTarget::new(HandType::OnePair).with_rank(rank('A'));
+        assert!(pair.is_met_by(&hand));
+        assert!(!pair.with_suit(suit('S')).is_met_by(&hand));
+        assert!(pair.with_suit(suit('H')).is_met_by(&[wild, wild]));
+        assert!(!pair.is_met_by(&[wild, stone]));
+    }
+}
@@ -0,0 +1,137 @@
+use crate::poker::poker_hand::PokerHand;
+
+/// Every hand type of the game in scoring order: the poker hands followed by
+/// the secret hands, which take more than four cards of a rank, or a Full
+/// House within a single suit. Masks of hand types extend `PokerHand::bit`.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    strum::EnumIter,
+    strum::EnumCount,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+#[repr(u8)]
+pub enum HandType {
+    HighCard = 0,
+    OnePair = 1,
+    TwoPair = 2,
+    ThreeOfAKind = 3,
+    Straight = 4,
+    Flush = 5,
+    FullHouse = 6,
+    FourOfAKind = 7,
+    StraightFlush = 8,
+    RoyalFlush = 9,
+    FiveOfAKind = 10,
+    FlushHouse = 11,
+    FlushFive = 12,
+}
+
+impl HandType {
+    /// Bits of the secret hands
+    pub const SECRET_HANDS: u16 = Self::FiveOfAKind.bit()
+        | Self::FlushHouse.bit()
+        | Self::FlushFive.bit();
+
+    pub fn from_raw(value: u8) -> Option<Self> {
+        use strum::IntoEnumIterator;
+        Self::iter().find(|h| *h as u8 == value)
+    }
+
+    #[inline]
+    pub const fn raw_value(self) -> usize {
+        self as usize
+    }
+
+    /// Flag of the hand type in bitmasks of hand types
+    #[inline]
+    pub const fn bit(self) -> u16 {
+        1 << self as u16
+    }
+
+    /// Base chips and mult at level 1
+    pub const fn base_score(self) -> (f64, f64) {
+        match self {
+            Self::FiveOfAKind => (120.0, 12.0),
+            Self::FlushHouse => (140.0, 14.0),
+            Self::FlushFive => (160.0, 16.0),
+            _ => match self.poker_hand() {
+                Some(hand) => hand.base_score(),
+                None => unreachable!(),
+            },
+        }
+    }
+
+    /// The poker hand of the same type, none for secret hands
+    pub const fn poker_hand(self) -> Option<PokerHand> {
+        Some(match self {
+            Self::HighCard => PokerHand::HighCard,
+            Self::OnePair => PokerHand::OnePair,
+            Self::TwoPair => PokerHand::TwoPair,
+            Self::ThreeOfAKind => PokerHand::ThreeOfAKind,
+            Self::Straight => PokerHand::Straight,
+            Self::Flush => PokerHand::Flush,
+            Self::FullHouse => PokerHand::FullHouse,
+            Self::FourOfAKind => PokerHand::FourOfAKind,
+            Self::StraightFlush => PokerHand::StraightFlush,
+            Self::RoyalFlush => PokerHand::RoyalFlush,
+            Self::FiveOfAKind | Self::FlushHouse | Self::FlushFive => {
+                return None
+            }
+        })
+    }
+
+    /// Cards forming the smallest hand of this type
+    pub const fn size(self) -> usize {
+        match self {
+            Self::HighCard => 1,
+            Self::OnePair => 2,
+            Self::ThreeOfAKind => 3,
+            Self::TwoPair | Self::FourOfAKind => 4,
+            _ => 5,
+        }
+    }
+}
+
+impl From<PokerHand> for HandType {
+    fn from(hand: PokerHand) -> Self {
+        match hand {
+            PokerHand::HighCard => HandType::HighCard,
+            PokerHand::OnePair => HandType::OnePair,
+            PokerHand::TwoPair => HandType::TwoPair,
+            PokerHand::ThreeOfAKind => HandType::ThreeOfAKind,
+            PokerHand::Straight => HandType::Straight,
+            PokerHand::Flush => HandType::Flush,
+            PokerHand::FullHouse => HandType::FullHouse,
+            PokerHand::FourOfAKind => HandType::FourOfAKind,
+            PokerHand::StraightFlush => HandType::StraightFlush,
+            PokerHand::RoyalFlush => HandType::RoyalFlush,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_poker_hand_bits() {
+        for hand in PokerHand::iter() {
+            assert_eq!(HandType::from(hand).bit(), hand.bit());
+            assert_eq!(HandType::from(hand).poker_hand(), Some(hand));
+        }
+        assert_eq!(HandType::FlushHouse.poker_hand(), None);
+        assert_eq!(HandType::FlushFive.base_score(), (160.0, 16.0));
+        assert!(HandType::iter().is_sorted());
+        assert_eq!(HandType::SECRET_HANDS, 0b111 << 10);
+    }
+}
@@ -1,6 +1,23 @@
+mod best_hands_count;
+mod boss_blind;
+mod hand_batch;
+mod hand_breakdown;
+mod hand_evaluator;
+mod hand_set_histogram;
+mod hand_target;
+mod hand_type;
 mod poker_hand;
 mod poker_hand_resolver;
 mod poker_hands_count;
 
+pub use best_hands_count::BestHandsCount;
+pub use boss_blind::BossBlind;
+pub use hand_batch::HandBatch;
+pub use hand_breakdown::HandBreakdown;
+pub use hand_evaluator::HandEvaluator;
+pub use hand_set_histogram::HandSetHistogram;
+pub use hand_target::HandTarget;
+pub use hand_type::HandType;
+pub use poker_hand::PokerHand;
 pub use poker_hand_resolver::PokerHandResolver;
 pub use poker_hands_count::PokerHandsCount;
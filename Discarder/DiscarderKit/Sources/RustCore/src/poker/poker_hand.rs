@@ -1,6 +1,18 @@
 #[derive(
-    Debug, Clone, Copy, PartialEq, Eq, Hash, strum::EnumIter, strum::EnumCount,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    strum::EnumIter,
+    strum::EnumCount,
+    serde::Serialize,
+    serde::Deserialize,
 )]
+#[serde(rename_all = "snake_case")]
 #[repr(u8)]
 pub enum PokerHand {
     HighCard = 0,
@@ -20,4 +32,27 @@ impl PokerHand {
     pub const fn raw_value(self) -> usize {
         self as usize
     }
+
+    /// Flag of the hand in bitmasks of hands
+    #[inline]
+    pub const fn bit(self) -> u16 {
+        1 << self as u16
+    }
+
+    /// Base chips and mult of the hand at level 1. A Royal Flush scores as
+    /// a Straight Flush.
+    pub const fn base_score(self) -> (f64, f64) {
+        match self {
+            PokerHand::HighCard => (5.0, 1.0),
+            PokerHand::OnePair => (10.0, 2.0),
+            PokerHand::TwoPair => (20.0, 2.0),
+            PokerHand::ThreeOfAKind => (30.0, 3.0),
+            PokerHand::Straight => (30.0, 4.0),
+            PokerHand::Flush => (35.0, 4.0),
+            PokerHand::FullHouse => (40.0, 4.0),
+            PokerHand::FourOfAKind => (60.0, 7.0),
+            PokerHand::StraightFlush => (100.0, 8.0),
+            PokerHand::RoyalFlush => (100.0, 8.0),
+        }
+    }
 }
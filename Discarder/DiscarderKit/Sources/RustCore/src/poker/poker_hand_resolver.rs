@@ -1,10 +1,61 @@
-use crate::deck::Card;
+use crate::deck::{Card, Enhancement};
+use crate::poker::boss_blind::BossBlind;
+use crate::poker::hand_type::HandType;
 use crate::poker::poker_hand::PokerHand;
 use crate::poker::poker_hands_count::PokerHandsCount;
+use crate::poker::HandEvaluator;
+use crate::utils::subsets;
 
 pub struct PokerHandResolver;
 
 impl PokerHandResolver {
+    /// Poker hands in `hand` that `blind` lets the player play, one bit per
+    /// `PokerHand`, see `BossBlind::allowed_hands`. Evaluated with the faster
+    /// `HandEvaluator`.
+    pub fn contained_with_blind(hand: &[Card], blind: &BossBlind) -> u16 {
+        HandEvaluator::contained(hand) & blind.allowed_hands(hand.len())
+    }
+
+    /// Whether `hand_type` can be formed from the cards of `hand` matching
+    /// `filter` alone, e.g. a pair of face cards or a flush of Hearts.
+    pub fn contains_made_of(
+        hand: &[Card],
+        hand_type: HandType,
+        filter: impl Fn(&Card) -> bool,
+    ) -> bool {
+        let cards = hand.iter().filter(|card| filter(card));
+        HandEvaluator::contained_in(cards) & hand_type.bit() != 0
+    }
+
+    /// Whether `hand_type` can be formed from `hand` with a card matching
+    /// `filter` among the cards forming it, e.g. a straight containing an
+    /// Ace.
+    pub fn contains_including(
+        hand: &[Card],
+        hand_type: HandType,
+        filter: impl Fn(&Card) -> bool,
+    ) -> bool {
+        Self::plays(hand, hand_type.size()).any(|(mut cards, hands)| {
+            hands & hand_type.bit() != 0 && cards.any(&filter)
+        })
+    }
+
+    /// Every `size` card subset of `hand` with the hand types it contains.
+    /// Hand types of exactly `size` cards use every card of the subset.
+    pub fn plays(
+        hand: &[Card],
+        size: usize,
+    ) -> impl Iterator<Item = (impl Iterator<Item = &Card> + Clone, u16)> {
+        subsets(hand.len(), size).map(move |subset| {
+            let cards = hand
+                .iter()
+                .enumerate()
+                .filter(move |&(i, _)| subset >> i & 1 != 0)
+                .map(|(_, card)| card);
+            (cards.clone(), HandEvaluator::contained_in(cards))
+        })
+    }
+
     pub fn poker_hands(hand: &[Card], hands: &mut PokerHandsCount) {
         if hand.is_empty() {
             return;
@@ -25,6 +76,21 @@ impl PokerHandResolver {
             let rank = card.rank.value as usize;
             let suit = card.suit.value as usize;
 
+            match card.enhancement {
+                // Stone cards have no rank or suit
+                Enhancement::Stone => continue,
+                // Wild cards count towards every suit
+                Enhancement::Wild => {
+                    rank_counts[rank] += 1;
+                    for suit in 0..4 {
+                        suit_counts[suit] += 1;
+                        suit_rank_bits[suit] |= 1u16 << rank;
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+
             rank_counts[rank] += 1;
             suit_counts[suit] += 1;
             suit_rank_bits[suit] |= 1u16 << rank;
@@ -114,8 +180,8 @@ impl PokerHandResolver {
     fn has_straight_bits(rank_counts: &[u8; 15]) -> bool {
         // Convert rank counts to bit representation
         let mut rank_bits = 0u16;
-        for i in 2..=14 {
-            if rank_counts[i] > 0 {
+        for (i, &count) in rank_counts.iter().enumerate().skip(2) {
+            if count > 0 {
                 rank_bits |= 1u16 << i;
             }
         }
@@ -135,9 +201,13 @@ impl PokerHandResolver {
             0b1111 << 2 | 0b1 << 14, // A-2-3-4-5 (wheel)
         ];
 
-        STRAIGHT_MASKS
-            .iter()
-            .any(|&mask| (rank_bits & mask) == mask)
+        for &mask in &STRAIGHT_MASKS {
+            if (rank_bits & mask) == mask {
+                return true;
+            }
+        }
+
+        false
     }
 
     // Optimized straight flush detection returning both straight flush and royal flush info
@@ -146,6 +216,8 @@ impl PokerHandResolver {
         suit_rank_bits: &[u16; 4],
         suit_counts: &[u8; 4],
     ) -> u8 {
+        let mut result = 0;
+
         // Only check suits that have 5+ cards
         for suit in 0..4 {
             if suit_counts[suit] < 5 {
@@ -173,15 +245,16 @@ impl PokerHandResolver {
                 return 3; // Both straight flush and royal flush
             }
 
-            // Check other straight flushes
+            // Check other straight flushes, a later suit may still hold a
+            // royal flush
             for &mask in &STRAIGHT_MASKS {
                 if (bits & mask) == mask {
-                    return 1; // Straight flush only
+                    result = 1; // Straight flush only
                 }
             }
         }
 
-        0 // No straight flush
+        result
     }
 }
 
@@ -190,6 +263,7 @@ impl PokerHandResolver {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::deck::{Rank, Suit};
     use std::collections::HashSet;
     use strum::IntoEnumIterator;
     use PokerHand::*;
@@ -823,10 +897,187 @@ mod tests {
         // rank_counts, but this tests that our logic handles multiple four-of-a-kinds correctly
     }
 
+    #[test]
+    fn test_royal_flush_after_straight_flush() {
+        assert_poker_hands(
+            "9H TH JH QH KH TD JD QD KD AD",
+            &[
+                HighCard,
+                OnePair,
+                TwoPair,
+                Straight,
+                Flush,
+                StraightFlush,
+                RoyalFlush,
+            ],
+        );
+    }
+
+    #[test]
+    fn test_wild_cards() {
+        let mut hand = Card::make_hand("2S 5S 7S 9S JH").unwrap();
+        assert_poker_hands_of(&hand, &[HighCard]);
+
+        hand[4] = hand[4].with_enhancement(Enhancement::Wild);
+        assert_poker_hands_of(&hand, &[HighCard, Flush]);
+
+        let mut hand = Card::make_hand("2S 3S 4S 5S 6H").unwrap();
+        hand[4] = hand[4].with_enhancement(Enhancement::Wild);
+        assert_poker_hands_of(
+            &hand,
+            &[HighCard, Flush, Straight, StraightFlush],
+        );
+    }
+
+    #[test]
+    fn test_stone_cards() {
+        let mut hand = Card::make_hand("AS AH 2S 3S 4S 5S").unwrap();
+        assert_poker_hands_of(
+            &hand,
+            &[HighCard, OnePair, Straight, StraightFlush, Flush],
+        );
+
+        // Ace of spades turns to stone, the other ace still makes a wheel
+        hand[0] = hand[0].with_enhancement(Enhancement::Stone);
+        assert_poker_hands_of(&hand, &[HighCard, Straight]);
+
+        let stones = vec![hand[0]; 3];
+        assert_poker_hands_of(&stones, &[HighCard]);
+    }
+
+    #[test]
+    fn test_boss_blind() {
+        let hand = Card::make_hand("KS KH KC 5D 5H").unwrap();
+        let count = |blind: BossBlind| {
+            let mut count = PokerHandsCount::new();
+            count.add_mask(PokerHandResolver::contained_with_blind(
+                &hand, &blind,
+            ));
+            count
+        };
+
+        // Debuffs leave hand types alone
+        let mut expected = PokerHandsCount::new();
+        PokerHandResolver::poker_hands(&hand, &mut expected);
+        assert_eq!(count(BossBlind::ThePlant), expected);
+        assert_eq!(count(BossBlind::ThePsychic), expected);
+
+        let blind = BossBlind::TheEye {
+            played: vec![OnePair, FullHouse],
+        };
+        expected[OnePair] = 0;
+        expected[FullHouse] = 0;
+        assert_eq!(count(blind), expected);
+
+        let blind = BossBlind::TheMouth {
+            hand: Some(TwoPair),
+        };
+        assert_eq!(
+            count(blind),
+            PokerHandsCount::from_array(&[0, 0, 1, 0, 0, 0, 0, 0, 0, 0])
+        );
+
+        // Not enough cards for a five card play
+        assert_eq!(
+            PokerHandResolver::contained_with_blind(
+                &hand[..4],
+                &BossBlind::ThePsychic
+            ),
+            0
+        );
+    }
+
+    #[test]
+    fn test_constrained_queries() {
+        let hand = Card::make_hand("KS KH 5H 8H 9H AH AD").unwrap();
+        let rank = |c: char| {
+            move |card: &Card| {
+                !card.is_stone() && Some(card.rank.value) == Rank::from_char(c)
+            }
+        };
+        let suit = |c: char| {
+            let suit = Suit {
+                value: Suit::from_char(c).unwrap(),
+            };
+            move |card: &Card| card.is_suit(suit)
+        };
+
+        assert!(PokerHandResolver::contains_made_of(
+            &hand,
+            HandType::OnePair,
+            Card::is_face
+        ));
+        assert!(PokerHandResolver::contains_made_of(
+            &hand,
+            HandType::Flush,
+            suit('H')
+        ));
+        assert!(!PokerHandResolver::contains_made_of(
+            &hand,
+            HandType::Flush,
+            suit('S')
+        ));
+        assert!(!PokerHandResolver::contains_made_of(
+            &hand,
+            HandType::TwoPair,
+            Card::is_face
+        ));
+
+        // Pairs of Kings and Aces, but only Hearts make the flush
+        assert!(PokerHandResolver::contains_including(
+            &hand,
+            HandType::OnePair,
+            rank('A')
+        ));
+        assert!(!PokerHandResolver::contains_including(
+            &hand,
+            HandType::OnePair,
+            rank('5')
+        ));
+        assert!(PokerHandResolver::contains_including(
+            &hand,
+            HandType::Flush,
+            rank('K')
+        ));
+        assert!(!PokerHandResolver::contains_including(
+            &hand,
+            HandType::Flush,
+            suit('D')
+        ));
+
+        let hand = Card::make_hand("AS 2H 3C 4D 5S 9H").unwrap();
+        assert!(PokerHandResolver::contains_including(
+            &hand,
+            HandType::Straight,
+            rank('A')
+        ));
+        assert!(!PokerHandResolver::contains_including(
+            &hand,
+            HandType::Straight,
+            rank('9')
+        ));
+        assert!(!PokerHandResolver::contains_including(
+            &hand,
+            HandType::Straight,
+            Card::is_face
+        ));
+    }
+
     fn assert_poker_hands(hand_str: &str, expected_hands: &[PokerHand]) {
-        let hand = Card::make_hand(hand_str).unwrap();
+        assert_poker_hands_of(
+            &Card::make_hand(hand_str).unwrap(),
+            expected_hands,
+        );
+    }
+
+    fn assert_poker_hands_of(hand: &[Card], expected_hands: &[PokerHand]) {
         let mut hands_count = PokerHandsCount::new();
-        PokerHandResolver::poker_hands(&hand, &mut hands_count);
+        PokerHandResolver::poker_hands(hand, &mut hands_count);
+
+        // The table driven evaluator agrees on every hand in this suite
+        let mut evaluated = PokerHandsCount::new();
+        evaluated.add_mask(HandEvaluator::contained(hand));
+        assert_eq!(evaluated, hands_count);
 
         // Check that each element is at most 1
         for (i, &count) in hands_count.iter().enumerate() {
@@ -1,5 +1,5 @@
-use crate::poker::poker_hand::PokerHand;
 use crate::montecarlo::MonteCarloOutput;
+use crate::poker::poker_hand::PokerHand;
 use std::ops::{Index, IndexMut};
 use strum::EnumCount;
 
@@ -38,6 +38,30 @@ impl PokerHandsCount {
             self.counts[i] += count;
         }
     }
+
+    /// Counts every hand whose `PokerHand::bit` is set in `mask` once
+    #[inline]
+    pub fn add_mask(&mut self, mask: u16) {
+        for (i, count) in self.counts.iter_mut().enumerate() {
+            *count += (mask >> i & 1) as i64;
+        }
+    }
+
+    /// Counts every hand whose `PokerHand::bit` is set in `mask` `factor`
+    /// times
+    #[inline]
+    pub fn add_mask_scaled(&mut self, mask: u16, factor: i64) {
+        for (i, count) in self.counts.iter_mut().enumerate() {
+            *count += (mask >> i & 1) as i64 * factor;
+        }
+    }
+
+    /// Adds `other` as if it was counted `factor` times
+    pub fn merge_scaled(&mut self, other: &Self, factor: i64) {
+        for (i, &count) in other.counts.iter().enumerate() {
+            self.counts[i] += count * factor;
+        }
+    }
 }
 
 impl Index<PokerHand> for PokerHandsCount {
@@ -0,0 +1,141 @@
+use std::{sync::Mutex, time::Duration};
+
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use super::{hand_from_name, parse_error, PyDeck, PyPokerHandsCount};
+use crate::discarder::{DiscardResult, Discarder, Mode};
+
+fn mode_from_name(name: &str) -> PyResult<Mode> {
+    serde_json::from_value(name.into())
+        .map_err(|_| PyValueError::new_err(format!("unknown mode `{}`", name)))
+}
+
+fn mode_name(mode: Mode) -> String {
+    serde_json::to_value(mode)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+#[pyclass(name = "Discarder", module = "discarder")]
+pub struct PyDiscarder(Discarder);
+
+#[pymethods]
+impl PyDiscarder {
+    #[new]
+    #[pyo3(signature = (
+        deck,
+        hand_size = 8,
+        seed = 0,
+        mode = "auto",
+        time_budget = None,
+        threads = None,
+    ))]
+    fn new(
+        deck: &PyDeck,
+        hand_size: usize,
+        seed: u64,
+        mode: &str,
+        time_budget: Option<f64>,
+        threads: Option<usize>,
+    ) -> PyResult<Self> {
+        let time_budget = time_budget
+            .filter(|s| s.is_finite() && *s > 0.0)
+            .map(Duration::try_from_secs_f64)
+            .transpose()
+            .map_err(|e| {
+                PyValueError::new_err(format!("time_budget: {}", e))
+            })?;
+
+        let mut discarder = Discarder::new(deck.0.clone(), hand_size, seed)
+            .with_hand_in_deck(true)
+            .with_mode(mode_from_name(mode)?)
+            .with_time_budget(time_budget);
+        if let Some(threads) = threads {
+            discarder = discarder.with_threads(threads);
+        }
+
+        Ok(Self(discarder))
+    }
+
+    /// Counts the poker hands contained after drawing up to the hand size.
+    /// `hand` takes card notation and is drawn from the deck. `progress`, if given, is called as
+    /// `progress(iterations, fraction, counts)` while sampling; exceptions
+    /// it raises are re-raised once the run finishes.
+    #[pyo3(signature = (hand, max_iterations = 100_000, progress = None))]
+    fn run(
+        &self,
+        py: Python<'_>,
+        hand: &str,
+        max_iterations: usize,
+        progress: Option<PyObject>,
+    ) -> PyResult<PyDiscardResult> {
+        let hand = self.0.parse_hand(hand).map_err(parse_error)?;
+        let callback_error = Mutex::new(None);
+
+        let result = py.allow_threads(|| {
+            self.0.run(&hand, max_iterations, |p| {
+                let Some(progress) = &progress else {
+                    return;
+                };
+
+                Python::with_gil(|py| {
+                    let counts = PyPokerHandsCount(p.count.clone());
+                    let call = progress.call1(
+                        py,
+                        (p.iterations, p.fraction_completed, counts),
+                    );
+
+                    if let Err(error) = call {
+                        let mut slot = callback_error.lock().unwrap();
+                        slot.get_or_insert(error);
+                    }
+                });
+            })
+        });
+
+        match callback_error.into_inner().unwrap() {
+            Some(error) => Err(error),
+            None => Ok(PyDiscardResult(result)),
+        }
+    }
+}
+
+#[pyclass(name = "DiscardResult", module = "discarder", frozen)]
+pub struct PyDiscardResult(DiscardResult);
+
+#[pymethods]
+impl PyDiscardResult {
+    #[getter]
+    fn counts(&self) -> PyPokerHandsCount {
+        PyPokerHandsCount(self.0.count.clone())
+    }
+
+    #[getter]
+    fn iterations(&self) -> usize {
+        self.0.iterations
+    }
+
+    #[getter]
+    fn mode(&self) -> String {
+        mode_name(self.0.mode)
+    }
+
+    fn probability(&self, hand: &str) -> PyResult<f64> {
+        Ok(self.0.probability(hand_from_name(hand)?))
+    }
+
+    /// Wilson score interval, `z` = 1.96 for 95%
+    #[pyo3(signature = (hand, z = 1.96))]
+    fn confidence_interval(&self, hand: &str, z: f64) -> PyResult<(f64, f64)> {
+        Ok(self.0.confidence_interval(hand_from_name(hand)?, z))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "DiscardResult(mode='{}', iterations={})",
+            self.mode(),
+            self.0.iterations
+        )
+    }
+}
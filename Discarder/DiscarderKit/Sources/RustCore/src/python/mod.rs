@@ -0,0 +1,158 @@
+//! Python extension module, built with `maturin develop --features python`.
+
+// The pyo3 0.22 macros trip this lint on every `PyResult`
+#![allow(clippy::useless_conversion)]
+
+mod discarder;
+
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyDict};
+use std::str::FromStr;
+
+use crate::{
+    deck::{Card, Deck, Edition, Enhancement, ParseCardError, Seal},
+    poker::{PokerHand, PokerHandsCount},
+};
+use discarder::{PyDiscardResult, PyDiscarder};
+use strum::IntoEnumIterator;
+
+fn parse_error(error: ParseCardError) -> PyErr {
+    PyValueError::new_err((error.to_string(), error.token, error.position))
+}
+
+/// Snake case name used for poker hands on the Python side
+fn hand_name(hand: PokerHand) -> String {
+    serde_json::to_value(hand)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn hand_from_name(name: &str) -> PyResult<PokerHand> {
+    PokerHand::iter()
+        .find(|&hand| hand_name(hand) == name)
+        .ok_or_else(|| {
+            PyValueError::new_err(format!("unknown poker hand `{}`", name))
+        })
+}
+
+#[pyclass(name = "Card", module = "discarder", frozen, eq, hash)]
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct PyCard(Card);
+
+#[pymethods]
+impl PyCard {
+    /// Parses one card, e.g. `Card("AS:glass:red")`
+    #[new]
+    fn new(notation: &str) -> PyResult<Self> {
+        Card::from_str(notation).map(Self).map_err(parse_error)
+    }
+
+    #[getter]
+    fn rank(&self) -> u8 {
+        self.0.rank.value
+    }
+
+    #[getter]
+    fn suit(&self) -> char {
+        self.0.suit.to_char()
+    }
+
+    #[getter]
+    fn enhancement(&self) -> Option<&'static str> {
+        let enhancement = self.0.enhancement;
+        (enhancement != Enhancement::None).then(|| enhancement.into())
+    }
+
+    #[getter]
+    fn seal(&self) -> Option<&'static str> {
+        (self.0.seal != Seal::None).then(|| self.0.seal.into())
+    }
+
+    #[getter]
+    fn edition(&self) -> Option<&'static str> {
+        (self.0.edition != Edition::None).then(|| self.0.edition.into())
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Card('{}')", self.0)
+    }
+}
+
+/// Parses whitespace separated cards. Raises `ValueError(message, token,
+/// position)` for the first invalid card.
+#[pyfunction]
+fn parse_hand(notation: &str) -> PyResult<Vec<PyCard>> {
+    Card::make_hand(notation)
+        .map(|cards| cards.into_iter().map(PyCard).collect())
+        .map_err(parse_error)
+}
+
+#[pyclass(name = "Deck", module = "discarder")]
+#[derive(Clone)]
+pub struct PyDeck(Deck);
+
+#[pymethods]
+impl PyDeck {
+    /// Takes card notation or a preset name like `"standard"`
+    #[new]
+    #[pyo3(signature = (cards = "standard", seed = 0))]
+    fn new(cards: &str, seed: u64) -> PyResult<Self> {
+        Deck::parse(cards, seed).map(Self).map_err(parse_error)
+    }
+
+    fn cards(&self) -> Vec<PyCard> {
+        self.0.cards.iter().copied().map(PyCard).collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.cards.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<Deck of {} cards>", self.0.cards.len())
+    }
+}
+
+#[pyclass(name = "PokerHandsCount", module = "discarder", frozen)]
+#[derive(Clone)]
+pub struct PyPokerHandsCount(PokerHandsCount);
+
+#[pymethods]
+impl PyPokerHandsCount {
+    fn __getitem__(&self, hand: &str) -> PyResult<i64> {
+        Ok(self.0[hand_from_name(hand)?])
+    }
+
+    fn __len__(&self) -> usize {
+        PokerHand::iter().len()
+    }
+
+    /// Counts keyed by poker hand, weakest first
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        for hand in PokerHand::iter() {
+            dict.set_item(hand_name(hand), self.0[hand])?;
+        }
+        Ok(dict)
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(format!("PokerHandsCount({})", self.to_dict(py)?.repr()?))
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "discarder")]
+fn discarder_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyCard>()?;
+    m.add_class::<PyDeck>()?;
+    m.add_class::<PyPokerHandsCount>()?;
+    m.add_class::<PyDiscarder>()?;
+    m.add_class::<PyDiscardResult>()?;
+    m.add_function(wrap_pyfunction!(parse_hand, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,165 @@
+use crate::deck::{Card, Rank, Suit};
+use crate::scoring::MAX_PLAYED_CARDS;
+
+/// Ranks from Two to Ace
+const RANKS: usize = 13;
+const SUITS: usize = 4;
+
+/// How many cards of each rank and suit score in the best play, over many
+/// draws, for jokers triggering on particular cards like Bloodstone or
+/// Walkie Talkie. `ranks[r][n]` counts the draws whose best play scores `n`
+/// cards of rank `r + 2`. Wild cards score as every suit, Stone cards as no
+/// rank or suit.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScoringCardHistogram {
+    ranks: [[i64; MAX_PLAYED_CARDS + 1]; RANKS],
+    suits: [[i64; MAX_PLAYED_CARDS + 1]; SUITS],
+}
+
+impl ScoringCardHistogram {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts a draw whose best play scores `scoring`, `weight` times
+    pub fn add(&mut self, scoring: &[Card], weight: i64) {
+        let mut ranks = [0usize; RANKS];
+        let mut suits = [0usize; SUITS];
+
+        for card in scoring.iter().filter(|card| !card.is_stone()) {
+            let rank = (card.rank.value as usize).checked_sub(2);
+            if let Some(count) = rank.and_then(|rank| ranks.get_mut(rank)) {
+                *count += 1;
+            }
+            for suit in Suit::iter().filter(|&suit| card.is_suit(suit)) {
+                suits[suit.value as usize] += 1;
+            }
+        }
+
+        for (counts, n) in self.ranks.iter_mut().zip(ranks) {
+            counts[n.min(MAX_PLAYED_CARDS)] += weight;
+        }
+        for (counts, n) in self.suits.iter_mut().zip(suits) {
+            counts[n.min(MAX_PLAYED_CARDS)] += weight;
+        }
+    }
+
+    pub fn merge(&mut self, other: &Self) {
+        let counts = self.ranks.iter_mut().chain(self.suits.iter_mut());
+        let others = other.ranks.iter().chain(other.suits.iter());
+
+        for (counts, other) in counts.zip(others) {
+            for (count, &other) in counts.iter_mut().zip(other) {
+                *count += other;
+            }
+        }
+    }
+
+    /// Draws counted
+    pub fn total(&self) -> i64 {
+        self.suits[0].iter().sum()
+    }
+
+    /// Draws whose best play scores exactly `n` cards of `rank`
+    pub fn rank_count(&self, rank: Rank, n: usize) -> i64 {
+        self.rank_counts(rank)
+            .and_then(|counts| counts.get(n))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Draws whose best play scores exactly `n` cards of `suit`
+    pub fn suit_count(&self, suit: Suit, n: usize) -> i64 {
+        self.suits
+            .get(suit.value as usize)
+            .and_then(|counts| counts.get(n))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Expected number of scoring cards of `rank` per draw
+    pub fn rank_mean(&self, rank: Rank) -> f64 {
+        self.rank_counts(rank)
+            .map_or(0.0, |counts| self.mean(counts))
+    }
+
+    /// Expected number of scoring cards of `suit` per draw
+    pub fn suit_mean(&self, suit: Suit) -> f64 {
+        self.suits
+            .get(suit.value as usize)
+            .map_or(0.0, |counts| self.mean(counts))
+    }
+
+    /// Copy counts to C arrays, indexed by rank from Two or by suit, then
+    /// by the number of scoring cards
+    pub fn to_arrays(
+        &self,
+        ranks: &mut [[i64; MAX_PLAYED_CARDS + 1]; RANKS],
+        suits: &mut [[i64; MAX_PLAYED_CARDS + 1]; SUITS],
+    ) {
+        *ranks = self.ranks;
+        *suits = self.suits;
+    }
+
+    fn rank_counts(&self, rank: Rank) -> Option<&[i64; MAX_PLAYED_CARDS + 1]> {
+        self.ranks.get((rank.value as usize).checked_sub(2)?)
+    }
+
+    fn mean(&self, counts: &[i64]) -> f64 {
+        let total = self.total();
+        if total == 0 {
+            return 0.0;
+        }
+
+        let cards: i64 = counts.iter().zip(0..).map(|(&c, n)| c * n).sum();
+        cards as f64 / total as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::Enhancement;
+
+    fn rank(c: char) -> Rank {
+        Rank {
+            value: Rank::from_char(c).unwrap(),
+        }
+    }
+
+    fn suit(c: char) -> Suit {
+        Suit {
+            value: Suit::from_char(c).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_add() {
+        let mut histogram = ScoringCardHistogram::new();
+        let mut scoring = Card::make_hand("TH TS 4H 4D").unwrap();
+
+        histogram.add(&scoring, 3);
+        scoring[0] = scoring[0].with_enhancement(Enhancement::Wild);
+        scoring[3] = scoring[3].with_enhancement(Enhancement::Stone);
+        histogram.add(&scoring, 1);
+        histogram.add(&[], 1);
+
+        assert_eq!(histogram.total(), 5);
+        assert_eq!(histogram.rank_count(rank('T'), 2), 4);
+        assert_eq!(histogram.rank_count(rank('4'), 2), 3);
+        assert_eq!(histogram.rank_count(rank('4'), 1), 1);
+        assert_eq!(histogram.rank_count(rank('A'), 0), 5);
+        assert_eq!(histogram.suit_count(suit('H'), 2), 4);
+        assert_eq!(histogram.suit_count(suit('S'), 2), 1);
+        assert_eq!(histogram.suit_count(suit('D'), 1), 4);
+
+        // (3 x 2 + 1 x 1) fours over 5 draws
+        assert_eq!(histogram.rank_mean(rank('4')), 7.0 / 5.0);
+        assert_eq!(histogram.suit_mean(suit('C')), 1.0 / 5.0);
+
+        let mut merged = histogram.clone();
+        merged.merge(&histogram);
+        assert_eq!(merged.total(), 10);
+        assert_eq!(merged.rank_mean(rank('4')), 7.0 / 5.0);
+    }
+}
@@ -0,0 +1,419 @@
+mod card_histogram;
+mod score;
+
+pub use card_histogram::*;
+pub use score::*;
+
+use crate::{
+    deck::{Card, Edition, Enhancement, Seal},
+    jokers::JokerEffect,
+    poker::{
+        BossBlind, HandEvaluator, HandType, PokerHand, PokerHandResolver,
+        PokerHandsCount,
+    },
+};
+use strum::IntoEnumIterator;
+
+/// Most cards that can be played at once
+pub const MAX_PLAYED_CARDS: usize = 5;
+
+/// Everything jokers can look at when the played hand is scored.
+pub struct ScoringContext<'a> {
+    /// Best hand type formed by the played cards, secret hands included
+    pub hand: HandType,
+    /// Every poker hand contained in the played cards
+    pub contained: &'a PokerHandsCount,
+    pub played: &'a [Card],
+    /// Played cards that count towards the score, in played order
+    pub scoring: &'a [Card],
+    /// Cards left in hand
+    pub held: &'a [Card],
+}
+
+/// The best cards to play from a hand and what they score.
+#[derive(Debug, Clone)]
+pub struct Play {
+    pub cards: Vec<Card>,
+    pub hand: HandType,
+    /// Played cards that score, without those the blind debuffs
+    pub scoring: Vec<Card>,
+    pub score: Score,
+}
+
+pub struct Scorer;
+
+impl Scorer {
+    /// Tries every selection of up to five cards `blind` allows and returns
+    /// the one with the highest score after jokers.
+    pub fn best_play(
+        hand: &[Card],
+        jokers: &[Box<dyn JokerEffect>],
+        blind: &BossBlind,
+    ) -> Option<Play> {
+        let mut best: Option<Play> = None;
+        let mut played = Vec::with_capacity(MAX_PLAYED_CARDS);
+        let mut held = Vec::with_capacity(hand.len());
+
+        let max_played = hand.len().min(MAX_PLAYED_CARDS);
+        for mask in 1u32..(1 << hand.len()) {
+            let size = mask.count_ones() as usize;
+            if size > max_played || size < blind.min_played() {
+                continue;
+            }
+
+            played.clear();
+            held.clear();
+            for (i, &card) in hand.iter().enumerate() {
+                if mask & (1 << i) != 0 {
+                    played.push(card);
+                } else {
+                    held.push(card);
+                }
+            }
+
+            let (hand_type, score) = Self::score(&played, &held, jokers, blind);
+            if !blind.allows(hand_type) {
+                continue;
+            }
+
+            if best
+                .as_ref()
+                .is_none_or(|b| score.total() > b.score.total())
+            {
+                let scoring = Self::scoring_cards(hand_type, &played)
+                    .into_iter()
+                    .filter(|card| !blind.is_debuffed(card))
+                    .collect();
+
+                best = Some(Play {
+                    cards: played.clone(),
+                    hand: hand_type,
+                    scoring,
+                    score,
+                });
+            }
+        }
+
+        best
+    }
+
+    /// Scores `played` with `held` left in hand, in Balatro's order: base
+    /// hand, scoring cards left to right with their joker triggers, cards
+    /// held in hand, then jokers left to right. Cards debuffed by `blind`
+    /// are skipped. Secret hands score as themselves.
+    pub fn score(
+        played: &[Card],
+        held: &[Card],
+        jokers: &[Box<dyn JokerEffect>],
+        blind: &BossBlind,
+    ) -> (HandType, Score) {
+        let mut contained = PokerHandsCount::new();
+        PokerHandResolver::poker_hands(played, &mut contained);
+
+        let poker_hand = PokerHand::iter()
+            .rev()
+            .find(|&h| contained[h] > 0)
+            .unwrap_or(PokerHand::HighCard);
+        let secret = HandEvaluator::contained(played) & HandType::SECRET_HANDS;
+        let hand = match secret {
+            0 => poker_hand.into(),
+            // Highest secret hand, they are the last hand types
+            _ => HandType::from_raw(15 - secret.leading_zeros() as u8).unwrap(),
+        };
+
+        let scoring = Self::scoring_cards(hand, played);
+        let mut score = Score::base(hand, blind);
+
+        for card in scoring.iter().filter(|c| !blind.is_debuffed(c)) {
+            for _ in 0..Self::triggers(card) {
+                score.chips += Self::card_chips(card);
+
+                match card.enhancement {
+                    Enhancement::Mult => score.mult += 4.0,
+                    Enhancement::Glass => score.mult *= 2.0,
+                    // Expected value of the 1 in 5 chance for +20 mult
+                    Enhancement::Lucky => score.mult += 4.0,
+                    _ => {}
+                }
+
+                match card.edition {
+                    Edition::Foil => score.chips += 50.0,
+                    Edition::Holographic => score.mult += 10.0,
+                    Edition::Polychrome => score.mult *= 1.5,
+                    Edition::None => {}
+                }
+
+                for joker in jokers {
+                    joker.on_card_scored(card, &mut score);
+                }
+            }
+        }
+
+        for card in held.iter().filter(|c| !blind.is_debuffed(c)) {
+            if card.enhancement == Enhancement::Steel {
+                score.mult *= 1.5f64.powi(Self::triggers(card) as i32);
+            }
+        }
+
+        let context = ScoringContext {
+            hand,
+            contained: &contained,
+            played,
+            scoring: &scoring,
+            held,
+        };
+
+        for joker in jokers {
+            joker.on_hand_scored(&context, &mut score);
+        }
+
+        (hand, score)
+    }
+
+    /// How many times a card's effects apply, Red seals retrigger once.
+    /// Gold, Blue and Purple seals pay out money or consumables and leave
+    /// the score alone.
+    fn triggers(card: &Card) -> usize {
+        match card.seal {
+            Seal::Red => 2,
+            _ => 1,
+        }
+    }
+
+    /// Chips a card adds when it scores
+    pub fn card_chips(card: &Card) -> f64 {
+        let rank_chips = match card.rank.value {
+            _ if card.is_stone() => 0.0,
+            14 => 11.0,
+            11..=13 => 10.0,
+            value => value as f64,
+        };
+
+        let enhancement_chips = match card.enhancement {
+            Enhancement::Bonus => 30.0,
+            Enhancement::Stone => 50.0,
+            _ => 0.0,
+        };
+
+        rank_chips + enhancement_chips
+    }
+
+    /// Cards making up `hand` within `played`, Stone cards always score.
+    fn scoring_cards(hand: HandType, played: &[Card]) -> Vec<Card> {
+        let group_size = match hand {
+            HandType::HighCard => 1,
+            HandType::OnePair | HandType::TwoPair => 2,
+            HandType::ThreeOfAKind => 3,
+            HandType::FourOfAKind => 4,
+            // Five card hands score every played card
+            _ => return played.to_vec(),
+        };
+
+        let mut rank_counts = [0u8; 15];
+        for card in played.iter().filter(|c| !c.is_stone()) {
+            rank_counts[card.rank.value as usize] += 1;
+        }
+
+        // High card only scores the highest ranked card
+        let high_card = played
+            .iter()
+            .filter(|c| !c.is_stone())
+            .max_by_key(|c| c.rank.value)
+            .copied();
+
+        played
+            .iter()
+            .filter(|&card| {
+                card.is_stone()
+                    || match hand {
+                        HandType::HighCard => Some(*card) == high_card,
+                        _ => {
+                            rank_counts[card.rank.value as usize] >= group_size
+                        }
+                    }
+            })
+            .copied()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jokers::joker_by_name;
+    use std::str::FromStr;
+
+    fn score(played: &str, held: &str, jokers: &[&str]) -> (HandType, f64) {
+        let played = Card::make_hand(played).unwrap();
+        let held = Card::make_hand(held).unwrap();
+        let jokers: Vec<_> =
+            jokers.iter().map(|n| joker_by_name(n).unwrap()).collect();
+
+        let (hand, score) =
+            Scorer::score(&played, &held, &jokers, &BossBlind::None);
+        (hand, score.total())
+    }
+
+    #[test]
+    fn test_card_chips() {
+        let ace = Card::from_str("AS").unwrap();
+        assert_eq!(Scorer::card_chips(&ace), 11.0);
+        assert_eq!(Scorer::card_chips(&Card::from_str("KD").unwrap()), 10.0);
+        assert_eq!(Scorer::card_chips(&Card::from_str("7H").unwrap()), 7.0);
+        assert_eq!(
+            Scorer::card_chips(&ace.with_enhancement(Enhancement::Bonus)),
+            41.0
+        );
+        assert_eq!(
+            Scorer::card_chips(&ace.with_enhancement(Enhancement::Stone)),
+            50.0
+        );
+    }
+
+    #[test]
+    fn test_scoring_cards() {
+        // Only the pair scores
+        assert_eq!(score("AS AH KC 7D 2S", "", &[]), (HandType::OnePair, 64.0));
+        // Highest card only
+        assert_eq!(score("KS 7H 2C", "", &[]), (HandType::HighCard, 15.0));
+        // Every card of a straight scores: (30 + 20) x 4
+        assert_eq!(
+            score("2S 3H 4C 5D 6S", "", &[]),
+            (HandType::Straight, 200.0)
+        );
+        // (40 + 48) x 4
+        assert_eq!(
+            score("TS TH TC 9D 9S", "", &[]),
+            (HandType::FullHouse, 352.0)
+        );
+    }
+
+    #[test]
+    fn test_secret_hands() {
+        // (120 + 55) x 12
+        assert_eq!(
+            score("AS AS AH AC AD", "", &[]),
+            (HandType::FiveOfAKind, 2100.0)
+        );
+        // (140 + 53) x 14
+        assert_eq!(
+            score("AS AS AS KS KS", "", &[]),
+            (HandType::FlushHouse, 2702.0)
+        );
+        // (160 + 55) x 16
+        assert_eq!(
+            score("AS AS AS AS AS", "", &[]),
+            (HandType::FlushFive, 3440.0)
+        );
+
+        // Never locked in by The Mouth, but ruled out once it locked a hand
+        let hand = Card::make_hand("AS AS AS KS KS KH KH").unwrap();
+        let mouth = BossBlind::TheMouth {
+            hand: Some(PokerHand::FullHouse),
+        };
+        let play = Scorer::best_play(&hand, &[], &mouth).unwrap();
+        assert_eq!(play.hand, HandType::FullHouse);
+    }
+
+    #[test]
+    fn test_enhancements() {
+        let mut played = Card::make_hand("AS AH").unwrap();
+        played[0] = played[0].with_enhancement(Enhancement::Mult);
+        played[1] = played[1].with_enhancement(Enhancement::Glass);
+        let held = [Card::from_str("2C")
+            .unwrap()
+            .with_enhancement(Enhancement::Steel)];
+
+        // 32 chips, (2 + 4) x 2 x 1.5 mult
+        let (hand, score) =
+            Scorer::score(&played, &held, &[], &BossBlind::None);
+        assert_eq!(hand, HandType::OnePair);
+        assert_eq!(score.chips, 32.0);
+        assert_eq!(score.mult, 18.0);
+    }
+
+    #[test]
+    fn test_seals_and_editions() {
+        // Foil pair: (10 + 22 + 50) x 2
+        assert_eq!(score("AS:foil AH", "", &[]), (HandType::OnePair, 164.0));
+        // Holographic: 32 x (2 + 10)
+        assert_eq!(score("AS:holo AH", "", &[]), (HandType::OnePair, 384.0));
+        // Polychrome: 32 x 2 x 1.5
+        assert_eq!(
+            score("AS:polychrome AH", "", &[]),
+            (HandType::OnePair, 96.0)
+        );
+        // Red seal scores the ace twice: (10 + 33) x 2
+        assert_eq!(score("AS:red AH", "", &[]), (HandType::OnePair, 86.0));
+        // Other seals do not score
+        assert_eq!(
+            score("AS:gold_seal AH:blue AH:purple", "", &[]),
+            (HandType::ThreeOfAKind, 3.0 * 63.0)
+        );
+
+        // Glass and Fibonacci retrigger too:
+        // (10 + 33) x ((2 x 2 + 8) x 2 + 8 + 8)
+        assert_eq!(
+            score("AS:glass:red AH", "", &["Fibonacci"]),
+            (HandType::OnePair, 43.0 * 40.0)
+        );
+
+        // Retriggered steel card held in hand: 32 x 2 x 1.5 x 1.5
+        assert_eq!(
+            score("AS AH", "2C:steel:red", &[]),
+            (HandType::OnePair, 144.0)
+        );
+    }
+
+    #[test]
+    fn test_best_play() {
+        let hand = Card::make_hand("AS AH KC 7D 2S 3S 4S 5H").unwrap();
+
+        let play = Scorer::best_play(&hand, &[], &BossBlind::None).unwrap();
+        assert_eq!(play.hand, HandType::Straight);
+        assert_eq!(play.cards.len(), 5);
+        assert_eq!(play.scoring, play.cards);
+
+        // Only the pair scores, without the debuffed Ace of Spades
+        let hand = Card::make_hand("AS AH 9C 7D").unwrap();
+        let play = Scorer::best_play(&hand, &[], &BossBlind::TheGoad).unwrap();
+        assert_eq!(play.hand, HandType::OnePair);
+        assert_eq!(play.scoring, Card::make_hand("AH").unwrap());
+
+        assert!(Scorer::best_play(&[], &[], &BossBlind::None).is_none());
+    }
+
+    #[test]
+    fn test_boss_blinds() {
+        let hand = Card::make_hand("AS AH KC 7D 2S 3S 4S 5H").unwrap();
+        let best = |blind: BossBlind| {
+            Scorer::best_play(&hand, &[], &blind)
+                .map(|play| (play.hand, play.score.total()))
+        };
+
+        // Wheel: (30 + 25) x 4
+        assert_eq!(best(BossBlind::None), Some((HandType::Straight, 220.0)));
+        // Spades score nothing: (30 + 16) x 4
+        assert_eq!(best(BossBlind::TheGoad), Some((HandType::Straight, 184.0)));
+        // Halved base: (15 + 25) x 2
+        assert_eq!(best(BossBlind::TheFlint), Some((HandType::Straight, 80.0)));
+
+        let eye = BossBlind::TheEye {
+            played: vec![PokerHand::Straight],
+        };
+        assert_eq!(best(eye), Some((HandType::OnePair, 64.0)));
+
+        let mouth = BossBlind::TheMouth {
+            hand: Some(PokerHand::Flush),
+        };
+        assert_eq!(best(mouth), None);
+
+        // A pair padded to five cards with kickers
+        let play =
+            Scorer::best_play(&hand[..5], &[], &BossBlind::ThePsychic).unwrap();
+        assert_eq!(play.cards.len(), 5);
+        assert_eq!(play.hand, HandType::OnePair);
+        assert!(Scorer::best_play(&hand[..4], &[], &BossBlind::ThePsychic)
+            .is_none());
+    }
+}
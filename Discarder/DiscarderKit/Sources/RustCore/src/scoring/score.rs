@@ -0,0 +1,23 @@
+use crate::poker::{BossBlind, HandType};
+
+/// Chips and mult accumulated while a hand is scored.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Score {
+    pub chips: f64,
+    pub mult: f64,
+}
+
+impl Score {
+    pub fn new(chips: f64, mult: f64) -> Self {
+        Self { chips, mult }
+    }
+
+    pub fn base(hand: HandType, blind: &BossBlind) -> Self {
+        let (chips, mult) = blind.base_score(hand);
+        Self { chips, mult }
+    }
+
+    pub fn total(&self) -> f64 {
+        self.chips * self.mult
+    }
+}
@@ -0,0 +1,770 @@
+use std::{
+    marker::PhantomData,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    deck::{Card, Deck, DrawSampler},
+    discarder::Jokers,
+    montecarlo::{
+        with_rng, Chunk, MonteCarlo, MonteCarloAlgorithm,
+        MonteCarloAlgorithmFactory, MonteCarloConfiguration, MonteCarloOutput,
+        MonteCarloRng, Pcg64, RngKind,
+    },
+};
+
+use super::{
+    solver::{outcome, outcomes, Node, Step},
+    Action, KeepScoring, Policy, Round, RoundState,
+};
+
+/// Weight of exploration in UCB1, relative to the largest reward seen
+const EXPLORATION: f64 = std::f64::consts::SQRT_2;
+
+/// Nodes a thread grows its tree to at most. Every node holds all the
+/// actions of its hand, some 10 kB for eight cards, and the steps of those
+/// it tried.
+const MAX_NODES: usize = 512;
+
+/// What the advisor maximizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    /// Chance of reaching the target
+    Clear,
+    /// Mean score at the end of the round, playing every hand
+    Score,
+}
+
+/// How long the advisor searches.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Budget {
+    Iterations(usize),
+    Time(Duration),
+}
+
+/// Ranks the plays and discards of the current hand by Monte Carlo tree
+/// search, for rounds too large for the `Solver`.
+///
+/// Every iteration shuffles the cards left to draw, walks down the tree
+/// with UCB1 while refilling the hand from the shuffle, and finishes the
+/// round with `KeepScoring` after the first action not tried yet. Below
+/// the root, nodes first try what `KeepScoring` would do and widen to more
+/// actions and draws as they are visited. Every thread grows a tree of its
+/// own, only the visits of the current actions are merged.
+pub struct Advisor {
+    hand_size: usize,
+    goal: Goal,
+    budget: Budget,
+    seed: u64,
+    threads: usize,
+    rng: RngKind,
+}
+
+/// Actions of the current hand, most visited first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Advice {
+    pub actions: Vec<RankedAction>,
+    pub iterations: usize,
+}
+
+/// An action with the iterations that started with it and the mean of the
+/// goal they reached. Exploring worse actions further down keeps the mean
+/// somewhat below the value of playing on at best.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankedAction {
+    pub action: Action,
+    pub visits: usize,
+    pub value: f64,
+}
+
+impl Advisor {
+    /// Searches 10000 iterations for the best chance to clear the blind
+    pub fn new(hand_size: usize, seed: u64) -> Self {
+        use std::thread::available_parallelism;
+
+        Self {
+            hand_size,
+            goal: Goal::Clear,
+            budget: Budget::Iterations(10000),
+            seed,
+            threads: available_parallelism().map_or(1, |n| n.get()),
+            rng: RngKind::default(),
+        }
+    }
+
+    pub fn with_goal(mut self, goal: Goal) -> Self {
+        self.goal = goal;
+        self
+    }
+
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Generator shuffling the deck and the untried actions, see
+    /// `Discarder::with_rng`
+    pub fn with_rng(mut self, rng: RngKind) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    /// Needs a card in hand and a hand left to play
+    pub fn advise(&self, state: &RoundState) -> Advice {
+        assert!(!state.hand.is_empty() && state.hands_left > 0);
+
+        // The score goal only counts down from zero, so that the
+        // score reached is minus what is left
+        let remaining = match self.goal {
+            Goal::Clear => state.target - state.score,
+            Goal::Score => -state.score,
+        };
+        let root = Node {
+            hand: state.hand.to_vec(),
+            deck: state.deck.cards.clone(),
+            hands_left: state.hands_left,
+            discards_left: state.discards_left,
+            remaining,
+            blind: state.blind.clone(),
+        };
+
+        let actions: Vec<Action> = outcomes(&root, state.jokers)
+            .into_iter()
+            .map(|(action, _)| action)
+            .collect();
+
+        let root = Arc::new(root);
+        let configuration = MonteCarloConfiguration {
+            threads: self.threads,
+            chunk_size: 100,
+        };
+
+        let result = with_rng!(self.rng, R => {
+            let factory = TreeSearchFactory::<R> {
+                root: root.clone(),
+                hand_size: self.hand_size,
+                goal: self.goal,
+                jokers: state.jokers.clone(),
+                rng: PhantomData,
+            };
+
+            let mut mc = MonteCarlo::new(factory, configuration, self.seed);
+            match self.budget {
+                Budget::Iterations(iterations) => mc.run(iterations, |_| {}),
+                Budget::Time(budget) => run_for(&mut mc, budget, self.threads),
+            }
+        });
+
+        let totals = &result.output;
+        let mut actions: Vec<RankedAction> = actions
+            .into_iter()
+            .enumerate()
+            .map(|(i, action)| {
+                let visits = totals.visits.get(i).map_or(0, |&v| v as usize);
+                let reward = totals.rewards.get(i).copied().unwrap_or(0.0);
+                RankedAction {
+                    action,
+                    visits,
+                    value: if visits > 0 {
+                        reward / visits as f64
+                    } else {
+                        0.0
+                    },
+                }
+            })
+            .collect();
+        actions.sort_by(|a, b| {
+            b.visits.cmp(&a.visits).then(b.value.total_cmp(&a.value))
+        });
+
+        Advice {
+            actions,
+            iterations: result.iterations_done,
+        }
+    }
+}
+
+impl Policy for Advisor {
+    fn name(&self) -> &'static str {
+        "Advisor"
+    }
+
+    fn decide(&self, state: &RoundState) -> Action {
+        self.advise(state).actions.swap_remove(0).action
+    }
+}
+
+/// Samples on the calling thread for a tenth of `budget` to time an
+/// iteration, then on every thread for what is left of it
+fn run_for<R: MonteCarloRng>(
+    mc: &mut MonteCarlo<TreeSearchFactory<R>>,
+    budget: Duration,
+    threads: usize,
+) -> Chunk<TreeSearchTotals> {
+    let start = Instant::now();
+    while start.elapsed() < budget / 10 {
+        mc.step(10);
+    }
+
+    let done = mc.chunk().iterations_done;
+    if done == 0 {
+        return mc.chunk().clone();
+    }
+
+    let iterations = iterations_left(budget, start.elapsed(), done, threads);
+    mc.run(iterations, |_| {})
+}
+
+/// Iterations `threads` threads sample in what is left of `budget`, at the
+/// pace of `done` iterations in `elapsed` on one thread.
+fn iterations_left(
+    budget: Duration,
+    elapsed: Duration,
+    done: usize,
+    threads: usize,
+) -> usize {
+    let left = budget.saturating_sub(elapsed).as_secs_f64();
+    let per_thread = (left * done as f64 / elapsed.as_secs_f64()) as usize;
+
+    per_thread.saturating_mul(threads)
+}
+
+/// Visits and summed rewards of every action of the current hand. Starts
+/// without actions, merging adopts them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TreeSearchTotals {
+    visits: Vec<i64>,
+    rewards: Vec<f64>,
+}
+
+impl MonteCarloOutput for TreeSearchTotals {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn merge(&mut self, other: &Self) {
+        if self.visits.is_empty() {
+            *self = other.clone();
+            return;
+        }
+
+        for (a, b) in self.visits.iter_mut().zip(&other.visits) {
+            *a += b;
+        }
+        for (a, b) in self.rewards.iter_mut().zip(&other.rewards) {
+            *a += b;
+        }
+    }
+}
+
+pub struct TreeSearchFactory<R: MonteCarloRng = Pcg64> {
+    root: Arc<Node>,
+    hand_size: usize,
+    goal: Goal,
+    jokers: Jokers,
+    rng: PhantomData<R>,
+}
+
+impl<R: MonteCarloRng> MonteCarloAlgorithmFactory for TreeSearchFactory<R> {
+    type Algorithm = TreeSearchAlgorithm<R>;
+    type Rng = R;
+
+    fn make(&self, seed: u64) -> TreeSearchAlgorithm<R> {
+        let deck = Deck::new(&self.root.deck);
+        let size = deck.cards.len();
+        let mut rng = R::seed_from_u64(seed);
+
+        TreeSearchAlgorithm {
+            hand_size: self.hand_size,
+            goal: self.goal,
+            jokers: self.jokers.clone(),
+            nodes: vec![TreeNode::new(
+                self.root.as_ref().clone(),
+                &self.jokers,
+                &mut rng,
+            )],
+            scale: 1.0,
+            path: Vec::new(),
+            shuffled: vec![Card::invalid(); size],
+            shuffler: Round::new(deck, 0, 0.0).shuffler(),
+            rng,
+        }
+    }
+}
+
+/// A decision state of the tree with the states the actions tried so far
+/// lead to before the hand is refilled.
+struct TreeNode {
+    /// Without the deck, the draws come from the shuffle
+    state: Node,
+    edges: Vec<Edge>,
+    /// Actions never taken yet, their steps are only worked out once taken
+    untried: Vec<Untried>,
+    visits: usize,
+}
+
+/// An action by its place among the outcomes of the node and the cards of
+/// the hand it picks
+#[derive(Clone, Copy)]
+struct Untried {
+    index: usize,
+    mask: u64,
+    discard: bool,
+}
+
+struct Edge {
+    /// Place among the outcomes of the node
+    index: usize,
+    step: Step,
+    visits: usize,
+    /// Sum of the rewards of the iterations through the edge
+    total: f64,
+    /// Nodes reached by refilling the hand, by sorted hand
+    children: Vec<(Vec<Card>, usize)>,
+}
+
+impl TreeNode {
+    /// Tries the actions in a random order
+    fn new(state: Node, jokers: &Jokers, rng: &mut impl Rng) -> Self {
+        let mut untried: Vec<Untried> = outcomes(&state, jokers)
+            .into_iter()
+            .enumerate()
+            .map(|(index, (action, _))| {
+                let (picked, discard) = match action {
+                    Action::Play(picked) => (picked, false),
+                    Action::Discard(picked) => (picked, true),
+                };
+                Untried {
+                    index,
+                    mask: picked.iter().fold(0, |mask, &i| mask | 1 << i),
+                    discard,
+                }
+            })
+            .collect();
+        untried.shuffle(rng);
+
+        Self {
+            state: Node {
+                deck: Vec::new(),
+                ..state
+            },
+            edges: Vec::new(),
+            untried,
+            visits: 0,
+        }
+    }
+
+    /// Number of actions of the hand
+    fn actions(&self) -> usize {
+        self.edges.len() + self.untried.len()
+    }
+
+    /// Takes the next untried action, returning its edge
+    fn try_next(&mut self, jokers: &Jokers) -> Option<usize> {
+        let untried = self.untried.pop()?;
+        let (_, step) =
+            outcome(&self.state, untried.mask, untried.discard, jokers);
+
+        self.edges.push(Edge {
+            index: untried.index,
+            step,
+            visits: 0,
+            total: 0.0,
+            children: Vec::new(),
+        });
+        Some(self.edges.len() - 1)
+    }
+
+    /// Tries the action keeping the same cards as `action` first
+    fn prefer(&mut self, action: &Action) {
+        let (picked, discard) = match action {
+            Action::Play(picked) => (picked, false),
+            Action::Discard(picked) => (picked, true),
+        };
+        let hand = &self.state.hand;
+        let kept = |mask: u64| {
+            (0..hand.len())
+                .filter(move |&i| mask & 1 << i == 0)
+                .map(move |i| hand[i])
+        };
+        let mask = picked.iter().fold(0, |mask, &i| mask | 1 << i);
+
+        let same = |untried: &Untried| {
+            untried.discard == discard && kept(untried.mask).eq(kept(mask))
+        };
+        if let Some(i) = self.untried.iter().position(same) {
+            let last = self.untried.len() - 1;
+            self.untried.swap(i, last);
+        }
+    }
+}
+
+/// Edges a node, or children an edge, taken `visits` times may have. The
+/// tree widens progressively as it is visited.
+fn widened(visits: usize) -> usize {
+    ((visits as f64).sqrt() as usize).max(1)
+}
+
+pub struct TreeSearchAlgorithm<R: MonteCarloRng = Pcg64> {
+    hand_size: usize,
+    goal: Goal,
+    jokers: Jokers,
+    /// The root first, with the actions of the current hand
+    nodes: Vec<TreeNode>,
+    /// Largest reward so far, scaling exploration
+    scale: f64,
+    /// Node and edge taken at every level of the current iteration
+    path: Vec<(usize, usize)>,
+    shuffled: Vec<Card>,
+    shuffler: DrawSampler,
+    rng: R,
+}
+
+impl<R: MonteCarloRng> TreeSearchAlgorithm<R> {
+    /// An untried edge of `node` while the node may widen, always at the
+    /// root, otherwise the one with the highest UCB1 bound. Tells whether
+    /// the edge is new.
+    fn select(&mut self, node: usize) -> (usize, bool) {
+        let root = node == 0;
+        let node = &mut self.nodes[node];
+        if root || node.edges.len() < widened(node.visits) {
+            if let Some(edge) = node.try_next(&self.jokers) {
+                return (edge, true);
+            }
+        }
+
+        let log_visits = (node.visits as f64).ln();
+        let bound = |edge: &Edge| {
+            let visits = edge.visits as f64;
+            edge.total / visits
+                + EXPLORATION * self.scale * (log_visits / visits).sqrt()
+        };
+
+        let edge = (0..node.edges.len())
+            .max_by(|&a, &b| {
+                bound(&node.edges[a]).total_cmp(&bound(&node.edges[b]))
+            })
+            .unwrap();
+        (edge, false)
+    }
+
+    /// Whether the round is over right after `step`
+    fn is_over(&self, step: &Step) -> bool {
+        step.hands_left == 0
+            || self.goal == Goal::Clear && step.remaining <= 0.0
+    }
+
+    /// Reward of a round over with `remaining` left to score
+    fn reward(&self, remaining: f64) -> f64 {
+        match self.goal {
+            Goal::Clear => (remaining <= 0.0) as u8 as f64,
+            Goal::Score => -remaining,
+        }
+    }
+
+    /// What `KeepScoring` sees in `node`
+    fn round_state<'a>(
+        &'a self,
+        node: &'a Node,
+        deck: &'a Deck,
+    ) -> RoundState<'a> {
+        RoundState {
+            hand: &node.hand,
+            deck,
+            hands_left: node.hands_left,
+            discards_left: node.discards_left,
+            score: 0.0,
+            target: self.target(node.remaining),
+            blind: &node.blind,
+            jokers: &self.jokers,
+        }
+    }
+
+    /// Target of a round left to play with `remaining` left to score
+    fn target(&self, remaining: f64) -> f64 {
+        match self.goal {
+            Goal::Clear => remaining,
+            Goal::Score => f64::INFINITY,
+        }
+    }
+
+    /// Finishes the round after `step` with `KeepScoring`, drawing what is
+    /// left of the shuffle from `drawn` on
+    fn roll_out(&self, step: &Step, drawn: usize) -> f64 {
+        let target = self.target(step.remaining);
+        let round = Round::new(Deck::new(&[]), self.hand_size, target)
+            .with_hands(step.hands_left)
+            .with_discards(step.discards_left)
+            .with_boss_blind(step.blind.clone())
+            .with_jokers(self.jokers.clone());
+
+        let mut order = step.kept.clone();
+        order.extend_from_slice(&self.shuffled[drawn..]);
+        let outcome = round.play(&KeepScoring, &order);
+
+        match self.goal {
+            Goal::Clear => outcome.won as u8 as f64,
+            Goal::Score => outcome.score - step.remaining,
+        }
+    }
+
+    /// Node reached from `edge` of `node` with the sorted `hand`, grown
+    /// while the edge has been taken often enough for one more child and
+    /// the tree is not full. `None` if the iteration has to leave the tree.
+    fn child(
+        &mut self,
+        node: usize,
+        edge: usize,
+        hand: Vec<Card>,
+        drawn: usize,
+    ) -> Option<usize> {
+        let taken = &self.nodes[node].edges[edge];
+        if let Some(&(_, child)) =
+            taken.children.iter().find(|(cards, _)| *cards == hand)
+        {
+            return Some(child);
+        }
+
+        if self.nodes.len() >= MAX_NODES
+            || taken.children.len() >= widened(taken.visits)
+        {
+            return None;
+        }
+
+        let state = Node {
+            hand: hand.clone(),
+            deck: self.shuffled[drawn..].to_vec(),
+            hands_left: taken.step.hands_left,
+            discards_left: taken.step.discards_left,
+            remaining: taken.step.remaining,
+            blind: taken.step.blind.clone(),
+        };
+        let deck = Deck::new(&state.deck);
+        let action = KeepScoring.decide(&self.round_state(&state, &deck));
+        let mut tree_node = TreeNode::new(state, &self.jokers, &mut self.rng);
+        tree_node.prefer(&action);
+
+        let child = self.nodes.len();
+        self.nodes.push(tree_node);
+        self.nodes[node].edges[edge].children.push((hand, child));
+
+        Some(child)
+    }
+}
+
+impl<R: MonteCarloRng> MonteCarloAlgorithm for TreeSearchAlgorithm<R> {
+    type Output = TreeSearchTotals;
+
+    fn sample(&mut self, output: &mut Self::Output) {
+        let actions = self.nodes[0].actions();
+        if output.visits.len() != actions {
+            output.visits = vec![0; actions];
+            output.rewards = vec![0.0; actions];
+        }
+
+        self.shuffler.sample_draw(&mut self.rng, &mut self.shuffled);
+        self.path.clear();
+
+        let mut node = 0;
+        let mut drawn = 0;
+        let reward = loop {
+            let (edge, new) = self.select(node);
+            self.path.push((node, edge));
+
+            let step = &self.nodes[node].edges[edge].step;
+            if self.is_over(step) {
+                break self.reward(step.remaining);
+            }
+            if new {
+                break self.roll_out(step, drawn);
+            }
+
+            let refill = self
+                .hand_size
+                .saturating_sub(step.kept.len())
+                .min(self.shuffled.len() - drawn);
+            let mut hand = step.kept.clone();
+            hand.extend_from_slice(&self.shuffled[drawn..drawn + refill]);
+            hand.sort_unstable();
+            if hand.is_empty() {
+                break self.reward(step.remaining);
+            }
+
+            match self.child(node, edge, hand, drawn + refill) {
+                Some(child) => {
+                    node = child;
+                    drawn += refill;
+                }
+                None => {
+                    let step = &self.nodes[node].edges[edge].step;
+                    break self.roll_out(step, drawn);
+                }
+            }
+        };
+
+        self.scale = self.scale.max(reward);
+        for &(node, edge) in &self.path {
+            let node = &mut self.nodes[node];
+            node.visits += 1;
+            node.edges[edge].visits += 1;
+            node.edges[edge].total += reward;
+        }
+
+        let (_, edge) = self.path[0];
+        let action = self.nodes[0].edges[edge].index;
+        output.visits[action] += 1;
+        output.rewards[action] += reward;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poker::BossBlind;
+
+    fn advise(
+        advisor: &Advisor,
+        hand: &str,
+        deck: &Deck,
+        hands_left: usize,
+        discards_left: usize,
+        target: f64,
+    ) -> Advice {
+        let hand = Card::make_hand(hand).unwrap();
+        let state = RoundState {
+            hand: &hand,
+            deck,
+            hands_left,
+            discards_left,
+            score: 0.0,
+            target,
+            blind: &BossBlind::None,
+            jokers: &Jokers::default(),
+        };
+        advisor.advise(&state)
+    }
+
+    fn deck_without(hand: &str) -> Deck {
+        let cards = Card::make_hand(hand).unwrap();
+        Deck::new(
+            &Deck::make_standard()
+                .cards
+                .into_iter()
+                .filter(|card| !cards.contains(card))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn test_clear() {
+        let hand = "AH KH QH JH 2C";
+        let advisor = Advisor::new(5, 43)
+            .with_threads(4)
+            .with_budget(Budget::Iterations(4000));
+
+        // A Heart or a Ten out of 47 cards, on the discard of the Two
+        let advice = advise(&advisor, hand, &deck_without(hand), 1, 1, 300.0);
+        assert_eq!(advice.iterations, 4000);
+        let visits: usize = advice.actions.iter().map(|a| a.visits).sum();
+        assert_eq!(visits, 4000);
+
+        let best = &advice.actions[0];
+        assert_eq!(best.action, Action::Discard(vec![4]));
+        assert!((best.value - 12.0 / 47.0).abs() < 0.06);
+        assert!(advice.actions[1].visits < best.visits);
+    }
+
+    #[test]
+    fn test_score() {
+        let hand = "AS AH 2C 7D";
+        let deck = Deck::new(&Card::make_hand("3S 4H 5C").unwrap());
+        let advisor = Advisor::new(4, 43)
+            .with_threads(4)
+            .with_goal(Goal::Score)
+            .with_budget(Budget::Iterations(2000));
+
+        // Scores the pair of aces whatever the target, with or without
+        // kickers: (10 + 22) x 2
+        let advice = advise(&advisor, hand, &deck, 1, 0, 10.0);
+        let best = &advice.actions[0];
+        assert!(
+            matches!(&best.action, Action::Play(picked) if picked[..2] == [0, 1])
+        );
+        assert_eq!(best.value, 64.0);
+        assert!(advice.actions.iter().all(|a| a.value <= 64.0));
+    }
+
+    #[test]
+    fn test_time_budget() {
+        let hand = "AS KD 9C 7H 5S 4D 3C 2H";
+        let advisor = Advisor::new(8, 43)
+            .with_threads(4)
+            .with_budget(Budget::Time(Duration::from_millis(200)));
+
+        let start = Instant::now();
+        let advice = advise(&advisor, hand, &deck_without(hand), 4, 3, 300.0);
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert!(advice.iterations > 0);
+
+        let visits: usize = advice.actions.iter().map(|a| a.visits).sum();
+        assert_eq!(visits, advice.iterations);
+        assert!(advice
+            .actions
+            .windows(2)
+            .all(|pair| pair[0].visits >= pair[1].visits));
+    }
+
+    #[test]
+    fn test_lazy_steps() {
+        let hand = "AS KD 9C 7H 5S 4D 3C 2H";
+        let root = Node {
+            hand: Card::make_hand(hand).unwrap(),
+            deck: deck_without(hand).cards,
+            hands_left: 4,
+            discards_left: 3,
+            remaining: 300.0,
+            blind: BossBlind::None,
+        };
+        let factory = TreeSearchFactory::<Pcg64> {
+            root: Arc::new(root),
+            hand_size: 8,
+            goal: Goal::Clear,
+            jokers: Jokers::default(),
+            rng: PhantomData,
+        };
+
+        let mut algorithm = factory.make(43);
+        let mut totals = TreeSearchTotals::default();
+        for _ in 0..500 {
+            algorithm.sample(&mut totals);
+        }
+
+        // Below the root, only the actions taken hold a step
+        let nodes = &algorithm.nodes;
+        assert!(nodes.len() > 1);
+        assert_eq!(nodes[0].actions(), totals.visits.len());
+        for node in &nodes[1..] {
+            assert!(node.edges.len() <= widened(node.visits));
+            assert!(node.edges.iter().all(|edge| edge.visits > 0));
+        }
+    }
+
+    #[test]
+    fn test_iterations_left() {
+        let ms = Duration::from_millis;
+
+        assert_eq!(iterations_left(ms(100), ms(10), 50, 4), 1800);
+
+        // Calibration overran the budget
+        assert_eq!(iterations_left(ms(100), ms(99), 50, 4), 0);
+        assert_eq!(iterations_left(ms(100), ms(150), 50, 4), 0);
+    }
+}
@@ -0,0 +1,71 @@
+mod advisor;
+mod policies;
+mod round;
+mod simulation;
+mod solver;
+
+pub use advisor::*;
+pub use policies::*;
+pub use round::*;
+pub use simulation::*;
+pub use solver::*;
+
+use crate::{
+    deck::{Card, Deck},
+    discarder::Jokers,
+    poker::BossBlind,
+};
+
+/// What a policy sees before each decision of a round.
+pub struct RoundState<'a> {
+    pub hand: &'a [Card],
+    /// Cards left to draw, in no particular order
+    pub deck: &'a Deck,
+    pub hands_left: usize,
+    pub discards_left: usize,
+    /// Score reached so far
+    pub score: f64,
+    pub target: f64,
+    pub blind: &'a BossBlind,
+    pub jokers: &'a Jokers,
+}
+
+impl RoundState<'_> {
+    /// Score still missing to win the round
+    pub fn remaining(&self) -> f64 {
+        (self.target - self.score).max(0.0)
+    }
+}
+
+/// Cards of the hand to play or discard, by index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Play(Vec<usize>),
+    Discard(Vec<usize>),
+}
+
+/// A strategy for playing a round, deciding on every turn which cards to
+/// play or discard.
+pub trait Policy: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Plays and discards take one to five cards of the hand, discards
+    /// only while some are left.
+    fn decide(&self, state: &RoundState) -> Action;
+}
+
+/// Every built-in policy, simplest first.
+pub fn all_policies() -> Vec<Box<dyn Policy>> {
+    vec![
+        Box::new(PlayBest),
+        Box::new(ChaseFlush::new(4)),
+        Box::new(KeepScoring),
+    ]
+}
+
+/// Looks a built-in policy up by name, ignoring case.
+pub fn policy_by_name(name: &str) -> Option<Box<dyn Policy>> {
+    all_policies()
+        .into_iter()
+        .find(|policy| policy.name().eq_ignore_ascii_case(name))
+}
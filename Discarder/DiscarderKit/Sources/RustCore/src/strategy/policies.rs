@@ -0,0 +1,234 @@
+use crate::{
+    deck::{Card, Suit},
+    poker::HandType,
+    scoring::{Play, Scorer, MAX_PLAYED_CARDS},
+};
+
+use super::{Action, Policy, RoundState};
+
+/// Plays the highest scoring cards every turn and never discards.
+pub struct PlayBest;
+
+impl Policy for PlayBest {
+    fn name(&self) -> &'static str {
+        "Play Best"
+    }
+
+    fn decide(&self, state: &RoundState) -> Action {
+        play(state, best_play(state).as_ref())
+    }
+}
+
+/// Discards the cards off the most common suit while at least
+/// `min_suited` cards share it and the deck holds enough of it to complete
+/// a Flush, until a Flush or better can be played or the best play wins the
+/// round.
+pub struct ChaseFlush {
+    min_suited: usize,
+}
+
+impl ChaseFlush {
+    pub fn new(min_suited: usize) -> Self {
+        Self { min_suited }
+    }
+}
+
+impl Policy for ChaseFlush {
+    fn name(&self) -> &'static str {
+        "Chase Flush"
+    }
+
+    fn decide(&self, state: &RoundState) -> Action {
+        let best = best_play(state);
+        let settled = best.as_ref().is_some_and(|play| {
+            play.hand >= HandType::Flush
+                || play.score.total() >= state.remaining()
+        });
+        if state.discards_left == 0 || settled {
+            return play(state, best.as_ref());
+        }
+
+        let suited = |cards: &[Card], suit: Suit| {
+            cards.iter().filter(|card| card.is_suit(suit)).count()
+        };
+        let Some(suit) =
+            Suit::iter().max_by_key(|&suit| suited(state.hand, suit))
+        else {
+            return play(state, best.as_ref());
+        };
+
+        let in_hand = suited(state.hand, suit);
+        let in_deck = suited(&state.deck.cards, suit);
+        if in_hand < self.min_suited || in_hand + in_deck < 5 {
+            return play(state, best.as_ref());
+        }
+
+        let off_suit = (0..state.hand.len())
+            .filter(|&i| !state.hand[i].is_suit(suit))
+            .collect();
+        discard(state, off_suit).unwrap_or_else(|| play(state, best.as_ref()))
+    }
+}
+
+/// Plays the best cards once they score at least an even share of what is
+/// left to win over the remaining hands, otherwise discards the cards that
+/// would not score.
+pub struct KeepScoring;
+
+impl Policy for KeepScoring {
+    fn name(&self) -> &'static str {
+        "Keep Scoring"
+    }
+
+    fn decide(&self, state: &RoundState) -> Action {
+        let best = best_play(state);
+        let pace = state.remaining() / state.hands_left.max(1) as f64;
+        let Some(scoring) = best
+            .as_ref()
+            .filter(|play| play.score.total() < pace)
+            .map(|play| indices_of(state.hand, &play.scoring))
+        else {
+            return play(state, best.as_ref());
+        };
+        if state.discards_left == 0 {
+            return play(state, best.as_ref());
+        }
+
+        let idle = (0..state.hand.len())
+            .filter(|i| !scoring.contains(i))
+            .collect();
+        discard(state, idle).unwrap_or_else(|| play(state, best.as_ref()))
+    }
+}
+
+fn best_play(state: &RoundState) -> Option<Play> {
+    Scorer::best_play(state.hand, state.jokers, state.blind)
+}
+
+/// Plays `best`, or as many cards as allowed if the blind allows no play
+/// at all
+fn play(state: &RoundState, best: Option<&Play>) -> Action {
+    match best {
+        Some(play) => Action::Play(indices_of(state.hand, &play.cards)),
+        None => {
+            Action::Play((0..state.hand.len().min(MAX_PLAYED_CARDS)).collect())
+        }
+    }
+}
+
+/// Discards up to five of `candidates`, those adding the fewest chips
+/// first. `None` if there is nothing to discard.
+fn discard(state: &RoundState, mut candidates: Vec<usize>) -> Option<Action> {
+    if candidates.is_empty() {
+        return None;
+    }
+
+    candidates.sort_by(|&a, &b| {
+        let chips = |i: usize| Scorer::card_chips(&state.hand[i]);
+        chips(a).total_cmp(&chips(b))
+    });
+    candidates.truncate(MAX_PLAYED_CARDS);
+    candidates.sort_unstable();
+
+    Some(Action::Discard(candidates))
+}
+
+/// Positions of `cards` in `hand`, taking each position at most once as
+/// hands can hold identical cards
+fn indices_of(hand: &[Card], cards: &[Card]) -> Vec<usize> {
+    let mut taken = vec![false; hand.len()];
+
+    cards
+        .iter()
+        .filter_map(|card| {
+            let i = (0..hand.len()).find(|&i| !taken[i] && hand[i] == *card)?;
+            taken[i] = true;
+            Some(i)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        deck::Deck,
+        discarder::Jokers,
+        poker::BossBlind,
+        strategy::{all_policies, policy_by_name},
+    };
+
+    fn decide(
+        policy: &dyn Policy,
+        hand: &str,
+        deck: &Deck,
+        target: f64,
+        discards_left: usize,
+    ) -> Action {
+        let hand = Card::make_hand(hand).unwrap();
+        let state = RoundState {
+            hand: &hand,
+            deck,
+            hands_left: 4,
+            discards_left,
+            score: 0.0,
+            target,
+            blind: &BossBlind::None,
+            jokers: &Jokers::default(),
+        };
+        policy.decide(&state)
+    }
+
+    #[test]
+    fn test_play_best() {
+        let deck = Deck::make_standard();
+        let action = decide(&PlayBest, "7D AS 2C AH", &deck, 1000.0, 3);
+        assert_eq!(action, Action::Play(vec![1, 3]));
+    }
+
+    #[test]
+    fn test_chase_flush() {
+        let hand = "AH 2H 7H 9H KS QD 3C 4C";
+        let deck = Deck::make_standard();
+        let chase = |min_suited, deck, target, discards_left| {
+            let policy = ChaseFlush::new(min_suited);
+            decide(&policy, hand, deck, target, discards_left)
+        };
+        assert_eq!(
+            chase(4, &deck, 1000.0, 3),
+            Action::Discard(vec![4, 5, 6, 7])
+        );
+
+        // Not enough suited cards, none left to draw, no discards left, or
+        // already winning
+        let spades = Deck::new(&Card::make_hand("2S 3S 4S 5S 6S").unwrap());
+        assert!(matches!(chase(5, &deck, 1000.0, 3), Action::Play(_)));
+        assert!(matches!(chase(4, &spades, 1000.0, 3), Action::Play(_)));
+        assert!(matches!(chase(4, &deck, 1000.0, 0), Action::Play(_)));
+        assert_eq!(chase(4, &deck, 10.0, 3), Action::Play(vec![0]));
+    }
+
+    #[test]
+    fn test_keep_scoring() {
+        let hand = "AS AH 2C 7D";
+        let deck = Deck::make_standard();
+
+        // A pair of aces scores 64, short of 1000 / 4 but not of 200 / 4
+        let action = decide(&KeepScoring, hand, &deck, 1000.0, 3);
+        assert_eq!(action, Action::Discard(vec![2, 3]));
+        let action = decide(&KeepScoring, hand, &deck, 200.0, 3);
+        assert_eq!(action, Action::Play(vec![0, 1]));
+        let action = decide(&KeepScoring, hand, &deck, 1000.0, 0);
+        assert_eq!(action, Action::Play(vec![0, 1]));
+    }
+
+    #[test]
+    fn test_policy_by_name() {
+        assert_eq!(
+            policy_by_name("chase flush").unwrap().name(),
+            "Chase Flush"
+        );
+        assert!(policy_by_name("Not A Policy").is_none());
+        assert_eq!(all_policies().len(), 3);
+    }
+}
@@ -0,0 +1,226 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    deck::{Card, Deck, DrawMethod, DrawSampler},
+    discarder::Jokers,
+    poker::BossBlind,
+    scoring::{Scorer, MAX_PLAYED_CARDS},
+};
+
+use super::{Action, Policy, RoundState};
+
+/// Rules of a single round: `hand_size` cards are drawn from the shuffled
+/// deck, and the round is won by reaching `target` within `hands` plays.
+/// Played and discarded cards are replaced from the deck.
+#[derive(Clone)]
+pub struct Round {
+    pub deck: Deck,
+    pub hand_size: usize,
+    pub hands: usize,
+    pub discards: usize,
+    pub target: f64,
+    pub blind: BossBlind,
+    pub jokers: Jokers,
+}
+
+/// How a round played out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoundOutcome {
+    pub score: f64,
+    pub won: bool,
+    pub hands_played: usize,
+    pub discards_used: usize,
+}
+
+impl Round {
+    /// A round with the game's default four hands and three discards
+    pub fn new(deck: Deck, hand_size: usize, target: f64) -> Self {
+        Self {
+            deck,
+            hand_size,
+            hands: 4,
+            discards: 3,
+            target,
+            blind: BossBlind::None,
+            jokers: Jokers::default(),
+        }
+    }
+
+    pub fn with_hands(mut self, hands: usize) -> Self {
+        self.hands = hands;
+        self
+    }
+
+    pub fn with_discards(mut self, discards: usize) -> Self {
+        self.discards = discards;
+        self
+    }
+
+    pub fn with_boss_blind(mut self, blind: BossBlind) -> Self {
+        self.blind = blind;
+        self
+    }
+
+    pub fn with_jokers(mut self, jokers: Jokers) -> Self {
+        self.jokers = jokers;
+        self
+    }
+
+    /// Draws the whole deck in a uniformly random order
+    pub fn shuffler(&self) -> DrawSampler {
+        let size = self.deck.cards.len();
+        DrawSampler::with_method(&self.deck, size, DrawMethod::FisherYates)
+    }
+
+    /// Plays the round with `policy`, drawing cards in `order`. Panics if
+    /// the policy picks an action the rules forbid.
+    pub fn play(&self, policy: &dyn Policy, order: &[Card]) -> RoundOutcome {
+        let mut drawn = self.hand_size.min(order.len());
+        let mut hand = order[..drawn].to_vec();
+        let mut blind = self.blind.clone();
+
+        let mut outcome = RoundOutcome {
+            score: 0.0,
+            won: false,
+            hands_played: 0,
+            discards_used: 0,
+        };
+
+        while outcome.hands_played < self.hands && !hand.is_empty() {
+            let mut left = order[drawn..].to_vec();
+            left.sort();
+            let deck = Deck::new(&left);
+
+            let state = RoundState {
+                hand: &hand,
+                deck: &deck,
+                hands_left: self.hands - outcome.hands_played,
+                discards_left: self.discards - outcome.discards_used,
+                score: outcome.score,
+                target: self.target,
+                blind: &blind,
+                jokers: &self.jokers,
+            };
+
+            let action = policy.decide(&state);
+            let picked = match &action {
+                Action::Play(picked) | Action::Discard(picked) => picked,
+            };
+            let picked: BTreeSet<usize> = picked.iter().copied().collect();
+
+            assert!(
+                (1..=MAX_PLAYED_CARDS).contains(&picked.len())
+                    && picked.iter().all(|&i| i < hand.len()),
+                "{} picked invalid cards {:?}",
+                policy.name(),
+                action
+            );
+
+            let (chosen, kept): (Vec<_>, Vec<_>) = hand
+                .iter()
+                .enumerate()
+                .partition(|(i, _)| picked.contains(i));
+            let chosen: Vec<Card> =
+                chosen.into_iter().map(|(_, &c)| c).collect();
+            hand = kept.into_iter().map(|(_, &c)| c).collect();
+
+            match action {
+                Action::Play(_) => {
+                    let (poker_hand, score) =
+                        Scorer::score(&chosen, &hand, &self.jokers, &blind);
+                    if blind.allows(poker_hand)
+                        && chosen.len() >= blind.min_played()
+                    {
+                        outcome.score += score.total();
+                    }
+
+                    blind.record_play(poker_hand);
+                    outcome.hands_played += 1;
+                }
+                Action::Discard(_) => {
+                    assert!(
+                        outcome.discards_used < self.discards,
+                        "{} discarded without discards left",
+                        policy.name()
+                    );
+                    outcome.discards_used += 1;
+                }
+            }
+
+            if outcome.score >= self.target {
+                outcome.won = true;
+                break;
+            }
+
+            let refill = (self.hand_size - hand.len()).min(order.len() - drawn);
+            hand.extend_from_slice(&order[drawn..drawn + refill]);
+            drawn += refill;
+        }
+
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::PlayBest;
+
+    /// Plays the first `n` cards every turn
+    struct PlayFirst(usize);
+
+    impl Policy for PlayFirst {
+        fn name(&self) -> &'static str {
+            "Play First"
+        }
+
+        fn decide(&self, _state: &RoundState) -> Action {
+            Action::Play((0..self.0).collect())
+        }
+    }
+
+    #[test]
+    fn test_play() {
+        let order = Card::make_hand("AS AH 2C 7D KS KH 3C 9D").unwrap();
+        let round = Round::new(Deck::new(&order), 4, 1000.0).with_hands(2);
+
+        // Pair of aces, then the Seven high card drawn cards are put after:
+        // (10 + 22) x 2 + (5 + 7)
+        let outcome = round.play(&PlayFirst(2), &order);
+        assert_eq!(outcome.score, 76.0);
+        assert!(!outcome.won);
+        assert_eq!(outcome.hands_played, 2);
+
+        // Pair of aces, then pair of kings: 64 + (10 + 20) x 2
+        let outcome = round.play(&PlayBest, &order);
+        assert_eq!(outcome.score, 124.0);
+
+        // The Eye forbids the second pair, leaving a King high card
+        let round = round.with_boss_blind(BossBlind::TheEye { played: vec![] });
+        let outcome = round.play(&PlayBest, &order);
+        assert_eq!(outcome.score, 79.0);
+    }
+
+    #[test]
+    fn test_win_and_empty_deck() {
+        let order = Card::make_hand("AS AH 2C 7D").unwrap();
+        let round = Round::new(Deck::new(&order), 4, 60.0);
+
+        let outcome = round.play(&PlayFirst(2), &order);
+        assert!(outcome.won);
+        assert_eq!(outcome.hands_played, 1);
+
+        // A Seven high card, then the hand runs out
+        let round = round.with_hands(4);
+        let outcome = round.play(&PlayFirst(2), &order[2..]);
+        assert_eq!(outcome.hands_played, 1);
+        assert_eq!(outcome.score, 12.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "picked invalid cards")]
+    fn test_invalid_action() {
+        let order = Card::make_hand("AS AH 2C 7D").unwrap();
+        Round::new(Deck::new(&order), 4, 60.0).play(&PlayFirst(6), &order);
+    }
+}
@@ -0,0 +1,412 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use crate::{
+    deck::{Card, DrawSampler},
+    montecarlo::{
+        with_rng, MonteCarlo, MonteCarloAlgorithm, MonteCarloAlgorithmFactory,
+        MonteCarloConfiguration, MonteCarloOutput, MonteCarloRng, Pcg64,
+        RngKind,
+    },
+    utils::wilson_interval,
+};
+
+use super::{Policy, Round};
+
+/// Plays the same shuffled rounds with several policies, so that their
+/// results can be compared pairwise with far less noise than independent
+/// runs.
+pub struct Simulation {
+    round: Round,
+    policies: Arc<Vec<Box<dyn Policy>>>,
+    seed: u64,
+    threads: usize,
+    rng: RngKind,
+}
+
+impl Simulation {
+    pub fn new(
+        round: Round,
+        policies: Vec<Box<dyn Policy>>,
+        seed: u64,
+    ) -> Self {
+        use std::thread::available_parallelism;
+
+        Self {
+            round,
+            policies: Arc::new(policies),
+            seed,
+            threads: available_parallelism().map_or(1, |n| n.get()),
+            rng: RngKind::default(),
+        }
+    }
+
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Generator shuffling the deck, see `Discarder::with_rng`
+    pub fn with_rng(mut self, rng: RngKind) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    /// Plays `rounds` shuffles of the deck with every policy
+    pub fn run(&self, rounds: usize) -> SimulationResult {
+        let configuration = MonteCarloConfiguration {
+            threads: self.threads,
+            chunk_size: 1000,
+        };
+
+        let result = with_rng!(self.rng, R => {
+            let factory = SimulationFactory::<R> {
+                round: self.round.clone(),
+                policies: self.policies.clone(),
+                rng: PhantomData,
+            };
+
+            MonteCarlo::new(factory, configuration, self.seed)
+                .run(rounds, |_| {})
+        });
+
+        // Without any round, no thread got to size the totals
+        let mut totals = SimulationTotals::with_policies(self.policies.len());
+        totals.merge(&result.output);
+
+        SimulationResult {
+            totals,
+            rounds: result.iterations_done,
+        }
+    }
+}
+
+/// Sums over the simulated rounds, with products of every pair of policies
+/// for paired variances. Starts without policies, merging adopts them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimulationTotals {
+    wins: Vec<i64>,
+    scores: Vec<f64>,
+    /// `joint_wins[i * n + j]` counts rounds won by both policies
+    joint_wins: Vec<i64>,
+    /// `score_products[i * n + j]` sums the products of their scores
+    score_products: Vec<f64>,
+}
+
+impl SimulationTotals {
+    fn with_policies(n: usize) -> Self {
+        Self {
+            wins: vec![0; n],
+            scores: vec![0.0; n],
+            joint_wins: vec![0; n * n],
+            score_products: vec![0.0; n * n],
+        }
+    }
+
+    fn policies(&self) -> usize {
+        self.wins.len()
+    }
+
+    fn add(&mut self, outcomes: &[super::RoundOutcome]) {
+        let n = outcomes.len();
+
+        for (i, a) in outcomes.iter().enumerate() {
+            self.wins[i] += a.won as i64;
+            self.scores[i] += a.score;
+
+            for (j, b) in outcomes.iter().enumerate() {
+                self.joint_wins[i * n + j] += (a.won && b.won) as i64;
+                self.score_products[i * n + j] += a.score * b.score;
+            }
+        }
+    }
+}
+
+impl MonteCarloOutput for SimulationTotals {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn merge(&mut self, other: &Self) {
+        if self.policies() == 0 {
+            *self = other.clone();
+            return;
+        }
+
+        for (a, b) in self.wins.iter_mut().zip(&other.wins) {
+            *a += b;
+        }
+        for (a, b) in self.joint_wins.iter_mut().zip(&other.joint_wins) {
+            *a += b;
+        }
+        for (a, b) in self.scores.iter_mut().zip(&other.scores) {
+            *a += b;
+        }
+        for (a, b) in self.score_products.iter_mut().zip(&other.score_products)
+        {
+            *a += b;
+        }
+    }
+}
+
+/// A simulated mean with its confidence interval.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    pub mean: f64,
+    pub low: f64,
+    pub high: f64,
+}
+
+impl Estimate {
+    /// Normal interval of the mean of `n` samples summing to `sum`, their
+    /// squares to `sum_squared`
+    fn normal(sum: f64, sum_squared: f64, n: usize, z: f64) -> Self {
+        if n == 0 {
+            return Self::exact(0.0);
+        }
+
+        let mean = sum / n as f64;
+        if n == 1 {
+            return Self::exact(mean);
+        }
+
+        let n = n as f64;
+        let variance = ((sum_squared - sum * mean) / (n - 1.0)).max(0.0);
+        let margin = z * (variance / n).sqrt();
+
+        Self {
+            mean,
+            low: mean - margin,
+            high: mean + margin,
+        }
+    }
+
+    fn exact(mean: f64) -> Self {
+        Self {
+            mean,
+            low: mean,
+            high: mean,
+        }
+    }
+}
+
+/// Results of a simulation, policies indexed in the order they were given.
+#[derive(Debug)]
+pub struct SimulationResult {
+    pub totals: SimulationTotals,
+    pub rounds: usize,
+}
+
+impl SimulationResult {
+    /// Share of rounds won, with its Wilson score interval
+    pub fn win_rate(&self, i: usize, z: f64) -> Estimate {
+        let p = match self.rounds {
+            0 => 0.0,
+            n => self.totals.wins[i] as f64 / n as f64,
+        };
+        let (low, high) = wilson_interval(p, self.rounds, z);
+
+        Estimate { mean: p, low, high }
+    }
+
+    pub fn mean_score(&self, i: usize, z: f64) -> Estimate {
+        let scores = &self.totals.scores;
+        let squared = self.score_product(i, i);
+
+        Estimate::normal(scores[i], squared, self.rounds, z)
+    }
+
+    /// Win rate of policy `i` minus that of policy `j`, with the interval
+    /// of their paired differences over the same rounds
+    pub fn win_rate_difference(&self, i: usize, j: usize, z: f64) -> Estimate {
+        let wins = |i: usize| self.totals.wins[i] as f64;
+        let joint = |i: usize, j: usize| {
+            self.totals.joint_wins[i * self.totals.policies() + j] as f64
+        };
+
+        // Differences are 1 or -1 in rounds won by only one of them
+        let sum = wins(i) - wins(j);
+        let squared = wins(i) + wins(j) - 2.0 * joint(i, j);
+
+        Estimate::normal(sum, squared, self.rounds, z)
+    }
+
+    /// Mean score of policy `i` minus that of policy `j`, with the interval
+    /// of their paired differences over the same rounds
+    pub fn score_difference(&self, i: usize, j: usize, z: f64) -> Estimate {
+        let scores = &self.totals.scores;
+        let sum = scores[i] - scores[j];
+        let squared = self.score_product(i, i) + self.score_product(j, j)
+            - 2.0 * self.score_product(i, j);
+
+        Estimate::normal(sum, squared, self.rounds, z)
+    }
+
+    fn score_product(&self, i: usize, j: usize) -> f64 {
+        self.totals.score_products[i * self.totals.policies() + j]
+    }
+}
+
+pub struct SimulationFactory<R: MonteCarloRng = Pcg64> {
+    round: Round,
+    policies: Arc<Vec<Box<dyn Policy>>>,
+    rng: PhantomData<R>,
+}
+
+impl<R: MonteCarloRng> MonteCarloAlgorithmFactory for SimulationFactory<R> {
+    type Algorithm = SimulationAlgorithm<R>;
+    type Rng = R;
+
+    fn make(&self, seed: u64) -> SimulationAlgorithm<R> {
+        SimulationAlgorithm {
+            round: self.round.clone(),
+            policies: self.policies.clone(),
+            order: vec![Card::invalid(); self.round.deck.cards.len()],
+            outcomes: Vec::with_capacity(self.policies.len()),
+            shuffler: self.round.shuffler(),
+            rng: R::seed_from_u64(seed),
+        }
+    }
+}
+
+pub struct SimulationAlgorithm<R: MonteCarloRng = Pcg64> {
+    round: Round,
+    policies: Arc<Vec<Box<dyn Policy>>>,
+    order: Vec<Card>,
+    outcomes: Vec<super::RoundOutcome>,
+    shuffler: DrawSampler,
+    rng: R,
+}
+
+impl<R: MonteCarloRng> MonteCarloAlgorithm for SimulationAlgorithm<R> {
+    type Output = SimulationTotals;
+
+    fn sample(&mut self, output: &mut Self::Output) {
+        if output.policies() != self.policies.len() {
+            *output = SimulationTotals::with_policies(self.policies.len());
+        }
+
+        self.shuffler.sample_draw(&mut self.rng, &mut self.order);
+
+        self.outcomes.clear();
+        for policy in self.policies.iter() {
+            let outcome = self.round.play(policy.as_ref(), &self.order);
+            self.outcomes.push(outcome);
+        }
+
+        output.add(&self.outcomes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        deck::Deck,
+        strategy::{ChaseFlush, KeepScoring, PlayBest},
+    };
+
+    fn round() -> Round {
+        Round::new(Deck::make_standard(), 8, 300.0)
+    }
+
+    #[test]
+    fn test_same_policy() {
+        let policies: Vec<Box<dyn Policy>> =
+            vec![Box::new(PlayBest), Box::new(PlayBest)];
+        let result = Simulation::new(round(), policies, 43)
+            .with_threads(4)
+            .run(500);
+        assert_eq!(result.rounds, 500);
+
+        let difference = result.score_difference(0, 1, 1.96);
+        assert_eq!(difference, Estimate::exact(0.0));
+        let difference = result.win_rate_difference(1, 0, 1.96);
+        assert_eq!(difference, Estimate::exact(0.0));
+
+        let win_rate = result.win_rate(0, 1.96);
+        assert!(win_rate.low < win_rate.mean && win_rate.mean < win_rate.high);
+        assert_eq!(win_rate, result.win_rate(1, 1.96));
+    }
+
+    #[test]
+    fn test_compare() {
+        let policies: Vec<Box<dyn Policy>> = vec![
+            Box::new(PlayBest),
+            Box::new(ChaseFlush::new(4)),
+            Box::new(KeepScoring),
+        ];
+        let result = Simulation::new(round(), policies, 43)
+            .with_threads(4)
+            .run(500);
+
+        let z = 1.96;
+        for (i, j) in [(1, 0), (2, 0), (2, 1)] {
+            let difference = result.score_difference(i, j, z);
+            let expected =
+                result.mean_score(i, z).mean - result.mean_score(j, z).mean;
+            assert!((difference.mean - expected).abs() < 1e-9);
+            assert!(difference.low < difference.mean);
+
+            let difference = result.win_rate_difference(i, j, z);
+            let expected =
+                result.win_rate(i, z).mean - result.win_rate(j, z).mean;
+            assert!((difference.mean - expected).abs() < 1e-12);
+        }
+
+        // Discarding towards better hands beats never discarding
+        assert!(result.score_difference(2, 0, z).low > 0.0);
+        assert!(result.win_rate_difference(2, 0, z).low > 0.0);
+    }
+
+    #[test]
+    fn test_zero_rounds() {
+        let policies: Vec<Box<dyn Policy>> =
+            vec![Box::new(PlayBest), Box::new(KeepScoring)];
+        let result = Simulation::new(round(), policies, 43)
+            .with_threads(4)
+            .run(0);
+        assert_eq!(result.rounds, 0);
+
+        let zero = Estimate::exact(0.0);
+        assert_eq!(result.win_rate(1, 1.96), zero);
+        assert_eq!(result.mean_score(1, 1.96), zero);
+        assert_eq!(result.win_rate_difference(0, 1, 1.96), zero);
+        assert_eq!(result.score_difference(1, 0, 1.96), zero);
+    }
+
+    #[test]
+    fn test_estimates() {
+        let estimate = Estimate::normal(6.0, 14.0, 3, 2.0);
+        // Samples 1, 2 and 3 have a variance of 1
+        assert_eq!(estimate.mean, 2.0);
+        assert!((estimate.high - 2.0 - 2.0 / 3f64.sqrt()).abs() < 1e-12);
+        assert_eq!(Estimate::normal(5.0, 25.0, 1, 2.0), Estimate::exact(5.0));
+        assert_eq!(Estimate::normal(0.0, 0.0, 0, 2.0), Estimate::exact(0.0));
+    }
+
+    #[test]
+    fn test_rng_kinds() {
+        use strum::IntoEnumIterator;
+
+        let run = |rng| {
+            let policies: Vec<Box<dyn Policy>> = vec![Box::new(PlayBest)];
+            Simulation::new(round(), policies, 43)
+                .with_threads(4)
+                .with_rng(rng)
+                .run(200)
+        };
+        let reference = run(RngKind::Pcg64);
+        let mean = reference.mean_score(0, 3.29).mean;
+
+        for rng in RngKind::iter() {
+            let result = run(rng);
+            if rng != RngKind::Pcg64 {
+                assert_ne!(result.totals, reference.totals, "{:?}", rng);
+            }
+
+            // Within the interval of the reference mean score
+            let score = result.mean_score(0, 3.29);
+            assert!(score.low <= mean && mean <= score.high, "{:?}", rng);
+        }
+    }
+}
@@ -0,0 +1,677 @@
+use std::{
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+    sync::Arc,
+};
+
+use crate::{
+    deck::{
+        canonical_cards, Card, Deck, DrawSampler, Rank, Suit, SuitPermutation,
+    },
+    discarder::{Jokers, Mode},
+    montecarlo::{
+        with_rng, MonteCarlo, MonteCarloAlgorithm, MonteCarloAlgorithmFactory,
+        MonteCarloConfiguration, MonteCarloOutput, MonteCarloRng, Pcg64,
+        RngKind,
+    },
+    poker::BossBlind,
+    scoring::{Scorer, MAX_PLAYED_CARDS},
+    utils::{combinations, subsets},
+};
+
+use super::{Action, KeepScoring, Policy, Round, RoundState};
+
+/// Finds the action with the best chance of winning the round, by
+/// expectimax over every play, discard and draw until the hands run out.
+/// States are memoized up to suit symmetries of the rules.
+///
+/// Once the search would visit more than `budget` states, counting every
+/// hand drawn, it gives up and rolls every candidate action out with
+/// `KeepScoring` instead.
+pub struct Solver {
+    hand_size: usize,
+    budget: usize,
+    rollouts: usize,
+    seed: u64,
+    threads: usize,
+    rng: RngKind,
+}
+
+/// Best action found and its chance of winning the round.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solution {
+    pub action: Action,
+    pub probability: f64,
+    pub mode: Mode,
+    /// States visited in exact mode, rollouts per action in Monte Carlo
+    /// mode
+    pub iterations: usize,
+}
+
+impl Solver {
+    pub fn new(hand_size: usize, seed: u64) -> Self {
+        use std::thread::available_parallelism;
+
+        Self {
+            hand_size,
+            budget: 1000000,
+            rollouts: 1000,
+            seed,
+            threads: available_parallelism().map_or(1, |n| n.get()),
+            rng: RngKind::default(),
+        }
+    }
+
+    pub fn with_budget(mut self, budget: usize) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    pub fn with_rollouts(mut self, rollouts: usize) -> Self {
+        self.rollouts = rollouts.max(1);
+        self
+    }
+
+    /// Threads rolling candidates out in Monte Carlo mode
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Generator shuffling the deck for rollouts, see
+    /// `Discarder::with_rng`
+    pub fn with_rng(mut self, rng: RngKind) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    /// Needs a card in hand and a hand left to play
+    pub fn solve(&self, state: &RoundState) -> Solution {
+        assert!(!state.hand.is_empty() && state.hands_left > 0);
+
+        let root = Node {
+            hand: state.hand.to_vec(),
+            deck: state.deck.cards.clone(),
+            hands_left: state.hands_left,
+            discards_left: state.discards_left,
+            remaining: state.target - state.score,
+            blind: state.blind.clone(),
+        };
+
+        let mut search = Search {
+            hand_size: self.hand_size,
+            jokers: state.jokers,
+            symmetries: rule_symmetries(state.blind, state.jokers),
+            memo: HashMap::new(),
+            wins: HashMap::new(),
+            visited: 0,
+            budget: self.budget,
+        };
+
+        if let Some((action, probability)) = search.best(&root) {
+            return Solution {
+                action,
+                probability,
+                mode: Mode::Exact,
+                iterations: search.visited,
+            };
+        }
+
+        self.roll_out(&root, state.jokers)
+    }
+
+    /// Estimates the chance of winning after the best play and after every
+    /// distinct discard, all from the same shuffles of the deck
+    fn roll_out(&self, root: &Node, jokers: &Jokers) -> Solution {
+        let mut candidates: Vec<Candidate> = Vec::new();
+        let mut best_play: Option<Candidate> = None;
+
+        for (action, step) in outcomes(root, jokers) {
+            let round = (step.remaining > 0.0).then(|| {
+                Round::new(
+                    Deck::new(&root.deck),
+                    self.hand_size,
+                    step.remaining,
+                )
+                .with_hands(step.hands_left)
+                .with_discards(step.discards_left)
+                .with_boss_blind(step.blind)
+                .with_jokers(jokers.clone())
+            });
+            let candidate = Candidate {
+                action,
+                kept: step.kept,
+                round,
+            };
+
+            match candidate.action {
+                Action::Discard(_) => candidates.push(candidate),
+                Action::Play(_) => {
+                    let target = |c: &Candidate| {
+                        c.round.as_ref().map_or(0.0, |round| round.target)
+                    };
+                    if best_play
+                        .as_ref()
+                        .is_none_or(|best| target(&candidate) < target(best))
+                    {
+                        best_play = Some(candidate);
+                    }
+                }
+            }
+        }
+        candidates.extend(best_play);
+
+        let candidates = Arc::new(candidates);
+
+        let configuration = MonteCarloConfiguration {
+            threads: self.threads,
+            chunk_size: 100,
+        };
+
+        let result = with_rng!(self.rng, R => {
+            let factory = RolloutFactory::<R> {
+                deck: Deck::new(&root.deck),
+                candidates: candidates.clone(),
+                rng: PhantomData,
+            };
+
+            MonteCarlo::new(factory, configuration, self.seed)
+                .run(self.rollouts, |_| {})
+        });
+        let rollouts = result.iterations_done;
+
+        let (best, wins) = result
+            .output
+            .wins
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|&(_, &wins)| wins)
+            .map_or((candidates.len() - 1, 0), |(i, &wins)| (i, wins));
+
+        Solution {
+            action: candidates[best].action.clone(),
+            probability: wins as f64 / rollouts.max(1) as f64,
+            mode: Mode::MonteCarlo,
+            iterations: rollouts,
+        }
+    }
+}
+
+impl Policy for Solver {
+    fn name(&self) -> &'static str {
+        "Solver"
+    }
+
+    fn decide(&self, state: &RoundState) -> Action {
+        self.solve(state).action
+    }
+}
+
+/// Suit permutations that change neither which cards the blind debuffs
+/// nor what the jokers score
+fn rule_symmetries(blind: &BossBlind, jokers: &Jokers) -> Vec<SuitPermutation> {
+    if jokers.iter().any(|joker| joker.is_suit_sensitive()) {
+        return Vec::new();
+    }
+
+    let debuffed = |suit: u8| {
+        let card = Card::new(Rank { value: 2 }, Suit { value: suit });
+        blind.is_debuffed(&card)
+    };
+
+    SuitPermutation::all()
+        .filter(|permutation| {
+            (0..4).all(|suit| {
+                debuffed(suit) == debuffed(permutation.0[suit as usize])
+            })
+        })
+        .collect()
+}
+
+/// A state of the round where the next action is decided.
+#[derive(Debug, Clone)]
+pub(super) struct Node {
+    pub hand: Vec<Card>,
+    pub deck: Vec<Card>,
+    pub hands_left: usize,
+    pub discards_left: usize,
+    /// Score still missing to win
+    pub remaining: f64,
+    pub blind: BossBlind,
+}
+
+#[derive(PartialEq, Eq, Hash)]
+struct NodeKey {
+    hand: Vec<Card>,
+    deck: Vec<Card>,
+    hands_left: usize,
+    discards_left: usize,
+    remaining: u64,
+    blind: BossBlind,
+}
+
+struct Search<'a> {
+    hand_size: usize,
+    jokers: &'a Jokers,
+    symmetries: Vec<SuitPermutation>,
+    /// Winning chance of every decision state solved
+    memo: HashMap<NodeKey, f64>,
+    /// Whether a sorted hand wins right away, by score missing and blind
+    wins: HashMap<(Vec<Card>, u64, BossBlind), bool>,
+    /// States reached through a draw so far
+    visited: usize,
+    budget: usize,
+}
+
+/// A state right after an action, before the hand is refilled from the
+/// deck of the state it was taken in.
+pub(super) struct Step {
+    pub kept: Vec<Card>,
+    pub hands_left: usize,
+    pub discards_left: usize,
+    pub remaining: f64,
+    pub blind: BossBlind,
+}
+
+impl Search<'_> {
+    /// Best action of `node` and its winning chance, `None` once over
+    /// budget
+    fn best(&mut self, node: &Node) -> Option<(Action, f64)> {
+        let mut best: Option<(Action, f64)> = None;
+
+        for (action, step) in outcomes(node, self.jokers) {
+            let value = self.expect(&node.deck, step)?;
+            if best.as_ref().is_none_or(|&(_, best)| value > best) {
+                best = Some((action, value));
+            }
+            if value >= 1.0 {
+                break;
+            }
+        }
+
+        best
+    }
+
+    /// Winning chance of the decision state `node`
+    fn value(&mut self, node: &Node) -> Option<f64> {
+        if node.remaining <= 0.0
+            || self.wins_now(node.hand.clone(), node.remaining, &node.blind)
+        {
+            return Some(1.0);
+        }
+        if node.hands_left == 1 && node.discards_left == 0
+            || node.hand.is_empty()
+        {
+            return Some(0.0);
+        }
+
+        let (hand, deck) =
+            canonical_cards(&node.hand, &node.deck, &self.symmetries);
+        let key = NodeKey {
+            hand,
+            deck,
+            hands_left: node.hands_left,
+            discards_left: node.discards_left,
+            remaining: node.remaining.to_bits(),
+            blind: node.blind.clone(),
+        };
+        if let Some(&value) = self.memo.get(&key) {
+            return Some(value);
+        }
+
+        let (_, value) = self.best(node)?;
+        self.memo.insert(key, value);
+
+        Some(value)
+    }
+
+    /// Winning chance after `step`, averaged over the cards of `deck`
+    /// refilling the hand
+    fn expect(&mut self, deck: &[Card], step: Step) -> Option<f64> {
+        if step.remaining <= 0.0 {
+            return Some(1.0);
+        }
+        if step.hands_left == 0 {
+            return Some(0.0);
+        }
+
+        let draw_size = self
+            .hand_size
+            .saturating_sub(step.kept.len())
+            .min(deck.len());
+        let total = combinations(deck.len(), draw_size)
+            .filter(|&total| self.visited + total <= self.budget)?;
+        self.visited += total;
+
+        let mut draws = Vec::new();
+        Deck::new(deck).canonical_draws(draw_size, &[], |draw, weight| {
+            draws.push((draw.to_vec(), weight))
+        });
+
+        let mut expected = 0.0;
+        for (draw, weight) in draws {
+            let mut hand = step.kept.clone();
+            hand.extend_from_slice(&draw);
+
+            // Only a win matters on the last hand, whatever is left to draw
+            if step.hands_left == 1 && step.discards_left == 0 {
+                if self.wins_now(hand, step.remaining, &step.blind) {
+                    expected += weight as f64;
+                }
+                continue;
+            }
+
+            let mut left = deck.to_vec();
+            for card in &draw {
+                let i = left.iter().position(|c| c == card).unwrap();
+                left.swap_remove(i);
+            }
+
+            let child = Node {
+                hand,
+                deck: left,
+                hands_left: step.hands_left,
+                discards_left: step.discards_left,
+                remaining: step.remaining,
+                blind: step.blind.clone(),
+            };
+            expected += weight as f64 * self.value(&child)?;
+        }
+
+        Some(expected / total as f64)
+    }
+
+    /// Whether some play of `hand` wins the round right away
+    fn wins_now(
+        &mut self,
+        mut hand: Vec<Card>,
+        remaining: f64,
+        blind: &BossBlind,
+    ) -> bool {
+        hand.sort_unstable();
+        let key = (hand, remaining.to_bits(), blind.clone());
+        if let Some(&wins) = self.wins.get(&key) {
+            return wins;
+        }
+
+        let hand = &key.0;
+        let n = hand.len();
+        let mut played = Vec::with_capacity(MAX_PLAYED_CARDS);
+        let mut held = Vec::with_capacity(n);
+        let min_played = blind.min_played();
+
+        let wins = (min_played..=n.min(MAX_PLAYED_CARDS)).any(|size| {
+            subsets(n, size).any(|mask| {
+                played.clear();
+                held.clear();
+                for (i, &card) in hand.iter().enumerate() {
+                    if mask & 1 << i != 0 {
+                        played.push(card);
+                    } else {
+                        held.push(card);
+                    }
+                }
+
+                let (hand, score) =
+                    Scorer::score(&played, &held, self.jokers, blind);
+                blind.allows(hand) && score.total() >= remaining
+            })
+        });
+
+        self.wins.insert(key, wins);
+        wins
+    }
+}
+
+/// Every play then every discard of `node`, skipping those leaving the
+/// same state as an earlier one
+pub(super) fn outcomes(node: &Node, jokers: &Jokers) -> Vec<(Action, Step)> {
+    let n = node.hand.len();
+    let mut outcomes = Vec::new();
+    let mut seen = HashSet::new();
+
+    for discard in [false, true] {
+        if discard && node.discards_left == 0 {
+            break;
+        }
+
+        for size in 1..=n.min(MAX_PLAYED_CARDS) {
+            for mask in subsets(n, size) {
+                let (action, step) = outcome(node, mask, discard, jokers);
+
+                let mut kept = step.kept.clone();
+                kept.sort_unstable();
+                let state = (
+                    kept,
+                    step.hands_left,
+                    step.discards_left,
+                    step.remaining.to_bits(),
+                    step.blind.clone(),
+                );
+                if seen.insert(state) {
+                    outcomes.push((action, step));
+                }
+            }
+        }
+    }
+
+    outcomes
+}
+
+/// Playing or discarding the cards of `node` in `mask`
+pub(super) fn outcome(
+    node: &Node,
+    mask: u64,
+    discard: bool,
+    jokers: &Jokers,
+) -> (Action, Step) {
+    let n = node.hand.len();
+    let picked: Vec<usize> = (0..n).filter(|&i| mask & 1 << i != 0).collect();
+    let chosen: Vec<Card> = picked.iter().map(|&i| node.hand[i]).collect();
+    let kept: Vec<Card> = (0..n)
+        .filter(|&i| mask & 1 << i == 0)
+        .map(|i| node.hand[i])
+        .collect();
+
+    let mut step = Step {
+        kept,
+        hands_left: node.hands_left,
+        discards_left: node.discards_left,
+        remaining: node.remaining,
+        blind: node.blind.clone(),
+    };
+
+    let action = if discard {
+        step.discards_left -= 1;
+        Action::Discard(picked)
+    } else {
+        let (hand, score) =
+            Scorer::score(&chosen, &step.kept, jokers, &node.blind);
+        if node.blind.allows(hand) && picked.len() >= node.blind.min_played() {
+            step.remaining -= score.total();
+        }
+        step.blind.record_play(hand);
+        step.hands_left -= 1;
+        Action::Play(picked)
+    };
+
+    (action, step)
+}
+
+/// An action of the Monte Carlo fallback, with the cards it keeps and the
+/// round left to play after it, `None` if it already wins.
+struct Candidate {
+    action: Action,
+    kept: Vec<Card>,
+    round: Option<Round>,
+}
+
+/// Rollouts won by every candidate. Starts without candidates, merging
+/// adopts them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RolloutWins {
+    wins: Vec<i64>,
+}
+
+impl MonteCarloOutput for RolloutWins {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn merge(&mut self, other: &Self) {
+        if self.wins.is_empty() {
+            self.wins = other.wins.clone();
+            return;
+        }
+
+        for (a, b) in self.wins.iter_mut().zip(&other.wins) {
+            *a += b;
+        }
+    }
+}
+
+pub struct RolloutFactory<R: MonteCarloRng = Pcg64> {
+    deck: Deck,
+    candidates: Arc<Vec<Candidate>>,
+    rng: PhantomData<R>,
+}
+
+impl<R: MonteCarloRng> MonteCarloAlgorithmFactory for RolloutFactory<R> {
+    type Algorithm = RolloutAlgorithm<R>;
+    type Rng = R;
+
+    fn make(&self, seed: u64) -> RolloutAlgorithm<R> {
+        let size = self.deck.cards.len();
+
+        RolloutAlgorithm {
+            candidates: self.candidates.clone(),
+            shuffled: vec![Card::invalid(); size],
+            order: Vec::new(),
+            shuffler: Round::new(self.deck.clone(), 0, 0.0).shuffler(),
+            rng: R::seed_from_u64(seed),
+        }
+    }
+}
+
+pub struct RolloutAlgorithm<R: MonteCarloRng = Pcg64> {
+    candidates: Arc<Vec<Candidate>>,
+    shuffled: Vec<Card>,
+    /// Cards kept by a candidate, then the shuffled deck
+    order: Vec<Card>,
+    shuffler: DrawSampler,
+    rng: R,
+}
+
+impl<R: MonteCarloRng> MonteCarloAlgorithm for RolloutAlgorithm<R> {
+    type Output = RolloutWins;
+
+    fn sample(&mut self, output: &mut Self::Output) {
+        if output.wins.len() != self.candidates.len() {
+            output.wins = vec![0; self.candidates.len()];
+        }
+
+        self.shuffler.sample_draw(&mut self.rng, &mut self.shuffled);
+
+        for (candidate, wins) in self.candidates.iter().zip(&mut output.wins) {
+            let won = match &candidate.round {
+                None => true,
+                Some(round) => {
+                    self.order.clear();
+                    self.order.extend_from_slice(&candidate.kept);
+                    self.order.extend_from_slice(&self.shuffled);
+                    round.play(&KeepScoring, &self.order).won
+                }
+            };
+            *wins += won as i64;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solve(
+        solver: &Solver,
+        hand: &str,
+        deck: &Deck,
+        hands_left: usize,
+        target: f64,
+    ) -> Solution {
+        let hand = Card::make_hand(hand).unwrap();
+        let state = RoundState {
+            hand: &hand,
+            deck,
+            hands_left,
+            discards_left: 1,
+            score: 0.0,
+            target,
+            blind: &BossBlind::None,
+            jokers: &Jokers::default(),
+        };
+        solver.solve(&state)
+    }
+
+    #[test]
+    fn test_exact() {
+        let solver = Solver::new(5, 43).with_threads(4);
+        let hand = "AH KH QH JH 2C";
+        let deck = Deck::new(&Card::make_hand("TH 3D 4S").unwrap());
+
+        // Only the Ten of Hearts completes a hand worth 300
+        let solution = solve(&solver, hand, &deck, 1, 300.0);
+        assert_eq!(solution.action, Action::Discard(vec![4]));
+        assert_eq!(solution.probability, 1.0 / 3.0);
+        assert_eq!(solution.mode, Mode::Exact);
+
+        // The Ace high card wins outright
+        let solution = solve(&solver, hand, &deck, 1, 16.0);
+        assert_eq!(solution.action, Action::Play(vec![0]));
+        assert_eq!(solution.probability, 1.0);
+
+        // The Ace then the King high cards, whatever is drawn
+        let solution = solve(&solver, hand, &deck, 2, 31.0);
+        assert_eq!(solution.action, Action::Play(vec![0]));
+        assert_eq!(solution.probability, 1.0);
+
+        let solution = solve(&solver, hand, &deck, 1, 2000.0);
+        assert_eq!(solution.probability, 0.0);
+    }
+
+    #[test]
+    fn test_rule_symmetries() {
+        let jokers = Jokers::default();
+        assert_eq!(rule_symmetries(&BossBlind::None, &jokers).len(), 24);
+        assert_eq!(rule_symmetries(&BossBlind::TheClub, &jokers).len(), 6);
+        assert_eq!(rule_symmetries(&BossBlind::ThePlant, &jokers).len(), 24);
+
+        let blackboard = crate::jokers::joker_by_name("Blackboard").unwrap();
+        let jokers = Arc::new(vec![blackboard]);
+        assert!(rule_symmetries(&BossBlind::None, &jokers).is_empty());
+    }
+
+    #[test]
+    fn test_montecarlo_fallback() {
+        let hand = "AH KH QH JH 2C";
+        let cards = Card::make_hand(hand).unwrap();
+        let deck = Deck::new(
+            &Deck::make_standard()
+                .cards
+                .into_iter()
+                .filter(|card| !cards.contains(card))
+                .collect::<Vec<_>>(),
+        );
+
+        // A Heart or a Ten out of 47 cards, on the discard of the Two
+        let solver = Solver::new(5, 43)
+            .with_threads(4)
+            .with_budget(1000)
+            .with_rollouts(500);
+        let solution = solve(&solver, hand, &deck, 1, 300.0);
+        assert_eq!(solution.mode, Mode::MonteCarlo);
+        assert_eq!(solution.iterations, 500);
+        assert_eq!(solution.action, Action::Discard(vec![4]));
+        assert!((solution.probability - 12.0 / 47.0).abs() < 0.06);
+    }
+}
@@ -29,6 +29,42 @@ pub fn combinations(n: usize, k: usize) -> Option<usize> {
     numerator.checked_div(denominator)
 }
 
+/// Every `k` element subset of `0..n` as a bitmask, in increasing order,
+/// using Gosper's hack.
+pub fn subsets(n: usize, k: usize) -> impl Iterator<Item = u64> {
+    assert!(n < 64);
+
+    let end = 1u64 << n;
+    let first = if k <= n { (1u64 << k) - 1 } else { end };
+
+    std::iter::successors(Some(first), move |&subset| {
+        if subset == 0 {
+            return None;
+        }
+        let lowest = subset & subset.wrapping_neg();
+        let carried = subset + lowest;
+        let next = (((carried ^ subset) >> 2) / lowest) | carried;
+        (next < end).then_some(next)
+    })
+    .take_while(move |&subset| subset < end)
+}
+
+/// Wilson score interval of a probability `p` estimated from `n` samples,
+/// for the normal quantile `z`, e.g. 1.96 for 95%.
+pub fn wilson_interval(p: f64, n: usize, z: f64) -> (f64, f64) {
+    if n == 0 {
+        return (p, p);
+    }
+
+    let n = n as f64;
+    let z2 = z * z;
+    let center = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+    let margin = z / (1.0 + z2 / n)
+        * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+
+    ((center - margin).max(0.0), (center + margin).min(1.0))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -41,4 +77,28 @@ mod tests {
         assert_eq!(combinations(100, 50), None); // This will overflow
         assert_eq!(combinations(5, 6), Some(1)); // k > n
     }
+
+    #[test]
+    fn test_subsets() {
+        let all: Vec<_> = subsets(4, 2).collect();
+        assert_eq!(all, [0b0011, 0b0101, 0b0110, 0b1001, 0b1010, 0b1100]);
+        assert_eq!(subsets(8, 5).count(), 56);
+        assert!(subsets(8, 5).all(|s| s.count_ones() == 5));
+        assert_eq!(subsets(3, 0).collect::<Vec<_>>(), [0]);
+        assert_eq!(subsets(3, 3).collect::<Vec<_>>(), [0b111]);
+        assert_eq!(subsets(3, 4).count(), 0);
+    }
+
+    #[test]
+    fn test_wilson_interval() {
+        assert_eq!(wilson_interval(0.5, 0, 1.96), (0.5, 0.5));
+
+        let (low, high) = wilson_interval(0.5, 100, 1.96);
+        assert!((low - 0.4038).abs() < 1e-4);
+        assert!((high - 0.5962).abs() < 1e-4);
+
+        let (low, high) = wilson_interval(0.0, 100, 1.96);
+        assert_eq!(low, 0.0);
+        assert!(high > 0.0);
+    }
 } 
\ No newline at end of file
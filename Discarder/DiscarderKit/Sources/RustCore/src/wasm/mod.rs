@@ -0,0 +1,141 @@
+//! Browser bindings, built with `wasm-pack build --features wasm`. Tests run
+//! in a local runtime with `wasm-pack test --node --features wasm`.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    deck::{Deck, ParseCardError},
+    discarder::{run_json, Discarder, DiscarderFactory},
+    montecarlo::MonteCarlo,
+    poker::PokerHand,
+};
+use strum::IntoEnumIterator;
+
+fn parse_error(field: &str, error: ParseCardError) -> JsError {
+    JsError::new(&format!("{}: {}", field, error))
+}
+
+/// Answers a JSON analysis request in one go, see `AnalysisRequest`.
+/// Blocks until done, prefer `Analysis` for long Monte Carlo runs.
+#[wasm_bindgen(js_name = runJson)]
+pub fn run_json_wasm(request: &str) -> String {
+    run_json(request)
+}
+
+/// Monte Carlo analysis the host advances with `step`, yielding to the
+/// event loop in between.
+#[wasm_bindgen]
+pub struct Analysis {
+    montecarlo: MonteCarlo<DiscarderFactory>,
+    iterations: usize,
+}
+
+#[wasm_bindgen]
+impl Analysis {
+    /// `deck` and `hand` take card notation, `deck` also a preset name. The
+    /// deck still holds the cards in `hand`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        deck: &str,
+        hand: &str,
+        hand_size: usize,
+        seed: u64,
+        iterations: usize,
+    ) -> Result<Analysis, JsError> {
+        let deck =
+            Deck::parse(deck, seed).map_err(|e| parse_error("deck", e))?;
+        let hand = deck.parse_hand(hand).map_err(|e| parse_error("hand", e))?;
+
+        if hand.len() >= hand_size {
+            return Err(JsError::new("hand is already full, nothing to draw"));
+        }
+        if deck.cards.len() < hand_size {
+            return Err(JsError::new("not enough cards to fill the hand"));
+        }
+
+        let discarder =
+            Discarder::new(deck, hand_size, seed).with_hand_in_deck(true);
+
+        Ok(Self {
+            montecarlo: discarder.montecarlo(&hand),
+            iterations,
+        })
+    }
+
+    /// Samples up to `max_samples` more draws, returns true once done.
+    pub fn step(&mut self, max_samples: usize) -> bool {
+        let remaining = self.iterations - self.done();
+        self.montecarlo.step(remaining.min(max_samples));
+        self.is_done()
+    }
+
+    #[wasm_bindgen(js_name = isDone)]
+    pub fn is_done(&self) -> bool {
+        self.done() >= self.iterations
+    }
+
+    pub fn done(&self) -> usize {
+        self.montecarlo.chunk().iterations_done
+    }
+
+    #[wasm_bindgen(js_name = fractionCompleted)]
+    pub fn fraction_completed(&self) -> f64 {
+        if self.iterations == 0 {
+            return 1.0;
+        }
+
+        self.done() as f64 / self.iterations as f64
+    }
+
+    /// Draws containing each poker hand so far, weakest first
+    pub fn counts(&self) -> Vec<f64> {
+        let count = &self.montecarlo.chunk().output.count;
+        PokerHand::iter().map(|hand| count[hand] as f64).collect()
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn test_chunked_analysis() {
+        let mut analysis =
+            Analysis::new("standard", "AS KS QS JS", 8, 43, 5000).unwrap();
+
+        let mut steps = 0;
+        while !analysis.step(1000) {
+            steps += 1;
+        }
+
+        assert_eq!(steps, 4);
+        assert_eq!(analysis.done(), 5000);
+        assert_eq!(analysis.fraction_completed(), 1.0);
+
+        let counts = analysis.counts();
+        assert_eq!(counts[0], 5000.0);
+        assert!(counts[PokerHand::Flush as usize] > 0.0);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_invalid_notation() {
+        assert!(Analysis::new("standard", "AS KX", 8, 43, 10).is_err());
+        assert!(Analysis::new("AS", "", 8, 43, 10).is_err());
+        assert!(Analysis::new("standard", "AS AS", 8, 43, 10).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_full_hand() {
+        assert!(Analysis::new("standard", "AS KS QS", 3, 43, 10).is_err());
+        assert!(Analysis::new("standard", "AS KS QS", 2, 43, 10).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_run_json() {
+        let response =
+            run_json_wasm(r#"{"hand": "AS KH", "iterations": 2000}"#);
+        assert!(response.contains("\"mode\""));
+        assert!(response.contains("\"elapsed_seconds\""));
+    }
+}